@@ -0,0 +1,704 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms)]
+/*! Derive macros for [combu](https://crates.io/crates/combu). This crate depends on only std library (and `proc_macro`).
+
+- `#[derive(CombuArgs)]` on struct with named fields implements `combu::derive::CombuArgs` (flags and positional args declared by fields) and `combu::derive::FromContext`.
+- `#[derive(CombuSubcommands)]` on enum implements `combu::derive::CombuSubcommands` (sub commands corresponding to variants) and `combu::derive::FromContext`.
+
+Fields and variants can be configured by `#[combu(...)]` attribute. Doc comments are used as description.
+
+| key | target | meaning |
+| --- | --- | --- |
+| `name = "..."` | field, variant | name of flag, positional arg or sub command (default: kebab-case of the identifier) |
+| `short = 'c'` | field | short alias of flag |
+| `alias = "..."` | field, variant | long alias of flag, or alias of sub command |
+| `description = "..."` | field, variant | description (overrides doc comments) |
+| `env = "..."` | field | environment variable name of flag |
+| `default = "..."` | field | default value of flag, parsed as the flag's type (checked at compile time for primitive types, `IpAddr` and `SocketAddr`) |
+| `required` | field | flag is required |
+| `count` | field | flag is count flag (such as `-vvv`) |
+| `common` | field | flag is common flag |
+| `positional` | field | field is positional arg (`Option<T>` is optional and `Vec<T>` is variadic) |
+| `subcommands` | variant | inner type of tuple variant implements `CombuSubcommands` (nested sub commands) |
+
+Default value which cannot be parsed as the field's type is a compile error:
+
+```compile_fail
+#[derive(combu_derive::CombuArgs)]
+struct Build {
+    #[combu(default = "four")]
+    jobs: u32,
+}
+```
+
+combu用のderiveマクロ。std（と`proc_macro`）のみに依存する。
+*/
+
+use proc_macro::{Delimiter, TokenStream, TokenTree, token_stream::IntoIter};
+use std::iter::Peekable;
+
+/// Derives `combu::derive::CombuArgs` and `combu::derive::FromContext` for struct with named fields.
+#[proc_macro_derive(CombuArgs, attributes(combu))]
+pub fn derive_combu_args(input: TokenStream) -> TokenStream {
+	expand(input, expand_args)
+}
+
+/// Derives `combu::derive::CombuSubcommands` and `combu::derive::FromContext` for enum.
+#[proc_macro_derive(CombuSubcommands, attributes(combu))]
+pub fn derive_combu_subcommands(input: TokenStream) -> TokenStream {
+	expand(input, expand_subcommands)
+}
+
+/// Parses `input` and expands it by `f`. If an error occurs, expands into `compile_error!`.
+fn expand(input: TokenStream, f: fn(Item) -> Result<String, String>) -> TokenStream {
+	let code = match Item::parse(input).and_then(f) {
+		Ok(code) => code,
+		Err(message) => format!("::core::compile_error!({message:?});"),
+	};
+	code.parse().expect("generated code should be valid tokens")
+}
+
+/// Parsed struct or enum
+struct Item {
+	name: String,
+	body: Body,
+}
+
+/// Body of parsed struct or enum
+enum Body {
+	Struct(Vec<Field>),
+	Enum(Vec<Variant>),
+}
+
+/// Parsed named field
+struct Field {
+	ident: String,
+	ty: Type,
+	attrs: Attrs,
+}
+
+/// Parsed variant of enum
+struct Variant {
+	ident: String,
+	fields: VariantFields,
+	attrs: Attrs,
+}
+
+/// Fields of variant
+enum VariantFields {
+	Unit,
+	Named(Vec<Field>),
+	Tuple(Type),
+}
+
+/// Type of field. `code` is the type as code, and `key` is the type without whitespaces for matching.
+struct Type {
+	code: String,
+	key: String,
+}
+
+/// Parsed attributes. Values are kept as code (such as string literal).
+#[derive(Default)]
+struct Attrs {
+	docs: Vec<String>,
+	combu: Vec<(String, Option<String>)>,
+}
+
+impl Attrs {
+	/// Returns the value of `key` in `#[combu(...)]`.
+	fn value(&self, key: &str) -> Option<&str> {
+		self
+			.combu
+			.iter()
+			.find(|(k, _)| k == key)
+			.and_then(|(_, v)| v.as_deref())
+	}
+
+	/// Returns all values of `key` in `#[combu(...)]`.
+	fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+		self
+			.combu
+			.iter()
+			.filter(move |(k, _)| k == key)
+			.filter_map(|(_, v)| v.as_deref())
+	}
+
+	/// Returns true if `key` is specified in `#[combu(...)]`.
+	fn has(&self, key: &str) -> bool {
+		self.combu.iter().any(|(k, _)| k == key)
+	}
+
+	/// Checks that only `allowed` keys are specified.
+	fn check(&self, allowed: &[&str], target: &str) -> Result<(), String> {
+		match self
+			.combu
+			.iter()
+			.find(|(k, _)| !allowed.contains(&k.as_str()))
+		{
+			Some((k, _)) => Err(format!("unknown combu attribute `{k}` for {target}")),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns code of description (from `description` or doc comments).
+	fn description(&self) -> Option<String> {
+		if let Some(description) = self.value("description") {
+			return Some(description.to_owned());
+		}
+		if self.docs.is_empty() {
+			return None;
+		}
+		Some(format!(
+			"[{}].iter().map(|line| line.trim()).collect::<::std::vec::Vec<&str>>().join(\"\\n\")",
+			self.docs.join(", ")
+		))
+	}
+}
+
+type Tokens = Peekable<IntoIter>;
+
+impl Item {
+	/// Parses struct or enum.
+	fn parse(input: TokenStream) -> Result<Item, String> {
+		let mut tokens = input.into_iter().peekable();
+		parse_attrs(&mut tokens)?;
+		skip_visibility(&mut tokens);
+		let kind = expect_ident(&mut tokens)?;
+		let name = expect_ident(&mut tokens)?;
+		if is_punct(tokens.peek(), '<') {
+			return Err(format!(
+				"combu derive does not support generic type `{name}`"
+			));
+		}
+		let body = match (kind.as_str(), tokens.next()) {
+			("struct", Some(TokenTree::Group(group))) if group.delimiter() == Delimiter::Brace => {
+				Body::Struct(parse_fields(group.stream())?)
+			}
+			("struct", Some(TokenTree::Punct(punct))) if punct.as_char() == ';' => {
+				Body::Struct(Vec::new())
+			}
+			("struct", _) => {
+				return Err(format!(
+					"combu derive supports only struct with named fields, but `{name}` is not"
+				));
+			}
+			("enum", Some(TokenTree::Group(group))) if group.delimiter() == Delimiter::Brace => {
+				Body::Enum(parse_variants(group.stream())?)
+			}
+			_ => return Err(format!("combu derive does not support `{name}`")),
+		};
+		Ok(Item { name, body })
+	}
+}
+
+/// Parses attributes (`#[...]`) at the head of `tokens`.
+fn parse_attrs(tokens: &mut Tokens) -> Result<Attrs, String> {
+	let mut attrs = Attrs::default();
+	while is_punct(tokens.peek(), '#') {
+		tokens.next();
+		let Some(TokenTree::Group(group)) = tokens.next() else {
+			return Err("invalid attribute".to_owned());
+		};
+		let mut inner = group.stream().into_iter().peekable();
+		match inner.next() {
+			Some(TokenTree::Ident(ident)) if ident.to_string() == "doc" => {
+				inner.next();
+				if let Some(doc) = inner.next() {
+					attrs.docs.push(doc.to_string());
+				}
+			}
+			Some(TokenTree::Ident(ident)) if ident.to_string() == "combu" => {
+				let Some(TokenTree::Group(args)) = inner.next() else {
+					return Err("combu attribute should be like #[combu(...)]".to_owned());
+				};
+				for arg in split_by_comma(args.stream()) {
+					let mut arg = arg.into_iter();
+					let Some(TokenTree::Ident(key)) = arg.next() else {
+						return Err("invalid combu attribute".to_owned());
+					};
+					let value = match arg.next() {
+						None => None,
+						Some(TokenTree::Punct(punct)) if punct.as_char() == '=' => {
+							Some(arg.map(|token| token.to_string()).collect::<String>())
+						}
+						Some(_) => return Err(format!("invalid combu attribute `{key}`")),
+					};
+					attrs.combu.push((key.to_string(), value));
+				}
+			}
+			_ => {}
+		}
+	}
+	Ok(attrs)
+}
+
+/// Skips visibility (such as `pub` or `pub(crate)`) at the head of `tokens`.
+fn skip_visibility(tokens: &mut Tokens) {
+	if matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident.to_string() == "pub") {
+		tokens.next();
+		if matches!(tokens.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+		{
+			tokens.next();
+		}
+	}
+}
+
+/// Takes ident at the head of `tokens`.
+fn expect_ident(tokens: &mut Tokens) -> Result<String, String> {
+	match tokens.next() {
+		Some(TokenTree::Ident(ident)) => Ok(ident.to_string()),
+		Some(token) => Err(format!("expected identifier, found `{token}`")),
+		None => Err("expected identifier".to_owned()),
+	}
+}
+
+/// Returns true if `token` is punct `c`.
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+	matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == c)
+}
+
+/// Splits `stream` by commas which are not in angle brackets.
+fn split_by_comma(stream: TokenStream) -> Vec<Vec<TokenTree>> {
+	let mut parts = Vec::new();
+	let mut current = Vec::new();
+	let mut depth = 0usize;
+	for token in stream {
+		if let TokenTree::Punct(punct) = &token {
+			match punct.as_char() {
+				'<' => depth += 1,
+				'>' => depth = depth.saturating_sub(1),
+				',' if depth == 0 => {
+					parts.push(std::mem::take(&mut current));
+					continue;
+				}
+				_ => {}
+			}
+		}
+		current.push(token);
+	}
+	if !current.is_empty() {
+		parts.push(current);
+	}
+	parts
+}
+
+/// Creates `Type` from tokens.
+fn to_type(tokens: Vec<TokenTree>) -> Type {
+	let code = tokens
+		.iter()
+		.map(ToString::to_string)
+		.collect::<Vec<String>>();
+	Type {
+		key: code.concat(),
+		code: code.join(" "),
+	}
+}
+
+/// Parses named fields.
+fn parse_fields(stream: TokenStream) -> Result<Vec<Field>, String> {
+	split_by_comma(stream)
+		.into_iter()
+		.map(|field| {
+			let mut tokens = TokenStream::from_iter(field).into_iter().peekable();
+			let attrs = parse_attrs(&mut tokens)?;
+			skip_visibility(&mut tokens);
+			let ident = expect_ident(&mut tokens)?;
+			if !is_punct(tokens.next().as_ref(), ':') {
+				return Err(format!("expected `:` after field `{ident}`"));
+			}
+			Ok(Field {
+				ident,
+				ty: to_type(tokens.collect()),
+				attrs,
+			})
+		})
+		.collect()
+}
+
+/// Parses variants of enum.
+fn parse_variants(stream: TokenStream) -> Result<Vec<Variant>, String> {
+	split_by_comma(stream)
+		.into_iter()
+		.map(|variant| {
+			let mut tokens = TokenStream::from_iter(variant).into_iter().peekable();
+			let attrs = parse_attrs(&mut tokens)?;
+			let ident = expect_ident(&mut tokens)?;
+			let fields = match tokens.next() {
+				Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+					VariantFields::Named(parse_fields(group.stream())?)
+				}
+				Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+					let mut types = split_by_comma(group.stream());
+					if types.len() != 1 {
+						return Err(format!(
+							"tuple variant `{ident}` should have exactly one field"
+						));
+					}
+					VariantFields::Tuple(to_type(types.remove(0)))
+				}
+				_ => VariantFields::Unit,
+			};
+			Ok(Variant {
+				ident,
+				fields,
+				attrs,
+			})
+		})
+		.collect()
+}
+
+/// Converts `ident` (`snake_case` or `CamelCase`) into kebab-case.
+fn kebab_case(ident: &str) -> String {
+	let ident = ident.strip_prefix("r#").unwrap_or(ident);
+	let mut kebab = String::new();
+	for (i, c) in ident.chars().enumerate() {
+		if c == '_' {
+			kebab.push('-');
+		} else if c.is_uppercase() {
+			if i > 0 && !kebab.ends_with('-') {
+				kebab.push('-');
+			}
+			kebab.extend(c.to_lowercase());
+		} else {
+			kebab.push(c);
+		}
+	}
+	kebab
+}
+
+/// Returns code of name (as string literal) from `name` attribute or `ident`.
+fn name_code(attrs: &Attrs, ident: &str) -> String {
+	match attrs.value("name") {
+		Some(name) => name.to_owned(),
+		None => format!("{:?}", kebab_case(ident)),
+	}
+}
+
+/// Splits type key into the last segment of its path and generic arg (such as `Vec` and `String` of `std::vec::Vec<String>`).
+fn split_generic(key: &str) -> (&str, Option<&str>) {
+	let (head, arg) = match key.find('<') {
+		Some(i) if key.ends_with('>') => (&key[..i], Some(&key[i + 1..key.len() - 1])),
+		_ => (key, None),
+	};
+	(head.rsplit("::").next().unwrap_or(head), arg)
+}
+
+/// Returns code of `FlagType` for scalar type key. Unknown types are treated as string (they should implement `FromFlagValue` for string value).
+fn scalar_flag_type(key: &str) -> String {
+	let variant = match split_generic(key).0 {
+		"bool" => "Bool",
+		"PathBuf" => "Path",
+		"i8" | "i16" | "i32" | "isize" => "Int",
+		"u8" | "u16" | "u32" | "usize" => "UInt",
+		"i64" | "i128" => "I64",
+		"u64" | "u128" => "U64",
+		"f32" | "f64" => "Float",
+		"char" => "Char",
+		"Duration" => "Duration",
+		"IpAddr" => "IpAddr",
+		"SocketAddr" => "SocketAddr",
+		_ => "String",
+	};
+	format!("::combu::FlagType::{variant}")
+}
+
+/// Returns code of `FlagType` for field type key.
+fn flag_type(key: &str) -> String {
+	match split_generic(key) {
+		("Option", Some(inner)) => flag_type(inner),
+		("Vec", Some(inner)) => format!("::combu::FlagType::list({})", scalar_flag_type(inner)),
+		_ => scalar_flag_type(key),
+	}
+}
+
+/// Checks that `default` (code of string literal) can be parsed as the flag's type of `field`.
+/// Defaults given by other expressions and defaults of types whose format is not known here (such as `Duration` or types implementing `FromFlagValue`) are not checked.
+/// デフォルト値がフィールドの型として解釈できるかチェックする
+fn check_default(field: &Field, default: &str) -> Result<(), String> {
+	let Some(value) = default
+		.strip_prefix('"')
+		.and_then(|d| d.strip_suffix('"'))
+		.filter(|d| !d.contains('\\'))
+	else {
+		return Ok(());
+	};
+	let count = field.attrs.has("count");
+	let scalar = match split_generic(&field.ty.key) {
+		("Option" | "Vec", Some(inner)) => split_generic(inner).0,
+		(head, _) => head,
+	};
+	let valid = match scalar {
+		_ if count => false,
+		"bool" => matches!(
+			value.to_ascii_lowercase().as_str(),
+			"true" | "yes" | "on" | "1" | "false" | "no" | "off" | "0"
+		),
+		"i8" => value.parse::<i8>().is_ok(),
+		"i16" => value.parse::<i16>().is_ok(),
+		"i32" => value.parse::<i32>().is_ok(),
+		"isize" => value.parse::<isize>().is_ok(),
+		"u8" => value.parse::<u8>().is_ok(),
+		"u16" => value.parse::<u16>().is_ok(),
+		"u32" => value.parse::<u32>().is_ok(),
+		"usize" => value.parse::<usize>().is_ok(),
+		"i64" | "i128" => value.parse::<i64>().is_ok(),
+		"u64" | "u128" => value.parse::<u64>().is_ok(),
+		"f32" | "f64" => value.parse::<f64>().is_ok(),
+		"char" => value.chars().count() == 1,
+		"IpAddr" => value.parse::<std::net::IpAddr>().is_ok(),
+		"SocketAddr" => value.parse::<std::net::SocketAddr>().is_ok(),
+		_ => true,
+	};
+	if valid {
+		Ok(())
+	} else if count {
+		Err(format!(
+			"count flag `{}` cannot have default value",
+			field.ident
+		))
+	} else {
+		Err(format!(
+			"default value {default} of field `{}` cannot be parsed as `{scalar}`",
+			field.ident
+		))
+	}
+}
+
+const FIELD_ATTRS: [&str; 10] = [
+	"name",
+	"short",
+	"alias",
+	"description",
+	"env",
+	"default",
+	"required",
+	"count",
+	"common",
+	"positional",
+];
+
+/// Code generated from fields
+#[derive(Default)]
+struct FieldsCode {
+	local_flags: Vec<String>,
+	common_flags: Vec<String>,
+	positionals: Vec<String>,
+	/// Field initializers such as `name: value,`
+	inits: Vec<String>,
+}
+
+impl FieldsCode {
+	/// Generates code from fields.
+	fn new(fields: &[Field]) -> Result<FieldsCode, String> {
+		let mut code = FieldsCode::default();
+		for field in fields {
+			field
+				.attrs
+				.check(&FIELD_ATTRS, &format!("field `{}`", field.ident))?;
+			let name = name_code(&field.attrs, &field.ident);
+			let ty = &field.ty;
+			let mut expr;
+			if field.attrs.has("positional") {
+				let (head, inner) = split_generic(&ty.key);
+				expr = match (head, inner) {
+					("Vec", Some(inner)) => format!(
+						"::combu::Positional::new({name}, {}).variadic(true).required(false)",
+						scalar_flag_type(inner)
+					),
+					("Option", Some(inner)) => format!(
+						"::combu::Positional::new({name}, {}).required(false)",
+						flag_type(inner)
+					),
+					_ => format!("::combu::Positional::new({name}, {})", flag_type(&ty.key)),
+				};
+				if let Some(description) = field.attrs.description() {
+					expr += &format!(".description({description})");
+				}
+				code.positionals.push(expr);
+				code.inits.push(format!(
+					"{}: ctx.get_positional::<{}>({name})?,",
+					field.ident, ty.code
+				));
+				continue;
+			}
+			let flag_type = if field.attrs.has("count") {
+				"::combu::FlagType::Count".to_owned()
+			} else {
+				flag_type(&ty.key)
+			};
+			expr = format!("::combu::Flag::new({name}, {flag_type}, \"\")");
+			for short in field.attrs.values("short") {
+				expr += &format!(".short_alias({short})");
+			}
+			for alias in field.attrs.values("alias") {
+				expr += &format!(".alias({alias})");
+			}
+			if let Some(description) = field.attrs.description() {
+				expr += &format!(".description({description})");
+			}
+			if let Some(env) = field.attrs.value("env") {
+				expr += &format!(".env({env})");
+			}
+			if field.attrs.has("required") {
+				expr += ".required(true)";
+			}
+			if let Some(default) = field.attrs.value("default") {
+				check_default(field, default)?;
+				expr = format!(
+					"{{ let flag = {expr}; let value = flag.derive_flag_value_from_string(::std::string::String::from({default})); flag.default_value(value) }}"
+				);
+			} else if split_generic(&ty.key).0 == "Option" {
+				// 未指定の場合にNoneとなるように、型ごとのデフォルト値を消す
+				expr = format!(
+					"{{ let mut flag = {expr}; flag.default_value = ::combu::FlagValue::None; flag }}"
+				);
+			}
+			if field.attrs.has("common") {
+				code.common_flags.push(expr);
+			} else {
+				code.local_flags.push(expr);
+			}
+			code.inits.push(format!(
+				"{}: ctx.get::<{}>({name}, cmd)?,",
+				field.ident, ty.code
+			));
+		}
+		Ok(code)
+	}
+
+	/// Returns code which adds flags and positional args to `cmd`.
+	fn augment(&self) -> String {
+		let mut code = String::new();
+		for positional in &self.positionals {
+			code += &format!(".positional({positional})");
+		}
+		for flag in &self.common_flags {
+			code += &format!(".common_flag({flag})");
+		}
+		for flag in &self.local_flags {
+			code += &format!(".local_flag({flag})");
+		}
+		code
+	}
+}
+
+/// Expands `#[derive(CombuArgs)]`.
+fn expand_args(item: Item) -> Result<String, String> {
+	let Body::Struct(fields) = &item.body else {
+		return Err(format!(
+			"CombuArgs can be derived only for struct, but `{}` is enum",
+			item.name
+		));
+	};
+	let code = FieldsCode::new(fields)?;
+	let name = &item.name;
+	Ok(format!(
+		"impl ::combu::derive::FromContext for {name} {{
+			#[allow(unused_variables)]
+			fn from_context(ctx: &::combu::Context, cmd: &::combu::Command) -> ::std::result::Result<Self, ::combu::FlagAccessError> {{
+				::std::result::Result::Ok(Self {{ {inits} }})
+			}}
+		}}
+		impl ::combu::derive::CombuArgs for {name} {{
+			fn local_flags() -> ::std::vec::Vec<::combu::Flag> {{
+				::std::vec![{local_flags}]
+			}}
+			fn common_flags() -> ::std::vec::Vec<::combu::Flag> {{
+				::std::vec![{common_flags}]
+			}}
+			fn positionals() -> ::std::vec::Vec<::combu::Positional> {{
+				::std::vec![{positionals}]
+			}}
+		}}",
+		inits = code.inits.concat(),
+		local_flags = code.local_flags.join(", "),
+		common_flags = code.common_flags.join(", "),
+		positionals = code.positionals.join(", "),
+	))
+}
+
+/// Expands `#[derive(CombuSubcommands)]`.
+fn expand_subcommands(item: Item) -> Result<String, String> {
+	let Body::Enum(variants) = &item.body else {
+		return Err(format!(
+			"CombuSubcommands can be derived only for enum, but `{}` is struct",
+			item.name
+		));
+	};
+	let mut commands = Vec::new();
+	let mut arms = Vec::new();
+	for variant in variants {
+		let ident = &variant.ident;
+		variant.attrs.check(
+			&["name", "alias", "description", "subcommands"],
+			&format!("variant `{ident}`"),
+		)?;
+		let name = name_code(&variant.attrs, ident);
+		let mut command = format!("::combu::Command::with_name({name}).action(action)");
+		for alias in variant.attrs.values("alias") {
+			command += &format!(".alias({alias})");
+		}
+		if let Some(description) = variant.attrs.description() {
+			command += &format!(".description({description})");
+		}
+		let value = match &variant.fields {
+			VariantFields::Unit => format!("Self::{ident}"),
+			VariantFields::Named(fields) => {
+				let code = FieldsCode::new(fields)?;
+				command += &code.augment();
+				format!("Self::{ident} {{ {} }}", code.inits.concat())
+			}
+			VariantFields::Tuple(ty) if variant.attrs.has("subcommands") => {
+				command = format!(
+					"{command}.sub_commands(<{} as ::combu::derive::CombuSubcommands>::sub_commands(action))",
+					ty.code
+				);
+				format!(
+					"Self::{ident}(<{} as ::combu::derive::CombuSubcommands>::from_route(rest, ctx, cmd)?)",
+					ty.code
+				)
+			}
+			VariantFields::Tuple(ty) => {
+				command = format!(
+					"<{} as ::combu::derive::CombuArgs>::augment({command})",
+					ty.code
+				);
+				format!(
+					"Self::{ident}(<{} as ::combu::derive::FromContext>::from_context(ctx, cmd)?)",
+					ty.code
+				)
+			}
+		};
+		commands.push(command);
+		arms.push(format!(
+			"{name} => return ::std::result::Result::Ok({value}),"
+		));
+	}
+	let name = &item.name;
+	Ok(format!(
+		"impl ::combu::derive::CombuSubcommands for {name} {{
+			fn sub_commands(action: ::combu::Action) -> ::std::vec::Vec<::combu::Command> {{
+				::std::vec![{commands}]
+			}}
+			#[allow(unused_variables)]
+			fn from_route(route: &[::std::string::String], ctx: &::combu::Context, cmd: &::combu::Command) -> ::std::result::Result<Self, ::combu::FlagAccessError> {{
+				let mut route = route;
+				while let ::std::option::Option::Some((head, rest)) = route.split_first() {{
+					match head.as_str() {{
+						{arms}
+						_ => route = rest,
+					}}
+				}}
+				::std::result::Result::Err(::combu::FlagAccessError::MissingSubCommand(cmd.name.clone()))
+			}}
+		}}
+		impl ::combu::derive::FromContext for {name} {{
+			fn from_context(ctx: &::combu::Context, cmd: &::combu::Command) -> ::std::result::Result<Self, ::combu::FlagAccessError> {{
+				<Self as ::combu::derive::CombuSubcommands>::from_route(&::combu::derive::route_of(ctx, cmd), ctx, cmd)
+			}}
+		}}",
+		commands = commands.join(", "),
+		arms = arms.concat(),
+	))
+}
@@ -0,0 +1,217 @@
+use combu::derive::{CombuArgs as _, CombuSubcommands as _, FromContext};
+use combu::vector::flag::FlagSearch;
+use combu::{
+	ActionError, ActionResult, Command, Context, Done, FlagAccessError, FlagType, FlagValue,
+	Positional,
+};
+use combu_derive::{CombuArgs, CombuSubcommands};
+use std::{cell::RefCell, path::PathBuf};
+
+#[derive(CombuArgs, Debug, PartialEq)]
+struct Global {
+	/// Enable verbose output.
+	#[combu(short = 'v', count, common)]
+	verbose: u64,
+	#[combu(name = "config-file", alias = "conf", common)]
+	config: Option<PathBuf>,
+}
+
+// 環境変数の値に結果が左右されないよう、パースには使わない
+#[derive(CombuArgs)]
+struct WithEnv {
+	#[combu(env = "COMBU_DERIVE_TEST_TOKEN")]
+	#[allow(dead_code)]
+	token: Option<String>,
+}
+
+#[derive(CombuArgs, Debug, PartialEq)]
+struct Build {
+	/// Target to build.
+	#[combu(positional)]
+	target: String,
+	#[combu(positional)]
+	extra: Vec<String>,
+	#[combu(short = 'j', default = "4")]
+	jobs: u32,
+	#[combu(description = "features to enable")]
+	features: Vec<String>,
+	r#release: bool,
+}
+
+#[derive(CombuSubcommands, Debug, PartialEq)]
+enum Remote {
+	Add {
+		#[combu(positional)]
+		name: String,
+		#[combu(positional)]
+		url: Option<String>,
+	},
+	#[combu(alias = "rm")]
+	Remove,
+}
+
+#[derive(CombuSubcommands, Debug, PartialEq)]
+enum Cli {
+	/// Build the project.
+	Build(Build),
+	#[combu(subcommands)]
+	Remote(Remote),
+	#[combu(name = "stat")]
+	ShowStatus {
+		#[combu(required)]
+		format: String,
+	},
+}
+
+thread_local! {
+	static PARSED: RefCell<Option<Result<(Global, Cli), FlagAccessError>>> = const { RefCell::new(None) };
+}
+
+fn act(cmd: Command, c: Context) -> Result<ActionResult, ActionError> {
+	let parsed = Global::from_context(&c, &cmd)
+		.and_then(|global| Cli::from_context(&c, &cmd).map(|cli| (global, cli)));
+	PARSED.with(|p| *p.borrow_mut() = Some(parsed));
+	Ok(Done)
+}
+
+fn root() -> Command {
+	Global::augment(
+		Command::with_name("app")
+			.action(act)
+			.sub_commands(Cli::sub_commands(act)),
+	)
+}
+
+fn parse(args: &[&str]) -> Option<Result<(Global, Cli), FlagAccessError>> {
+	let args = ["exe"]
+		.iter()
+		.chain(args)
+		.map(|arg| arg.to_string())
+		.collect();
+	let _ = root().run_from_args(args);
+	PARSED.with(|p| p.borrow_mut().take())
+}
+
+#[test]
+fn generated_flags_test() {
+	let locals = Build::local_flags();
+	assert_eq!(
+		locals.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+		vec!["jobs", "features", "release"]
+	);
+	assert_eq!(locals[0].short_alias.inner(), &Some(vec!['j']));
+	assert_eq!(locals[0].default_value, FlagValue::UInt(4));
+	assert_eq!(locals[1].flag_type, FlagType::list(FlagType::String));
+	assert_eq!(locals[1].description, "features to enable");
+	assert_eq!(locals[2].flag_type, FlagType::Bool);
+
+	let commons = Global::common_flags();
+	assert_eq!(commons.len(), 2);
+	assert!(Global::local_flags().is_empty());
+	assert_eq!(commons[0].flag_type, FlagType::Count);
+	assert_eq!(commons[0].description, "Enable verbose output.");
+	let config = &commons[1];
+	assert_eq!(config.name, "config-file");
+	assert_eq!(config.long_alias.inner(), &Some(vec!["conf".to_owned()]));
+	assert_eq!(config.flag_type, FlagType::Path);
+	assert_eq!(config.env, None);
+	assert_eq!(
+		WithEnv::local_flags()[0].env.as_deref(),
+		Some("COMBU_DERIVE_TEST_TOKEN")
+	);
+
+	assert_eq!(
+		Build::positionals(),
+		vec![
+			Positional::new("target", FlagType::String).description("Target to build."),
+			Positional::new("extra", FlagType::String)
+				.variadic(true)
+				.required(false),
+		]
+	);
+
+	let subs = Cli::sub_commands(act);
+	assert_eq!(
+		subs.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+		vec!["build", "remote", "stat"]
+	);
+	assert_eq!(subs[0].description, Some("Build the project.".to_owned()));
+	assert_eq!(subs[1].sub.len(), 2);
+	assert_eq!(
+		subs[1].sub.get(1).unwrap().alias.inner(),
+		&Some(vec!["rm".to_owned()])
+	);
+	assert!(subs[2].l_flags.find("format").unwrap().required);
+}
+
+#[test]
+fn from_context_test() {
+	assert_eq!(
+		parse(&[
+			"-vv",
+			"build",
+			"lib",
+			"a",
+			"b",
+			"--features",
+			"x",
+			"--release"
+		]),
+		Some(Ok((
+			Global {
+				verbose: 2,
+				config: None
+			},
+			Cli::Build(Build {
+				target: "lib".to_owned(),
+				extra: vec!["a".to_owned(), "b".to_owned()],
+				jobs: 4,
+				features: vec!["x".to_owned()],
+				release: true,
+			})
+		)))
+	);
+	assert_eq!(
+		parse(&["--conf", "c.toml", "remote", "add", "origin"]),
+		Some(Ok((
+			Global {
+				verbose: 0,
+				config: Some(PathBuf::from("c.toml"))
+			},
+			Cli::Remote(Remote::Add {
+				name: "origin".to_owned(),
+				url: None
+			})
+		)))
+	);
+	assert_eq!(
+		parse(&["remote", "rm"]),
+		Some(Ok((
+			Global {
+				verbose: 0,
+				config: None
+			},
+			Cli::Remote(Remote::Remove)
+		)))
+	);
+	assert_eq!(
+		parse(&["stat", "--format", "json", "-v"]),
+		Some(Ok((
+			Global {
+				verbose: 1,
+				config: None
+			},
+			Cli::ShowStatus {
+				format: "json".to_owned()
+			}
+		)))
+	);
+	assert_eq!(
+		parse(&[]),
+		Some(Err(FlagAccessError::MissingSubCommand("app".to_owned())))
+	);
+	assert_eq!(
+		parse(&["remote"]),
+		Some(Err(FlagAccessError::MissingSubCommand("remote".to_owned())))
+	);
+}
@@ -0,0 +1,74 @@
+use combu::command::presets::func::help_tablize_with_alias_dedup;
+use combu::derive::{CombuArgs as _, CombuSubcommands as _, FromContext};
+use combu::{
+	CombuArgs, CombuSubcommands, Command, Context, action_result, check_error, check_help, done,
+};
+use std::env;
+
+/// Global options
+#[derive(CombuArgs)]
+struct Global {
+	/// show help
+	#[combu(short = 'h', common)]
+	help: bool,
+	/// verbosity (-vvv)
+	#[combu(short = 'v', count, common)]
+	verbose: u64,
+}
+
+/// Sub commands
+#[derive(CombuSubcommands)]
+enum Todo {
+	/// add a task
+	Add {
+		/// title of the task
+		#[combu(positional)]
+		title: String,
+		/// priority of the task
+		#[combu(short = 'p', default = "3")]
+		priority: u32,
+	},
+	/// list tasks
+	List {
+		/// show done tasks too
+		#[combu(short = 'a')]
+		all: bool,
+	},
+	/// mark tasks done
+	Done {
+		/// ids of tasks
+		#[combu(positional)]
+		ids: Vec<u64>,
+	},
+}
+
+fn main() {
+	let root = Command::with_name(env!("CARGO_PKG_NAME"))
+		.description("derive example")
+		.action(act)
+		.sub_commands(Todo::sub_commands(act));
+	let _r = Global::augment(root).run_from_args(env::args().collect());
+}
+
+fn act(cmd: Command, c: Context) -> action_result!() {
+	check_error!(cmd, c);
+	check_help!(cmd, c, help_tablize_with_alias_dedup);
+	let Ok(global) = Global::from_context(&c, &cmd) else {
+		return done!();
+	};
+	if global.help {
+		return done!();
+	}
+	match Todo::from_context(&c, &cmd) {
+		Ok(Todo::Add { title, priority }) => {
+			println!("add {title} (priority: {priority})");
+		}
+		Ok(Todo::List { all }) => println!("list (all: {all})"),
+		Ok(Todo::Done { ids }) => println!("done {ids:?}"),
+		Err(err) => println!("{err}"),
+	}
+	if global.verbose > 0 {
+		println!("verbosity: {}", global.verbose);
+	}
+	done!()
+}
@@ -1,4 +1,6 @@
-use combu::{ActionError, ActionResult, Command, Context, Flag, FlagValue, command::presets, done};
+use combu::{
+	ActionError, ActionResult, Command, Context, Flag, FlagValue, Positional, command::presets, done,
+};
 /*
 multi command example.
 We can run as follows:
@@ -72,6 +74,11 @@ fn add_command() -> Command {
 		.alias("a")
 		.action(add_action)
 		.local_flag(Flag::new_bool("detail").short_alias('d'))
+		.positional(
+			Positional::new_float("numbers")
+				.variadic(true)
+				.description("numbers to add"),
+		)
 }
 
 fn add_action(cmd: Command, c: Context) -> Result<ActionResult, ActionError> {
@@ -79,18 +86,18 @@ fn add_action(cmd: Command, c: Context) -> Result<ActionResult, ActionError> {
 		return call_help(&c, &cmd);
 	}
 	let f = |(str, sum), num: f64| (format!("{str} {num} +"), sum + num);
+	let numbers: Vec<f64> = c
+		.get_positional_value_of("numbers")
+		.map(FlagValue::into_vec)
+		.unwrap_or_default()
+		.iter()
+		.filter_map(FlagValue::get_float)
+		.collect();
 	let (mut str, sum): (String, f64) =
 		if c.get_flag_value_of("reverse", &cmd) == Some(FlagValue::Bool(true)) {
-			c.args
-				.iter()
-				.rev()
-				.filter_map(|arg| arg.parse().ok())
-				.fold((String::new(), 0.0), f)
+			numbers.into_iter().rev().fold((String::new(), 0.0), f)
 		} else {
-			c.args
-				.iter()
-				.filter_map(|arg| arg.parse().ok())
-				.fold((String::new(), 0.0), f)
+			numbers.into_iter().fold((String::new(), 0.0), f)
 		};
 	str.pop();
 	str.pop();
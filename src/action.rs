@@ -53,6 +53,10 @@ pub enum ActionErrorKind {
 	Custom,
 	/// Shows that no action is registered to specidied command.
 	NoActionRegistered,
+	/// Shows that action is not run because of parse errors (in strict mode). Parse errors are stored in context's `error_info_list`.
+	ParseFailed,
+	/// Shows that response file (`@path` arg) cannot be expanded. `ResponseFileError` is stored as related error.
+	InvalidResponseFile,
 	/// Shows None.
 	None,
 }
@@ -101,6 +105,9 @@ impl fmt::Display for ActionError {
 			ActionErrorKind::NoActionRegistered => {
 				write!(f, "{} does not have its own action.", self.value)
 			}
+			ActionErrorKind::ParseFailed | ActionErrorKind::InvalidResponseFile => {
+				write!(f, "{}", self.value)
+			}
 			ActionErrorKind::None => write!(f, "no action error"),
 		}
 	}
@@ -112,7 +119,10 @@ impl Error for ActionError {}
 pub mod bundle {
 	use std::collections::VecDeque;
 
-	use crate::{Command, Context, FlagValue};
+	use crate::{
+		Command, Context, FlagValue, Vector,
+		flag::{FlagAccessError, FromFlagValue},
+	};
 
 	/// New-type for help processing in action. Inner is simple tuple - `(Context,Command)`
 	pub struct Bundle(pub Context, pub Command);
@@ -199,6 +209,18 @@ pub mod bundle {
 			self.0.get_local_flag_value_of(flag_name, &self.1)
 		}
 
+		/// Gets value of the flag matches `flag_name` as `T`. See `Context::get`.
+		/// フラグ値を型`T`として取得する
+		pub fn get<T: FromFlagValue>(&self, flag_name: &str) -> Result<T, FlagAccessError> {
+			self.0.get(flag_name, &self.1)
+		}
+
+		/// Gets values of the flag matches `flag_name` as Vec. Values of list flag specified repeatedly are returned in order.
+		/// フラグ値をVecとして取得する。繰り返し指定されたリストフラグの場合は指定順に全ての値を返す。
+		pub fn get_flag_values_of(&self, flag_name: &str) -> Option<Vec<FlagValue>> {
+			self.0.get_flag_values_of(flag_name, &self.1)
+		}
+
 		/// Gets `FlagValue`'s clone of the inputted flag matches `flag_name` from context.
 		/// contextからユーザから指定された場合のフラグ値のcloneを取得する。ユーザから入力されていない場合はNoneを返す。
 		pub fn get_inputted_flag_value_of(&self, flag_name: &str) -> Option<FlagValue> {
@@ -243,5 +265,10 @@ pub mod bundle {
 		pub fn arg(&self, index: usize) -> Option<&String> {
 			self.0.args.get(index)
 		}
+
+		/// Returns reference of args after end-of-options marker (default: --)
+		pub fn trailing_args(&self) -> &Vector<String> {
+			&self.0.trailing_args
+		}
 	}
 }
@@ -1,12 +1,24 @@
 use crate::{
-	Action, Context, Flag, FlagValue, Parser, Vector,
-	action::{ActionError, ActionErrorKind::NoActionRegistered, ActionResult},
+	Action, Config, Context, Flag, FlagGroup, FlagValue, Parser, Positional, Vector,
+	action::{
+		ActionError,
+		ActionErrorKind::{self, Custom, InvalidResponseFile, NoActionRegistered, ParseFailed},
+		ActionResult,
+	},
 	done,
-	parser::MiddleArg,
+	os_args::{self, NonUtf8Args},
+	parser::{ErrorInfo, MiddleArg, ParseError, preset::gen_error_description},
+	response_file,
 };
 
 use core::mem::swap;
-use std::{collections::VecDeque, fmt::Debug};
+use std::{
+	collections::VecDeque,
+	error::Error,
+	ffi::OsString,
+	fmt::{self, Debug},
+	path::PathBuf,
+};
 
 ///The struct for command information store and command execution
 ///This can be root and edge
@@ -37,6 +49,39 @@ pub struct Command {
 	pub version: String,
 	///container of sub-command
 	pub sub: Vector<Command>,
+	/// If this is true, the first non-flag arg and args after it are not parsed as flags and are stored in args as they are (like `docker run`).
+	/// trueの場合、最初の非フラグ引数以降の引数はフラグとしてパースせずにそのままargsに格納する
+	pub trailing_var_arg: bool,
+	/// Parser for this command. If this is None, parser inherited from parent command (or default parser if not inherited) is used.
+	/// コマンドの引数パーサー。Noneの場合、親コマンドのパーサー（親コマンドが持たない場合はデフォルトのパーサー）を使用する
+	pub parser: Option<Parser>,
+	/// Strictness for parse errors. If this is None, strictness inherited from parent command (or `Strictness::Lenient` if not inherited) is used.
+	/// パースエラーに対する厳格さ。Noneの場合、親コマンドの設定（親コマンドが持たない場合は`Strictness::Lenient`）を使用する
+	pub strictness: Option<Strictness>,
+	/// Declared positional args of command. Args left after parsing flags are validated and converted by these.
+	/// 宣言された位置引数。フラグのパース後に残った引数はこれに従って検証・変換される
+	pub positionals: Vector<Positional>,
+	/// Groups of flags constrained how many of them can be inputted. They are checked after parsing.
+	/// 指定できる数に制約があるフラグのグループ。パース後にチェックされる
+	pub groups: Vector<FlagGroup>,
+	/// Prefix for deriving environment variable names of flags which do not have own `env`. If this is None, prefix inherited from parent command is used.
+	/// フラグの環境変数名を導出するためのプレフィックス。Noneの場合、親コマンドの設定を使用する
+	pub env_prefix: Option<String>,
+	/// Function to look up environment variable. If this is None, lookup function inherited from parent command (or `std::env::var`) is used.
+	/// 環境変数の参照に使う関数。Noneの場合、親コマンドの設定（親コマンドが持たない場合は`std::env::var`）を使用する
+	pub env_lookup: Option<EnvLookup>,
+	/// Config for filling values of flags which are not inputted (and not set by environment variables). If this is None, config inherited from parent command is used.
+	/// 入力されず環境変数でも指定されなかったフラグの値を補う設定。Noneの場合、親コマンドの設定を使用する
+	pub config: Option<Config>,
+	/// App name for discovering config file (`$XDG_CONFIG_HOME/<config_name>/config`). If this is None, name inherited from parent command is used.
+	/// 設定ファイルを探索するためのアプリ名。Noneの場合、親コマンドの設定を使用する
+	pub config_name: Option<String>,
+	/// If this is true, `@path` args are expanded into args read from the file before dispatch (only for root command run from args).
+	/// trueの場合、ディスパッチ前に`@path`形式の引数をファイルの内容で展開する（引数から実行するルートコマンドのみ有効）
+	pub response_files: bool,
+	/// Originals of args which are not valid UTF-8 (set by `run_from_os_args`). They are moved to the command run, and then to its context.
+	/// UTF-8として不正な引数の元の値（`run_from_os_args`で設定され、実行されるコマンドを経てコンテキストへ移される）
+	non_utf8_args: NonUtf8Args,
 }
 
 /// Helper inner macro
@@ -47,14 +92,16 @@ macro_rules! run_result{
 }
 /// Helper inner macro
 macro_rules! no_registered_error {
-	($command:expr,$context:expr) => {
+	($command:expr,$context:expr) => {{
+		let mut command = $command;
+		let context = command.settle_non_utf8_args($context);
 		Err(ActionError::without_related_error(
 			"no action is registered.".into(),
 			NoActionRegistered,
-			$command,
-			$context,
+			command,
+			context,
 		))
-	};
+	}};
 }
 /// Helper inner macro
 macro_rules! check_sub {
@@ -66,6 +113,32 @@ macro_rules! check_sub {
 	};
 }
 /// Helper inner macro
+macro_rules! inherit_settings {
+	($sub:expr, $self:expr) => {
+		if $sub.parser.is_none() {
+			$sub.parser = $self.parser.clone();
+		}
+		if $sub.strictness.is_none() {
+			$sub.strictness = $self.strictness;
+		}
+		if $sub.env_prefix.is_none() {
+			$sub.env_prefix = $self.env_prefix.clone();
+		}
+		if $sub.env_lookup.is_none() {
+			$sub.env_lookup = $self.env_lookup;
+		}
+		if $sub.config.is_none() {
+			$sub.config = $self.config.clone();
+		}
+		if $sub.config_name.is_none() {
+			$sub.config_name = $self.config_name.clone();
+		}
+		if $sub.non_utf8_args.is_empty() {
+			$sub.non_utf8_args = std::mem::take(&mut $self.non_utf8_args);
+		}
+	};
+}
+/// Helper inner macro
 macro_rules! check_sub_field {
 	($sub: expr, $self:expr, $field: ident) => {
 		if $sub.$field.is_empty() {
@@ -84,6 +157,138 @@ macro_rules! check_sub_field {
 	};
 }
 
+/// `Strictness` shows how command handles parse errors (such as unknown flags) before its action runs.
+/// アクション実行前のパースエラー（未知のフラグなど）の扱い
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+	/// Parse errors are stored in context and action runs anyway.
+	#[default]
+	Lenient,
+	/// If there are parse errors, action is not run and `ActionError` is returned.
+	Strict,
+	/// Same as `Strict`, and also prints error descriptions and help of the command.
+	StrictWithHelp,
+}
+
+/// `ParseOutcome` shows how args are routed and parsed by `Command::parse`.
+/// `Command::parse`による引数の割り当てとパースの結果
+#[derive(Debug, Clone)]
+pub struct ParseOutcome {
+	/// Names of commands from root to parent of the routed command (same as `Context::routes`)
+	pub routes: Vec<String>,
+	/// Names of commands from root to the routed command (whose action would be run)
+	pub path: Vec<String>,
+	/// Values of local flags
+	pub local_flags_values: Vec<(String, FlagValue)>,
+	/// Values of common flags
+	pub common_flags_values: Vec<(String, FlagValue)>,
+	/// Non-flag args
+	pub args: VecDeque<String>,
+	/// Values of declared positional args
+	pub positional_values: Vec<(String, FlagValue)>,
+	/// Args after end-of-options marker
+	pub trailing_args: Vec<String>,
+	/// Parse errors
+	pub error_info_list: Vec<ErrorInfo>,
+	/// The routed command. It can be used with `context` (such as `Context::get`).
+	pub command: Command,
+	/// Context which would be passed to the action of the routed command
+	pub context: Context,
+}
+
+impl ParseOutcome {
+	/// Creates `ParseOutcome` from the routed command and its context.
+	pub fn new(command: Command, context: Context) -> Self {
+		fn collect<T: Clone>(vector: &Vector<T>) -> Vec<T> {
+			vector.inner().iter().flatten().cloned().collect()
+		}
+		ParseOutcome {
+			routes: collect(&context.routes),
+			path: crate::derive::route_of(&context, &command),
+			local_flags_values: collect(&context.local_flags_values),
+			common_flags_values: collect(&context.common_flags_values),
+			args: context.args.clone(),
+			positional_values: collect(&context.positional_values),
+			trailing_args: collect(&context.trailing_args),
+			error_info_list: collect(&context.error_info_list),
+			command,
+			context,
+		}
+	}
+}
+
+/// `ParseFailure` shows that `Command::parse` does not reach an action, in the same cases where `run_from_args` returns `ActionError`.
+/// `Command::parse`がアクションまで到達しなかったことを示す（`run_from_args`が`ActionError`を返す場合と同じ）
+#[derive(Debug)]
+pub enum ParseFailure {
+	/// Shows that response file (`@path` arg) cannot be expanded.
+	InvalidResponseFile(response_file::ResponseFileError),
+	/// Shows that the routed command does not have its own action. Inner is the outcome until then.
+	NoActionRegistered(Box<ParseOutcome>),
+	/// Shows that there are parse errors and strictness is not `Strictness::Lenient`. Inner is the outcome including the errors.
+	ParseFailed(Box<ParseOutcome>),
+	/// Shows that parsing ends unexpectedly (such as error which is not caused by parsing). Inner is the description.
+	Unexpected(String),
+}
+
+impl fmt::Display for ParseFailure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ParseFailure::InvalidResponseFile(err) => write!(f, "{err}"),
+			ParseFailure::NoActionRegistered(outcome) => {
+				write!(
+					f,
+					"{} does not have its own action.",
+					outcome.path.join(" ")
+				)
+			}
+			ParseFailure::Unexpected(description) => write!(f, "{description}"),
+			ParseFailure::ParseFailed(outcome) => write!(
+				f,
+				"{}",
+				outcome
+					.error_info_list
+					.iter()
+					.map(gen_error_description)
+					.collect::<Vec<String>>()
+					.join("\n")
+			),
+		}
+	}
+}
+
+impl Error for ParseFailure {}
+
+impl From<ActionError> for ParseFailure {
+	fn from(err: ActionError) -> Self {
+		let ActionError {
+			value,
+			kind,
+			command,
+			mut context,
+			related_error,
+			..
+		} = err;
+		context.parse_only = false;
+		match kind {
+			ParseFailed => ParseFailure::ParseFailed(Box::new(ParseOutcome::new(*command, *context))),
+			NoActionRegistered => {
+				ParseFailure::NoActionRegistered(Box::new(ParseOutcome::new(*command, *context)))
+			}
+			InvalidResponseFile => {
+				match related_error.map(|err| err.downcast::<response_file::ResponseFileError>()) {
+					Some(Ok(err)) => ParseFailure::InvalidResponseFile(*err),
+					_ => ParseFailure::Unexpected(value),
+				}
+			}
+			Custom | ActionErrorKind::None => ParseFailure::Unexpected(value),
+		}
+	}
+}
+
+/// `EnvLookup` shows type alias for function to look up environment variable
+pub type EnvLookup = fn(name: &str) -> Option<String>;
+
 /// `LicenseFunc` shows type alias for license function
 pub type LicenseFunc = fn(command: &Command, context: &Context) -> String;
 
@@ -170,16 +375,18 @@ impl License {
 
 /// Helper inner macro for context generation
 macro_rules! gen_context_for_self_action {
-	($raw_args:expr) => {{
+	($parse_only:expr; $raw_args:expr) => {{
 		let mut args = VecDeque::from($raw_args.clone());
 		let exe_path = args.pop_front().unwrap();
-		gen_context_for_self_action!($raw_args, args, exe_path)
+		gen_context_for_self_action!($parse_only; $raw_args, args, exe_path)
 	}};
-	($raw_args:expr,$args:expr,$exe_path:expr) => {
-		Context::new($raw_args, $args, Vector(None), Vector(None), $exe_path)
-	};
-	($raw_args:expr,$args:expr,$exe_path:expr, $inter_mediate_args:expr) => {
-		Context::with_all_field(
+	($parse_only:expr; $raw_args:expr,$args:expr,$exe_path:expr) => {{
+		let mut c = Context::new($raw_args, $args, Vector(None), Vector(None), $exe_path);
+		c.parse_only = $parse_only;
+		c
+	}};
+	($parse_only:expr; $raw_args:expr,$args:expr,$exe_path:expr, $inter_mediate_args:expr) => {{
+		let mut c = Context::with_all_field(
 			$raw_args,
 			$args,
 			Vector(None),
@@ -189,18 +396,21 @@ macro_rules! gen_context_for_self_action {
 			Vector::default(),
 			Some($inter_mediate_args),
 			Vector(None),
-		)
-	};
+		);
+		c.parse_only = $parse_only;
+		c
+	}};
 }
 
 /// Helper inner macro that is about context generation for sub command run
 macro_rules! gen_context_for_sub_run {
-	($self:expr,$raw_args:expr,$args:expr,$exe_path:expr) => {
-		gen_context_for_sub_run!(inner, $self, $raw_args, $args, $exe_path, None)
+	($parse_only:expr; $self:expr,$raw_args:expr,$args:expr,$exe_path:expr) => {
+		gen_context_for_sub_run!(inner, $parse_only, $self, $raw_args, $args, $exe_path, None)
 	};
-	($self:expr,$raw_args:expr,$args:expr,$exe_path:expr, $inter_mediate_args: expr) => {
+	($parse_only:expr; $self:expr,$raw_args:expr,$args:expr,$exe_path:expr, $inter_mediate_args: expr) => {
 		gen_context_for_sub_run!(
 			inner,
+			$parse_only,
 			$self,
 			$raw_args,
 			$args,
@@ -208,8 +418,8 @@ macro_rules! gen_context_for_sub_run {
 			Some($inter_mediate_args)
 		)
 	};
-	(inner,$self:expr, $raw_args:expr,$args:expr,$exe_path:expr,$inter_mediate_args:expr) => {
-		Context::with_all_field(
+	(inner,$parse_only:expr,$self:expr, $raw_args:expr,$args:expr,$exe_path:expr,$inter_mediate_args:expr) => {{
+		let mut c = Context::with_all_field(
 			$raw_args,
 			$args,
 			Vector::with_first_elem($self.c_flags.take()),
@@ -219,8 +429,10 @@ macro_rules! gen_context_for_sub_run {
 			Vector::default(),
 			$inter_mediate_args,
 			Vector::default(),
-		)
-	};
+		);
+		c.parse_only = $parse_only;
+		c
+	}};
 }
 
 impl Command {
@@ -268,6 +480,17 @@ impl Command {
 			alias: Vector::default(),
 			version: String::default(),
 			sub: Vector::default(),
+			trailing_var_arg: false,
+			parser: None,
+			strictness: None,
+			positionals: Vector::default(),
+			groups: Vector::default(),
+			env_prefix: None,
+			env_lookup: None,
+			config: None,
+			config_name: None,
+			response_files: false,
+			non_utf8_args: NonUtf8Args::default(),
 		}
 	}
 
@@ -300,39 +523,77 @@ impl Command {
 			alias,
 			version,
 			sub,
+			trailing_var_arg: false,
+			parser: None,
+			strictness: None,
+			positionals: Vector::default(),
+			groups: Vector::default(),
+			env_prefix: None,
+			env_lookup: None,
+			config: None,
+			config_name: None,
+			response_files: false,
+			non_utf8_args: NonUtf8Args::default(),
 		}
 	}
 
 	/// Run command with collecting args automatically
+	/// Args are collected as `OsString`, so args which are not valid UTF-8 are also accepted (see `run_from_os_args`).
 	pub fn run_with_auto_arg_collect(self) -> run_result!() {
-		match self.sub {
-			Vector(None) => self.single_run(std::env::args().collect::<Vec<String>>()),
-			_ => self.run(std::env::args().collect::<Vec<String>>()),
-		}
+		self.run_from_os_args(std::env::args_os().collect())
 	}
 
 	/// Run command as single(do not have sub) command
 	/// ルートからサブコマンドがないシンプルな状態の時
 	/// アクションが登録されていなければサブコマンドがあるかを調査する
-	pub fn single_run(mut self, raw_args: Vec<String>) -> run_result!() {
+	pub fn single_run(self, raw_args: Vec<String>) -> run_result!() {
+		match self.expand_response_files(raw_args, false) {
+			Ok((cmd, raw_args)) => cmd.single_run_expanded(raw_args, false),
+			Err(err) => Err(err),
+		}
+	}
+
+	/// Expands response files (`@path` args) in `raw_args` if `response_files` is true.
+	/// If a response file cannot be expanded, returns `ActionError` with the `ResponseFileError`.
+	fn expand_response_files(
+		self,
+		raw_args: Vec<String>,
+		parse_only: bool,
+	) -> Result<(Self, Vec<String>), ActionError> {
+		if !self.response_files {
+			return Ok((self, raw_args));
+		}
+		match response_file::expand_args(raw_args.clone()) {
+			Ok(expanded) => Ok((self, expanded)),
+			Err(err) => Err(ActionError::new(
+				err.to_string(),
+				InvalidResponseFile,
+				self,
+				gen_context_for_self_action!(parse_only; raw_args),
+				Some(Box::new(err)),
+			)),
+		}
+	}
+
+	/// Runs command as single command with `raw_args` whose response files are already expanded.
+	fn single_run_expanded(mut self, raw_args: Vec<String>, parse_only: bool) -> run_result!() {
 		match self.action.take() {
 			Some(action) => {
 				if raw_args.len() < 2 {
-					action(self, gen_context_for_self_action!(raw_args))
+					self.run_action(action, gen_context_for_self_action!(parse_only; raw_args))
 				} else {
-					let mut context = gen_context_for_self_action!(raw_args);
+					let mut context = gen_context_for_self_action!(parse_only; raw_args);
 					//println!("single_run_context: {:?}", context);
-					context =
-						Parser::default().parse_args_until_end(&self.l_flags, &self.c_flags, context);
-					action(self, context)
+					context = self.parse_args_for_action(self.derive_parser(), context);
+					self.run_action(action, context)
 				}
 			}
 			None => match self.sub {
 				Vector(None) => {
-					let c = gen_context_for_self_action!(raw_args);
+					let c = gen_context_for_self_action!(parse_only; raw_args);
 					no_registered_error!(self, c)
 				}
-				_ => self.run(raw_args),
+				_ => self.run_from_expanded_args(raw_args, parse_only),
 			},
 		}
 	}
@@ -427,6 +688,85 @@ impl Command {
 		self
 	}
 
+	/// Sets parser for this command (and sub commands which do not have own parser).
+	pub fn parser(mut self, parser: Parser) -> Self {
+		self.parser = Some(parser);
+		self
+	}
+
+	/// Returns parser used by this command. If this command does not have own parser, returns default parser.
+	pub fn derive_parser(&self) -> Parser {
+		match &self.parser {
+			Some(parser) => parser.clone(),
+			None => Parser::default(),
+		}
+	}
+
+	/// Add positional arg. Positional args are assigned to args in order of addition.
+	pub fn positional(mut self, positional: Positional) -> Self {
+		self.positionals.push(positional);
+		self
+	}
+
+	/// Add group of flags constrained how many of them can be inputted.
+	pub fn group(mut self, group: FlagGroup) -> Self {
+		self.groups.push(group);
+		self
+	}
+
+	/// Sets prefix for deriving environment variable names of flags (for this command and sub commands which do not have own prefix).
+	/// For example, with prefix `MYTOOL`, value of flag `dry-run` is read from `MYTOOL_DRY_RUN` when it is not inputted.
+	pub fn env_prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+		self.env_prefix = Some(prefix.into());
+		self
+	}
+
+	/// Sets function to look up environment variable (for this command and sub commands which do not have own function).
+	/// It is mainly used for testing without changing environment variables of the process.
+	pub fn env_lookup(mut self, env_lookup: EnvLookup) -> Self {
+		self.env_lookup = Some(env_lookup);
+		self
+	}
+
+	/// Sets config for filling values of flags which are not inputted (for this command and sub commands which do not have own config).
+	/// Precedence of flag value is command line > environment variable > config > default value.
+	pub fn config(mut self, config: Config) -> Self {
+		self.config = Some(config);
+		self
+	}
+
+	/// Sets app name for discovering config file (for this command and sub commands which do not have own name).
+	/// If config is not set and `--config` is not inputted, config file is loaded from `$XDG_CONFIG_HOME/<name>/config` (or `$HOME/.config/<name>/config`) if it exists.
+	pub fn config_name<T: Into<String>>(mut self, name: T) -> Self {
+		self.config_name = Some(name.into());
+		self
+	}
+
+	/// Sets strictness for parse errors of this command (and sub commands which do not have own strictness).
+	pub fn strictness(mut self, strictness: Strictness) -> Self {
+		self.strictness = Some(strictness);
+		self
+	}
+
+	/// Returns strictness used by this command. If this command does not have own strictness, returns `Strictness::Lenient`.
+	pub fn derive_strictness(&self) -> Strictness {
+		self.strictness.unwrap_or_default()
+	}
+
+	/// Sets whether the first non-flag arg and args after it are stored in args without parsing.
+	pub fn trailing_var_arg(mut self, trailing_var_arg: bool) -> Self {
+		self.trailing_var_arg = trailing_var_arg;
+		self
+	}
+
+	/// Sets whether `@path` args are expanded into args read from the file (response file) before dispatch.
+	/// Args in response file are split like shell, and `#` starts comment. Response file can include other response files (relative paths are resolved from the including file).
+	/// Args after the first `--` are not expanded.
+	pub fn response_files(mut self, response_files: bool) -> Self {
+		self.response_files = response_files;
+		self
+	}
+
 	/// Returns true if `name_or_alias` matches command's name or one of alias at least
 	/// `name_or_alias`がコマンド名かエイリアスのうち少なくとも一つにマッチした場合trueを返す
 	pub fn is(&self, name_or_alias: &str) -> bool {
@@ -440,15 +780,77 @@ impl Command {
 		}
 	}
 
+	/// Returns true if command's name or one of alias starts with `prefix`
+	pub fn has_prefix(&self, prefix: &str) -> bool {
+		self.name.starts_with(prefix)
+			|| match self.alias.inner() {
+				None => false,
+				Some(inner) => inner.iter().any(|a| a.starts_with(prefix)),
+			}
+	}
+
 	/// Take sub command matches `name_or_alias`.
+	/// If abbreviation is enabled in this command's parser and no sub command matches `name_or_alias` exactly, sub command whose name or alias uniquely starts with `name_or_alias` is taken.
 	/// `name_or_alias`に一致するサブコマンドがある場合、保持している`Vector`から`swap_remove`で取り出して返す
 	pub fn take_sub(&mut self, name_or_alias: &str) -> Option<Command> {
+		let abbreviation = self.allows_abbreviation();
 		match self.sub {
 			Vector(None) => None,
-			Vector(Some(ref mut inner)) => inner
+			Vector(Some(ref mut inner)) => match inner.iter().position(|c| c.is(name_or_alias)) {
+				Some(index) => Some(inner.swap_remove(index)),
+				None if abbreviation && !name_or_alias.is_empty() => {
+					let mut candidates = inner
+						.iter()
+						.enumerate()
+						.filter(|(_, c)| c.has_prefix(name_or_alias));
+					match (candidates.next(), candidates.next()) {
+						(Some((index, _)), None) => Some(inner.swap_remove(index)),
+						_ => None,
+					}
+				}
+				None => None,
+			},
+		}
+	}
+
+	/// Returns names of sub commands whose name or alias starts with `prefix`.
+	pub fn sub_candidates(&self, prefix: &str) -> Vec<String> {
+		match self.sub.inner() {
+			None => Vec::new(),
+			Some(inner) => inner
 				.iter()
-				.position(|c| c.is(name_or_alias))
-				.map(|index| inner.swap_remove(index)),
+				.filter(|c| c.has_prefix(prefix))
+				.map(|c| c.name.clone())
+				.collect(),
+		}
+	}
+
+	/// Returns true if this command's parser accepts abbreviation of sub command name.
+	fn allows_abbreviation(&self) -> bool {
+		self.parser.as_ref().is_some_and(|p| p.abbreviation)
+	}
+
+	/// Returns error info if `arg` is ambiguous abbreviation of sub commands.
+	fn ambiguous_sub_error(&self, arg: &str) -> Option<ErrorInfo> {
+		if !self.allows_abbreviation() || arg.is_empty() {
+			return None;
+		}
+		let candidates = self.sub_candidates(arg);
+		if candidates.len() > 1 {
+			Some((
+				MiddleArg::Normal(arg.to_owned()),
+				ParseError::AmbiguousSub(candidates.clone()),
+				ParseError::AmbiguousSub(candidates),
+			))
+		} else {
+			None
+		}
+	}
+
+	/// Pushes error to `c` if the first arg left in `c` (which is in place of sub command, such as `ins` of `app -v ins` whose `-v` does not take it as value) is ambiguous abbreviation of sub commands.
+	fn push_ambiguous_sub_error(&self, c: &mut Context) {
+		if let Some(err) = c.args.front().and_then(|arg| self.ambiguous_sub_error(arg)) {
+			c.error_info_list.push(err);
 		}
 	}
 
@@ -476,6 +878,24 @@ impl Command {
 			Vector(Some(vec![self.name.clone()]))
 		}
 	}
+
+	/// Parses args of context for this command's action. If `trailing_var_arg` is true, parses only flags before the first non-flag arg.
+	fn parse_args_for_action(&self, p: Parser, c: Context) -> Context {
+		if self.trailing_var_arg {
+			p.parse_args_until_first_positional(&self.l_flags, &self.c_flags, c)
+		} else {
+			p.parse_args_until_end(&self.l_flags, &self.c_flags, c)
+		}
+	}
+
+	/// Parses rest args of context after a non-flag arg appeared. If `trailing_var_arg` is true, rest args are not parsed.
+	fn parse_rest_args(&self, p: Parser, c: Context) -> Context {
+		if self.trailing_var_arg {
+			c
+		} else {
+			p.parse_args_until_end(&self.l_flags, &self.c_flags, c)
+		}
+	}
 }
 
 impl From<String> for Command {
@@ -493,6 +913,17 @@ impl From<String> for Command {
 			alias: Vector::default(),
 			version: String::default(),
 			sub: Vector::default(),
+			trailing_var_arg: false,
+			parser: None,
+			strictness: None,
+			positionals: Vector::default(),
+			groups: Vector::default(),
+			env_prefix: None,
+			env_lookup: None,
+			config: None,
+			config_name: None,
+			response_files: false,
+			non_utf8_args: NonUtf8Args::default(),
 		}
 	}
 }
@@ -509,6 +940,12 @@ impl Run<Vec<String>> for Command {
 	}
 }
 
+impl Run<Vec<OsString>> for Command {
+	fn run(self, args: Vec<OsString>) -> run_result!() {
+		self.run_from_os_args(args)
+	}
+}
+
 impl Run<Context> for Command {
 	fn run(self, c: Context) -> run_result!() {
 		self.run_with_context(c)
@@ -517,51 +954,118 @@ impl Run<Context> for Command {
 
 impl Command {
 	/// Run commands with `raw_args`
-	pub fn run_from_args(mut self, raw_args: Vec<String>) -> run_result!() {
+	pub fn run_from_args(self, raw_args: Vec<String>) -> run_result!() {
+		self.run_or_parse_from_args(raw_args, false)
+	}
+
+	/// Runs commands with `raw_args`. If `parse_only` is true, actions are not run and the routed command and its context are returned as `ActionResult::Result`.
+	fn run_or_parse_from_args(self, raw_args: Vec<String>, parse_only: bool) -> run_result!() {
+		match self.expand_response_files(raw_args, parse_only) {
+			Ok((cmd, raw_args)) => cmd.run_from_expanded_args(raw_args, parse_only),
+			Err(err) => Err(err),
+		}
+	}
+
+	/// Parses `raw_args` without running any action, and returns how they are routed and parsed.
+	/// This shares the same code path as `run_from_args` (including response files, environment variables, config and validation), so the result is same as the context passed to the action.
+	/// If `run_from_args` would return `ActionError` before running action, returns `ParseFailure`.
+	/// アクションを実行せずに引数をパースし、割り当て先とパース結果を返す（`run_from_args`と同じ処理を通る）
+	pub fn parse(&self, raw_args: Vec<String>) -> Result<ParseOutcome, ParseFailure> {
+		match self.clone().run_or_parse_from_args(raw_args, true) {
+			Ok(ActionResult::Result(cmd, mut ctx)) => {
+				ctx.parse_only = false;
+				Ok(ParseOutcome::new(cmd, ctx))
+			}
+			Ok(result) => Err(ParseFailure::Unexpected(format!(
+				"parsing ended with unexpected result: {result:?}"
+			))),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	/// Run commands with `raw_args` which may not be valid UTF-8.
+	/// Args which are not valid UTF-8 are lossily converted (invalid sequences are shown as `U+FFFD`), and their originals can be retrieved from context by getters such as `Context::os_args` or `Context::get_os_flag_value_of`.
+	/// If value of flag or positional arg whose type requires UTF-8 (not string or path) includes invalid sequence, `ParseError::NonUtf8Value` is reported.
+	/// UTF-8として不正な引数を含む可能性がある引数でコマンドを実行する
+	pub fn run_from_os_args(mut self, raw_args: Vec<OsString>) -> run_result!() {
+		let (raw_args, non_utf8_args) = os_args::convert(raw_args);
+		self.non_utf8_args = non_utf8_args;
+		self.run_from_args(raw_args)
+	}
+
+	/// Runs commands with `raw_args` whose response files are already expanded.
+	fn run_from_expanded_args(mut self, raw_args: Vec<String>, parse_only: bool) -> run_result!() {
 		if self.sub.is_none() {
-			return self.single_run(raw_args);
+			return self.single_run_expanded(raw_args, parse_only);
 		}
 		let mut args = VecDeque::from(raw_args.clone());
 		let exe_path = args.pop_front().unwrap();
 		let head = args.pop_front();
 		if head.is_none() {
 			//引数がない場合
-			let c = gen_context_for_self_action!(raw_args, args, exe_path);
+			let c = gen_context_for_self_action!(parse_only; raw_args, args, exe_path);
 			match self.action {
-				Some(action) => action(self, c),
+				Some(action) => self.run_action(action, c),
 				None => no_registered_error!(self, c),
 			}
 		} else {
 			//get before first non-flag arg with parsing flags
-			let p = Parser::default();
+			let p = self.derive_parser();
 			match head {
+				Some(end_of_options) if p.is_end_of_options(&end_of_options) => {
+					// オプション終端の場合、残りの引数はパースせずにルートコマンドのアクションへ
+					args.push_front(end_of_options);
+					let c = gen_context_for_self_action!(parse_only; raw_args, args, exe_path);
+					self.run_own_action_after_inter_mediate_args(c, p, false)
+				}
 				Some(long_flag) if p.long_flag(&long_flag) => {
 					//long flag
 					let last = p.long_middle(long_flag);
-					self.assign_run(args, VecDeque::new(), p, raw_args, exe_path, last)
+					self.assign_run_or_parse(
+						args,
+						VecDeque::new(),
+						p,
+						raw_args,
+						exe_path,
+						last,
+						parse_only,
+					)
 				}
 				Some(short_flag) if p.flag(&short_flag) => {
 					//short flag
 					let last = p.short_middle(short_flag);
-					self.assign_run(args, VecDeque::new(), p, raw_args, exe_path, last)
+					self.assign_run_or_parse(
+						args,
+						VecDeque::new(),
+						p,
+						raw_args,
+						exe_path,
+						last,
+						parse_only,
+					)
 				}
 				Some(arg) => {
 					match self.take_sub(&arg) {
 						None => {
 							// ルートコマンド実行のとき
+							let ambiguous = self.ambiguous_sub_error(&arg);
 							args.push_front(arg);
-							let mut c = gen_context_for_self_action!(raw_args, args, exe_path);
+							let mut c = gen_context_for_self_action!(parse_only; raw_args, args, exe_path);
+							if let Some(err) = ambiguous {
+								c.error_info_list.push(err);
+							}
 							match self.action {
 								None => no_registered_error!(self, c),
 								Some(action) => {
-									c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
-									action(self, c)
+									c = self.parse_rest_args(p, c);
+									self.run_action(action, c)
 								}
 							}
 						}
-						Some(sub) => {
+						Some(mut sub) => {
 							// サブコマンドがヒットしたとき
-							let c = gen_context_for_sub_run!(self, raw_args, args, exe_path);
+							inherit_settings!(sub, self);
+							let c = gen_context_for_sub_run!(parse_only; self, raw_args, args, exe_path);
 							let r = sub.run(c);
 							// サブコマンドの結果をハンドリング
 							self.handle_sub_result(r)
@@ -580,22 +1084,26 @@ impl Command {
 	pub fn run_with_context(mut self, mut context: Context) -> run_result!() {
 		if self.sub.is_none() {
 			// サブコマンドがない場合
-			let p = Parser::default();
+			let p = self.derive_parser();
 			let (mut context, non_flag_args) =
 				p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, context, true);
 			if let Some(mut non_flag_args) = non_flag_args {
 				non_flag_args.append(&mut context.args);
 				context.args = non_flag_args;
 			}
-			context = p.parse_args_until_end(&self.l_flags, &self.c_flags, context);
+			context = self.parse_args_for_action(p, context);
 			match self.action {
-				Some(action) => action(self, context),
+				Some(action) => self.run_action(action, context),
 				None => no_registered_error!(self, context),
 			}
 		} else {
 			//サブコマンドと一致するかを捜査
-			let p = Parser::default();
+			let p = self.derive_parser();
 			match context.args.pop_front() {
+				Some(end_of_options) if p.is_end_of_options(&end_of_options) => {
+					context.args.push_front(end_of_options);
+					self.run_own_action_after_inter_mediate_args(context, p, true)
+				}
 				Some(long_flag) if p.long_flag(&long_flag) => {
 					let last = p.long_middle(long_flag);
 					self.assign_context(context, p, VecDeque::new(), last)
@@ -610,6 +1118,7 @@ impl Command {
 						Some(mut sub) => {
 							context.common_flags.push(self.c_flags.take());
 							check_sub!(sub, self);
+							inherit_settings!(sub, self);
 							context.routes.push(self.name.clone());
 							let r = sub.run(context);
 							self.handle_sub_result(r)
@@ -617,6 +1126,9 @@ impl Command {
 						None => match self.action {
 							None => no_registered_error!(self, context),
 							Some(action) => {
+								if let Some(err) = self.ambiguous_sub_error(&arg) {
+									context.error_info_list.push(err);
+								}
 								let c = match p.parse_inter_mediate_args(
 									&self.l_flags,
 									&self.c_flags,
@@ -624,21 +1136,19 @@ impl Command {
 									true,
 								) {
 									(mut context, None) => {
-										context =
-											p.parse_args_until_end(&self.l_flags, &self.c_flags, context);
+										context = self.parse_rest_args(p, context);
 										context.args.push_front(arg);
 										context
 									}
 									(mut context, Some(mut non_flag_args)) => {
-										context =
-											p.parse_args_until_end(&self.l_flags, &self.c_flags, context);
+										context = self.parse_rest_args(p, context);
 										context.args.push_front(arg);
 										non_flag_args.append(&mut context.args);
 										context.args = non_flag_args;
 										context
 									}
 								};
-								action(self, c)
+								self.run_action(action, c)
 							}
 						},
 					}
@@ -653,7 +1163,7 @@ impl Command {
 								non_flag_args.append(&mut context.args);
 								context.args = non_flag_args;
 							}
-							action(self, context)
+							self.run_action(action, context)
 						}
 						None => {
 							let (mut context, non_flag_args) =
@@ -697,6 +1207,7 @@ impl Command {
 						c.parsing_args = Some(inter_mediate_args);
 					}
 					check_sub!(sub, self);
+					inherit_settings!(sub, self);
 					c.routes.push(self.name.clone());
 					let r = sub.run(c);
 					self.handle_sub_result(r)
@@ -709,6 +1220,16 @@ impl Command {
 						inter_mediate_args.push_back(MiddleArg::Normal(arg));
 						c.args = args;
 						match c.args.pop_front() {
+							Some(end_of_options) if p.is_end_of_options(&end_of_options) => {
+								c.args.push_front(end_of_options);
+								if let Some(mut parsing_args) = c.parsing_args {
+									parsing_args.append(&mut inter_mediate_args);
+									c.parsing_args = Some(parsing_args);
+								} else {
+									c.parsing_args = Some(inter_mediate_args);
+								}
+								self.run_own_action_after_inter_mediate_args(c, p, false)
+							}
 							Some(long_flag) if p.long_flag(&long_flag) => {
 								let last = p.long_middle(long_flag);
 								self.assign_context(c, p, inter_mediate_args, last)
@@ -728,6 +1249,7 @@ impl Command {
 									}
 									c.routes.push(self.name.clone());
 									check_sub!(sub, self);
+									inherit_settings!(sub, self);
 									let r = sub.run(c);
 									self.handle_sub_result(r)
 								}
@@ -740,14 +1262,15 @@ impl Command {
 									}
 									let (mut c, non_flag_args) =
 										p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-									c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
+									c = self.parse_rest_args(p, c);
 									c.args.push_front(arg);
 									if let Some(mut non_flag_args) = non_flag_args {
 										non_flag_args.append(&mut c.args);
 										c.args = non_flag_args;
 									}
+									self.push_ambiguous_sub_error(&mut c);
 									match self.action {
-										Some(action) => action(self, c),
+										Some(action) => self.run_action(action, c),
 										None => no_registered_error!(self, c),
 									}
 								}
@@ -759,17 +1282,7 @@ impl Command {
 								} else {
 									c.parsing_args = Some(inter_mediate_args);
 								}
-								let (mut c, args) =
-									p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-
-								if let Some(mut args) = args {
-									args.append(&mut c.args);
-									c.args = args;
-								}
-								match self.action {
-									Some(action) => action(self, c),
-									None => no_registered_error!(self, c),
-								}
+								self.run_own_action_after_inter_mediate_args(c, p, false)
 							}
 						}
 					}
@@ -782,14 +1295,15 @@ impl Command {
 						}
 						let (mut c, non_flag_args) =
 							p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-						c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
+						c = self.parse_rest_args(p, c);
 						c.args.push_front(arg);
 						if let Some(mut non_flag_args) = non_flag_args {
 							non_flag_args.append(&mut c.args);
 							c.args = non_flag_args;
 						}
+						self.push_ambiguous_sub_error(&mut c);
 						match self.action {
-							Some(action) => action(self, c),
+							Some(action) => self.run_action(action, c),
 							None => no_registered_error!(self, c),
 						}
 					}
@@ -804,17 +1318,7 @@ impl Command {
 				} else {
 					c.parsing_args = Some(inter_mediate_args);
 				}
-				let (mut c, non_flag_args) =
-					p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-				//println!("after_parse_ima:{:?}", c);
-				if let Some(non_flag_args) = non_flag_args {
-					//non_flag_args.append(&mut c.args);
-					c.args = non_flag_args;
-				}
-				match self.action {
-					Some(action) => action(self, c),
-					None => no_registered_error!(self, c),
-				}
+				self.run_own_action_after_inter_mediate_args(c, p, false)
 			}
 		}
 	}
@@ -822,6 +1326,20 @@ impl Command {
 	/// Assign subcomannd's run or command's own action with no context
 	/// コンテキストが生成されていないときに、`run_from_args`内で第一引数からサブコマンドかそうでないか分からなかった時に再帰処理を行って割り当てを行う関数
 	pub fn assign_run(
+		self,
+		args: VecDeque<String>,
+		inter_mediate_args: VecDeque<MiddleArg>,
+		p: Parser,
+		raw_args: Vec<String>,
+		exe_path: String,
+		last: MiddleArg,
+	) -> run_result!() {
+		self.assign_run_or_parse(args, inter_mediate_args, p, raw_args, exe_path, last, false)
+	}
+
+	/// Same as `assign_run`. If `parse_only` is true, actions are not run and the routed command and its context are returned as `ActionResult::Result`.
+	#[allow(clippy::too_many_arguments)]
+	fn assign_run_or_parse(
 		mut self,
 		mut args: VecDeque<String>,
 		mut inter_mediate_args: VecDeque<MiddleArg>,
@@ -829,6 +1347,7 @@ impl Command {
 		raw_args: Vec<String>,
 		exe_path: String,
 		last: MiddleArg,
+		parse_only: bool,
 	) -> run_result!() {
 		let (next_non_flag, _args, _inter_mediate_args, last) =
 			p.middle_parse(args, inter_mediate_args, last);
@@ -837,10 +1356,10 @@ impl Command {
 		match next_non_flag {
 			Some(arg) => {
 				match self.take_sub(&arg) {
-					Some(sub) => {
+					Some(mut sub) => {
+						inherit_settings!(sub, self);
 						inter_mediate_args.push_back(last);
-						let c =
-							gen_context_for_sub_run!(self, raw_args, args, exe_path, inter_mediate_args);
+						let c = gen_context_for_sub_run!(parse_only; self, raw_args, args, exe_path, inter_mediate_args);
 
 						sub.run(c)
 					}
@@ -853,17 +1372,46 @@ impl Command {
 								inter_mediate_args.push_back(last);
 								inter_mediate_args.push_back(MiddleArg::Normal(arg));
 								match args.pop_front() {
+									Some(end_of_options) if p.is_end_of_options(&end_of_options) => {
+										args.push_front(end_of_options);
+										let c = gen_context_for_self_action!(
+											parse_only;
+											raw_args,
+											args,
+											exe_path,
+											inter_mediate_args
+										);
+										self.run_own_action_after_inter_mediate_args(c, p, false)
+									}
 									Some(long_flag) if p.long_flag(&long_flag) => {
 										let last = p.long_middle(long_flag);
-										self.assign_run(args, inter_mediate_args, p, raw_args, exe_path, last)
+										self.assign_run_or_parse(
+											args,
+											inter_mediate_args,
+											p,
+											raw_args,
+											exe_path,
+											last,
+											parse_only,
+										)
 									}
 									Some(short_flag) if p.flag(&short_flag) => {
 										let last = p.short_middle(short_flag);
-										self.assign_run(args, inter_mediate_args, p, raw_args, exe_path, last)
+										self.assign_run_or_parse(
+											args,
+											inter_mediate_args,
+											p,
+											raw_args,
+											exe_path,
+											last,
+											parse_only,
+										)
 									}
 									Some(arg) => match self.take_sub(&arg) {
-										Some(sub) => {
+										Some(mut sub) => {
+											inherit_settings!(sub, self);
 											let c = gen_context_for_sub_run!(
+												parse_only;
 												self,
 												raw_args,
 												args,
@@ -876,6 +1424,7 @@ impl Command {
 										None => {
 											//サブコマンドはないのでそのままselfでaction
 											let c = gen_context_for_self_action!(
+												parse_only;
 												raw_args,
 												args,
 												exe_path,
@@ -887,14 +1436,15 @@ impl Command {
 												c,
 												false,
 											);
-											c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
+											c = self.parse_rest_args(p, c);
 											c.args.push_front(arg);
 											if let Some(mut non_flag_args) = non_flag_args {
 												non_flag_args.append(&mut c.args);
 												c.args = non_flag_args;
-											};
+											}
+											self.push_ambiguous_sub_error(&mut c);
 											match self.action {
-												Some(action) => action(self, c),
+												Some(action) => self.run_action(action, c),
 												None => no_registered_error!(self, c),
 											}
 										}
@@ -902,21 +1452,13 @@ impl Command {
 									None => {
 										//残りのargはなし、そのままaction
 										let c = gen_context_for_self_action!(
+											parse_only;
 											raw_args,
 											args,
 											exe_path,
 											inter_mediate_args
 										);
-										let (mut c, non_flag_args) =
-											p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-										if let Some(mut non_flag_args) = non_flag_args {
-											non_flag_args.append(&mut c.args);
-											c.args = non_flag_args;
-										}
-										match self.action {
-											Some(action) => action(self, c),
-											None => no_registered_error!(self, c),
-										}
+										self.run_own_action_after_inter_mediate_args(c, p, false)
 									}
 								}
 							}
@@ -926,6 +1468,7 @@ impl Command {
 								//inter_mediate_args.push_back(MiddleArg::Normal(arg));
 
 								let c = gen_context_for_self_action!(
+									parse_only;
 									raw_args,
 									args,
 									exe_path,
@@ -933,14 +1476,15 @@ impl Command {
 								);
 								let (mut c, non_flag_args) =
 									p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, false);
-								c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
+								c = self.parse_rest_args(p, c);
 								c.args.push_front(arg);
 								if let Some(mut non_flag_args) = non_flag_args {
 									non_flag_args.append(&mut c.args);
 									c.args = non_flag_args;
 								}
+								self.push_ambiguous_sub_error(&mut c);
 								match self.action {
-									Some(action) => action(self, c),
+									Some(action) => self.run_action(action, c),
 									_ => no_registered_error!(self, c),
 								}
 							}
@@ -952,21 +1496,161 @@ impl Command {
 				//argがなかった場合
 				//self.actionに放り込む
 				inter_mediate_args.push_back(last);
-				let context =
-					gen_context_for_self_action!(raw_args, args, exe_path, inter_mediate_args);
-				let (mut c, non_flag_args) =
-					p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, context, false);
-				if let Some(mut non_flag_args) = non_flag_args {
-					non_flag_args.append(&mut c.args);
-					c.args = non_flag_args;
-				}
+				let context = gen_context_for_self_action!(parse_only; raw_args, args, exe_path, inter_mediate_args);
+				self.run_own_action_after_inter_mediate_args(context, p, false)
+			}
+		}
+	}
+
+	/// Parses intermediate args of context and args left in context, then runs own action.
+	/// Args left in context are empty or start with end-of-options marker, so left args are stored as `trailing_args`.
+	/// 中間引数をパースした後、残った引数（空、もしくはオプション終端から始まる）を処理して自身のアクションを実行する
+	fn run_own_action_after_inter_mediate_args(
+		self,
+		c: Context,
+		p: Parser,
+		flag_only: bool,
+	) -> run_result!() {
+		let (mut c, non_flag_args) =
+			p.parse_inter_mediate_args(&self.l_flags, &self.c_flags, c, flag_only);
+		c = p.parse_args_until_end(&self.l_flags, &self.c_flags, c);
+		if let Some(mut non_flag_args) = non_flag_args {
+			non_flag_args.append(&mut c.args);
+			c.args = non_flag_args;
+		}
+		self.push_ambiguous_sub_error(&mut c);
+		match self.action {
+			Some(action) => self.run_action(action, c),
+			None => no_registered_error!(self, c),
+		}
+	}
 
-				match self.action {
-					Some(action) => action(self, c),
-					None => no_registered_error!(self, c),
+	/// Sets environment variable names derived from `env_prefix` to flags available in this command which do not have own `env`.
+	fn derive_env_names(&mut self, c: &mut Context) {
+		let Some(prefix) = &self.env_prefix else {
+			return;
+		};
+		let derive = |flags: &mut Vector<Flag>| {
+			for flag in flags.inner_mut().iter_mut().flatten() {
+				if flag.env.is_none() {
+					flag.env = Some(flag.derive_env_name(prefix));
 				}
 			}
+		};
+		derive(&mut self.l_flags);
+		derive(&mut self.c_flags);
+		for flags in c.common_flags.inner_mut().iter_mut().flatten() {
+			derive(flags);
+		}
+	}
+
+	/// Derives config for this command. Config file specified by `--config` flag is used first, then `config`, and then config file discovered by `config_name`.
+	/// If config file cannot be loaded, `ParseError::ConfigLoadFailed` is pushed to context's `error_info_list`.
+	fn derive_config<F: Fn(&str) -> Option<String>>(
+		&self,
+		lookup: F,
+		mut c: Context,
+	) -> (Option<Config>, Context) {
+		let path = match c.get_inputted_flag_value_of("config") {
+			Some(FlagValue::String(path)) if !path.is_empty() => PathBuf::from(path),
+			_ => match (&self.config, &self.config_name) {
+				(Some(config), _) => return (Some(config.clone()), c),
+				(None, Some(name)) => match Config::xdg_path(name, lookup) {
+					Some(path) if path.is_file() => path,
+					_ => return (None, c),
+				},
+				(None, None) => return (None, c),
+			},
+		};
+		match Config::load(&path) {
+			Ok(config) => (Some(config), c),
+			Err(err) => {
+				c.error_info_list.push((
+					MiddleArg::LongFlag(
+						String::from("config"),
+						FlagValue::String(path.display().to_string()),
+					),
+					ParseError::ConfigLoadFailed(err.message),
+					ParseError::None,
+				));
+				(None, c)
+			}
+		}
+	}
+
+	/// Moves originals of non-UTF-8 args into `c`, and replaces their invalid sequences in `c` by `U+FFFD`.
+	fn settle_non_utf8_args(&mut self, mut c: Context) -> Context {
+		c.non_utf8_args = std::mem::take(&mut self.non_utf8_args);
+		self.derive_parser().check_non_utf8_values(c)
+	}
+
+	/// Runs `action` with context after filling flag values from environment variables and config, validating flag values and args by declared positional args and checking required flags and relations between flags (required values and relations are not checked if help flag is set). If strictness is not `Strictness::Lenient` and context has parse errors, returns `ActionError` without running `action`.
+	/// 厳格モードでパースエラーがある場合、アクションを実行せずに`ActionError`を返す
+	fn run_action(mut self, action: Action, mut c: Context) -> run_result!() {
+		let p = self.derive_parser();
+		c.non_utf8_args = std::mem::take(&mut self.non_utf8_args);
+		self.derive_env_names(&mut c);
+		let lookup = |name: &str| match self.env_lookup {
+			Some(lookup) => lookup(name),
+			None => std::env::var(name).ok(),
+		};
+		let c = p.parse_env_values(&self.l_flags, &self.c_flags, lookup, c);
+		let (config, mut c) = self.derive_config(lookup, c);
+		if let Some(config) = config {
+			let section = Config::section_path(&self, &c);
+			c = p.parse_config_values(&self.l_flags, &self.c_flags, &config, &section, c);
+		}
+		let c = p.locate_invalid_elements(&self.l_flags, &self.c_flags, c);
+		let c = p.resolve_map_values(&self.l_flags, &self.c_flags, c);
+		let c = p.parse_positionals(&self.positionals, c);
+		let c = p.check_non_utf8_values(c);
+		let mut c = p.validate_flag_values(&self.l_flags, &self.c_flags, c);
+		let c = if c.is_flag_true("help", &self) {
+			// ヘルプ表示時は必須の引数やフラグの組み合わせを検証しない
+			if let Some(errors) = c.error_info_list.inner_mut() {
+				errors.retain(|(_, err, _)| !matches!(err, ParseError::MissingPositional(_)));
+			}
+			c
+		} else {
+			let c = p.check_required_flags(&self.l_flags, &self.c_flags, c);
+			p.check_flag_relations(&self.l_flags, &self.c_flags, &self.groups, c)
+		};
+		let strictness = self.derive_strictness();
+		if strictness == Strictness::Lenient || c.error_info_list.is_empty() {
+			if c.parse_only {
+				return Ok(ActionResult::Result(self, c));
+			}
+			return action(self, c);
+		}
+		let description = c
+			.error_info_list
+			.inner()
+			.iter()
+			.flatten()
+			.map(gen_error_description)
+			.collect::<Vec<String>>()
+			.join("\n");
+		let printed = strictness == Strictness::StrictWithHelp && !c.parse_only;
+		if printed {
+			println!("{description}");
+			println!(
+				"{}",
+				presets::func::help_tablize_with_alias_dedup(&self, &c)
+			);
 		}
+		let mut err = ActionError::without_related_error(description, ParseFailed, self, c);
+		err.printed = printed;
+		// ルートでもサブコマンドでも同じように出力する
+		Command::report_error(&mut err);
+		Err(err)
+	}
+
+	/// Prints `err` if it is not printed yet (it is not printed while parsing only), and marks it as printed.
+	fn report_error(err: &mut ActionError) {
+		if !err.printed && !err.context.parse_only {
+			println!("error: {err}");
+		}
+		err.printed = true;
 	}
 
 	/// Handle sub action's result (`Result<ActionResult, ActionError>`).
@@ -988,10 +1672,7 @@ impl Command {
 				action(self, ctx)
 			}
 			Err(ref mut err) => {
-				if !err.printed {
-					println!("error: {err}");
-				}
-				err.printed = true;
+				Command::report_error(err);
 				req
 			}
 			_ => req,
@@ -2016,39 +2697,2128 @@ mod tests {
 	}
 
 	#[test]
-	fn test_flag_type() {
-		let arg = vec![
-			"exe_path".to_string(),
-			"sub".to_string(),
-			"leaf".to_string(),
-			"test".to_string(),
-			"--common".to_string(),
-			"test".to_string(),
-			"-c".to_string(),
-			"--local".to_string(),
-			"-y".to_string(),
-			"-i".to_string(),
-			"111".to_string(),
-			"--float".to_string(),
-			"10.0".to_string(),
-		];
-
-		let leaf = Command::with_name("leaf")
+	fn run_with_end_of_options() {
+		let _ = base_root()
 			.action(|cmd, c| {
-				println!("sub_action: {c:?}");
-				let raw_args = vec![
-					"exe_path".to_string(),
-					"sub".to_string(),
-					"leaf".to_string(),
-					"test".to_string(),
-					"--common".to_string(),
-					"test".to_string(),
-					"-c".to_string(),
-					"--local".to_string(),
-					"-y".to_string(),
-					"-i".to_string(),
-					"111".to_string(),
-					"--float".to_string(),
+				assert_eq!(c.args, VecDeque::from(vec!["test".to_string()]));
+				assert_eq!(
+					c.trailing_args,
+					Vector::from(cnv_arg(vec!["--common", "sub", "-a"]))
+				);
+				assert_eq!(
+					c.get_flag_value_of("local", &cmd),
+					Some(FlagValue::String("l_val".into()))
+				);
+				assert_eq!(c.get_inputted_flag_value_of("common"), None);
+				assert!(c.has_no_error());
+				done!()
+			})
+			.sub_command(Command::with_name("sub").action(|_, c| {
+				panic!("not root, in sub: {c:?}");
+			}))
+			.run(cnv_arg(vec![
+				"exe_path", "--local", "l_val", "test", "--", "--common", "sub", "-a",
+			]));
+
+		let _ = base_root()
+			.action(|_, c| {
+				assert_eq!(c.args, VecDeque::new());
+				assert_eq!(c.trailing_args, Vector::from(cnv_arg(vec!["sub", "-l"])));
+				done!()
+			})
+			.sub_command(Command::with_name("sub").action(|_, c| {
+				panic!("not root, in sub: {c:?}");
+			}))
+			.run(cnv_arg(vec!["exe_path", "--", "sub", "-l"]));
+
+		let _ = base_root()
+			.action(|_, c| {
+				panic!("not sub, in root: {c:?}");
+			})
+			.sub_command(Command::with_name("sub").action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(vec!["arg".to_string()]));
+				assert_eq!(c.trailing_args, Vector::from(cnv_arg(vec!["--"])));
+				assert!(c.is_flag_true("common", &cmd));
+				assert_eq!(c.routes, Vector(None));
+				done!()
+			}))
+			.run(cnv_arg(vec!["exe_path", "-c", "sub", "arg", "--", "--"]));
+
+		let _ = Command::with_name("single")
+			.local_flag(Flag::new_bool("bool").short_alias('b'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(vec!["arg".to_string()]));
+				assert_eq!(c.trailing_args, Vector::with_inner(vec![]));
+				assert!(c.is_flag_true("bool", &cmd));
+				done!()
+			})
+			.single_run(cnv_arg(vec!["exe_path", "arg", "-b", "--"]));
+	}
+
+	#[test]
+	fn run_with_trailing_var_arg() {
+		let _ = base_root()
+			.action(|_, c| {
+				panic!("not sub, in root: {c:?}");
+			})
+			.sub_command(
+				Command::with_name("run")
+					.local_flag(Flag::new_string("name").short_alias('n'))
+					.local_flag(Flag::new_bool("interactive").short_alias('i'))
+					.trailing_var_arg(true)
+					.action(|cmd, c| {
+						assert_eq!(
+							c.args,
+							VecDeque::from(cnv_arg(vec!["image", "bash", "-c", "ls -l", "--name", "x"]))
+						);
+						assert_eq!(
+							c.get_flag_value_of("name", &cmd),
+							Some(FlagValue::String("n_val".into()))
+						);
+						assert!(c.is_flag_true("interactive", &cmd));
+						assert!(c.trailing_args.is_none());
+						assert!(c.has_no_error());
+						done!()
+					}),
+			)
+			.run(cnv_arg(vec![
+				"exe_path", "run", "-i", "--name", "n_val", "image", "bash", "-c", "ls -l", "--name",
+				"x",
+			]));
+	}
+
+	#[test]
+	fn run_with_custom_parser() {
+		let root = Command::with_name("root")
+			.parser(Parser::from(('+', ':')))
+			.common_flag(Flag::new_bool("common").short_alias('c'))
+			.local_flag(Flag::new_string("local").short_alias('l'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg", "-c"])));
+				assert_eq!(
+					c.get_flag_value_of("local", &cmd),
+					Some(FlagValue::String("l_val".into()))
+				);
+				assert!(c.is_flag_true("common", &cmd));
+				assert_eq!(c.trailing_args, Vector::from(cnv_arg(vec!["+c"])));
+				done!()
+			})
+			.sub_command(
+				Command::with_name("leaf")
+					.local_flag(Flag::new_int("int").short_alias('i'))
+					.action(|cmd, c| {
+						assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["--int=2"])));
+						assert_eq!(c.get_flag_value_of("int", &cmd), Some(FlagValue::Int(1)));
+						assert!(c.is_flag_true("common", &cmd));
+						done!()
+					}),
+			)
+			.sub_command(
+				Command::with_name("own")
+					.parser(Parser::default())
+					.local_flag(Flag::new_int("int").short_alias('i'))
+					.action(|cmd, c| {
+						assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["++int:2"])));
+						assert_eq!(c.get_flag_value_of("int", &cmd), Some(FlagValue::Int(3)));
+						done!()
+					}),
+			);
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"+c",
+			"++local:l_val",
+			"arg",
+			"-c",
+			"++",
+			"+c",
+		]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path", "+c", "leaf", "++int:1", "--int=2",
+		]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "own", "++int:2", "--int=3"]));
+	}
+
+	#[test]
+	fn run_with_dos_style_parser() {
+		let root = Command::with_name("root")
+			.parser(Parser::dos_style())
+			.common_flag(Flag::new_bool("help").short_alias('h'))
+			.local_flag(Flag::new_bool("verbose").short_alias('v'))
+			.local_flag(Flag::new_string("out").short_alias('o'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg"])));
+				assert!(c.is_flag_true("verbose", &cmd));
+				assert!(c.is_flag_true("help", &cmd));
+				assert_eq!(
+					c.get_flag_value_of("out", &cmd),
+					Some(FlagValue::String("file.txt".into()))
+				);
+				assert_eq!(c.trailing_args, Vector::from(cnv_arg(vec!["/v"])));
+				done!()
+			})
+			.sub_command(
+				Command::with_name("leaf")
+					.local_flag(Flag::new_int("int").short_alias('i'))
+					.action(|cmd, c| {
+						assert_eq!(c.get_flag_value_of("int", &cmd), Some(FlagValue::Int(2)));
+						assert!(c.is_flag_true("help", &cmd));
+						assert!(c.error_info_list.is_empty());
+						done!()
+					}),
+			)
+			.sub_command(
+				Command::with_name("exact")
+					.parser(Parser::dos_style().ignore_case(false))
+					.local_flag(Flag::new_bool("flag"))
+					.action(|cmd, c| {
+						assert!(!c.is_flag_true("flag", &cmd));
+						assert_eq!(c.error_info_list.len(), 1);
+						done!()
+					}),
+			);
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"/v",
+			"/OUT:file.txt",
+			"arg",
+			"/HELP",
+			"//",
+			"/v",
+		]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"/out=file.txt",
+			"/V",
+			"arg",
+			"/H",
+			"//",
+			"/v",
+		]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "leaf", "/Int:2", "/help"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "leaf", "/i=2", "/h"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "exact", "/FLAG"]));
+	}
+
+	#[test]
+	fn run_with_list_flag() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_list("define", FlagType::Int).short_alias('D'))
+			.local_flag(Flag::new_list("include", FlagType::String).short_alias('I'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg"])));
+				assert_eq!(
+					c.get_flag_value_of("include", &cmd),
+					Some(FlagValue::List(vec!["a".into(), "b".into(), "c".into()]))
+				);
+				assert_eq!(
+					c.get_flag_values_of("define", &cmd),
+					Some(vec![FlagValue::Int(1), FlagValue::Int(2)])
+				);
+				let mut bundle = crate::action::bundle::Bundle::new(c, cmd);
+				assert_eq!(
+					bundle.get_flag_values_of("include"),
+					Some(vec!["a".into(), "b".into(), "c".into()])
+				);
+				assert_eq!(
+					bundle.take_inputted_local_flag_value_of("include"),
+					Some(FlagValue::List(vec!["a".into(), "b".into(), "c".into()]))
+				);
+				assert_eq!(bundle.get_flag_values_of("include"), Some(vec![]));
+				done!()
+			})
+			.sub_command(
+				Command::with_name("leaf")
+					.local_flag(Flag::new_string("single"))
+					.action(|cmd, c| {
+						assert_eq!(c.get_flag_values_of("define", &cmd), Some(vec![]));
+						assert_eq!(c.get_flag_values_of("single", &cmd), Some(vec!["x".into()]));
+						done!()
+					}),
+			);
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"--include",
+			"a",
+			"-D=1",
+			"arg",
+			"-I",
+			"b",
+			"--define",
+			"2",
+			"--include=c",
+		]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "leaf", "--single", "x"]));
+	}
+
+	#[test]
+	fn run_with_count_flag() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_count("verbose").short_alias('v'))
+			.local_flag(Flag::new_bool("quiet").short_alias('q'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg"])));
+				assert_eq!(
+					c.get_flag_value_of("verbose", &cmd),
+					Some(FlagValue::Int(5))
+				);
+				assert!(c.is_flag_true("quiet", &cmd));
+				done!()
+			})
+			.sub_command(Command::with_name("leaf").action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("verbose", &cmd),
+					Some(FlagValue::Int(0))
+				);
+				done!()
+			}));
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"-vvq",
+			"arg",
+			"--verbose",
+			"-v",
+			"--verbose",
+		]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "leaf"]));
+	}
+
+	#[test]
+	fn run_with_short_flag_cluster() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_string("color").short_alias('c'))
+			.local_flag(Flag::new_bool("all").short_alias('a'))
+			.local_flag(Flag::new_bool("brief").short_alias('b'))
+			.local_flag(Flag::new_bool("dry").short_alias('d'))
+			.local_flag(Flag::new_bool("each").short_alias('e'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["x"])));
+				// 引数の前にある連結ショートフラグも全て反映される
+				for name in ["all", "brief", "dry", "each"] {
+					assert!(c.is_flag_true(name, &cmd), "{name}");
+				}
+				// コモンフラグの値はコモンフラグの値として保持される
+				assert_eq!(
+					c.get_common_flag_value_of("color", &cmd),
+					Some(FlagValue::String("red".to_owned()))
+				);
+				assert_eq!(c.get_inputted_local_flag_value_of("color"), None);
+				assert!(c.error_info_list.is_empty());
+				done!()
+			});
+		let args = cnv_arg(vec!["exe_path", "-ab", "-de", "x", "-c=red"]);
+
+		// サブコマンドがある場合は引数より前のフラグを後からまとめて解析する
+		let _ = root
+			.clone()
+			.sub_command(Command::with_name("leaf"))
+			.run(args.clone());
+		let _ = root.run(args);
+	}
+
+	#[test]
+	fn run_with_negatable_flag() {
+		let root = Command::with_name("root")
+			.common_flag(
+				Flag::new_bool("cache")
+					.negatable(true)
+					.default_value(FlagValue::Bool(true)),
+			)
+			.local_flag(
+				Flag::new_bool("color")
+					.negatable(true)
+					.default_value(FlagValue::Bool(true)),
+			)
+			.local_flag(Flag::new_bool("force").short_alias('f'))
+			.local_flag(Flag::new_bool("quiet"))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg", "x"])));
+				assert_eq!(
+					c.get_flag_value_of("color", &cmd),
+					Some(FlagValue::Bool(false))
+				);
+				assert_eq!(
+					c.get_flag_value_of("force", &cmd),
+					Some(FlagValue::Bool(true))
+				);
+				assert_eq!(
+					c.get_flag_value_of("quiet", &cmd),
+					Some(FlagValue::Bool(false))
+				);
+				assert!(c.is_flag_true("cache", &cmd));
+				assert_eq!(c.error_info_list.len(), 1);
+				done!()
+			})
+			.sub_command(Command::with_name("leaf").action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("cache", &cmd),
+					Some(FlagValue::Bool(false))
+				);
+				assert!(c.error_info_list.is_empty());
+				done!()
+			}));
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"-f",
+			"true",
+			"--no-color",
+			"arg",
+			"--quiet=off",
+			"--no-force",
+			"x",
+		]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "--no-cache", "leaf"]));
+	}
+
+	#[test]
+	fn run_with_bool_literal_args() {
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_bool("verbose").short_alias('v'))
+			.local_flag(Flag::new_bool("quiet").short_alias('q'))
+			.action(|cmd, c| {
+				// yes/no/1/0などは`--flag=value`の形式でのみ値として扱い、次の引数からは取らない
+				match c.args.front().map(String::as_str) {
+					Some("1") => {
+						assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["1", "2"])));
+						assert!(c.is_flag_true("verbose", &cmd));
+					}
+					Some("yes") => {
+						assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["yes"])));
+						assert!(c.is_flag_true("verbose", &cmd));
+					}
+					_ => {
+						assert!(c.args.is_empty());
+						assert!(c.is_flag_true("verbose", &cmd));
+						assert!(!c.is_flag_true("quiet", &cmd));
+					}
+				}
+				assert!(c.error_info_list.is_empty());
+				done!()
+			});
+
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--verbose", "1", "2"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "-v", "yes"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "--verbose=yes", "-q", "false"]));
+	}
+
+	#[test]
+	fn run_with_attached_short_value() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_int("lines").numeric_shorthand(true))
+			.local_flag(Flag::new_bool("verbose").short_alias('v'))
+			.local_flag(Flag::new_bool("extra").short_alias('x'))
+			.local_flag(Flag::new_string("out").short_alias('o'))
+			.local_flag(Flag::new_int("num").short_alias('n'))
+			.action(|cmd, c| {
+				assert_eq!(c.args, VecDeque::from(cnv_arg(vec!["arg"])));
+				assert!(c.error_info_list.is_empty());
+				match c.get_flag_value_of("out", &cmd) {
+					Some(FlagValue::String(out)) if out == "file.txt" => {
+						assert!(c.is_flag_true("verbose", &cmd));
+						assert_eq!(c.get_flag_value_of("num", &cmd), Some(FlagValue::Int(5)));
+						assert_eq!(c.get_flag_value_of("lines", &cmd), Some(FlagValue::Int(10)));
+					}
+					Some(FlagValue::String(out)) if out == "foo=bar" => {
+						assert!(c.is_flag_true("extra", &cmd));
+						assert_eq!(c.get_flag_value_of("num", &cmd), Some(FlagValue::Int(3)));
+						assert_eq!(c.get_flag_value_of("lines", &cmd), Some(FlagValue::Int(5)));
+					}
+					val => panic!("unexpected out: {val:?}"),
+				}
+				done!()
+			})
+			.sub_command(Command::with_name("leaf").action(|cmd, c| {
+				assert_eq!(c.get_flag_value_of("lines", &cmd), Some(FlagValue::Int(20)));
+				done!()
+			}));
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"-vofile.txt",
+			"-n5",
+			"arg",
+			"-10",
+		]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"arg",
+			"-xofoo=bar",
+			"-n",
+			"3",
+			"-5",
+		]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "-ofoo=bar", "-5", "-xn3", "arg"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "-20", "leaf"]));
+	}
+
+	#[test]
+	fn run_with_abbreviation() {
+		let root = Command::with_name("root")
+			.parser(Parser::default().abbreviation(true))
+			.common_flag(Flag::new_bool("verbose"))
+			.common_flag(Flag::new_bool("version"))
+			.local_flag(Flag::new_string("output").alias("out"))
+			.local_flag(Flag::new_bool("outer"))
+			.action(|cmd, c| {
+				match c.args.front().map(String::as_str) {
+					Some("arg") => {
+						assert!(c.error_info_list.is_empty());
+						assert!(c.is_flag_true("verbose", &cmd));
+						assert_eq!(
+							c.get_flag_value_of("output", &cmd),
+							Some(FlagValue::String("a.txt".into()))
+						);
+					}
+					Some("arg2") => {
+						assert_eq!(
+							c.first_error().map(|e| &e.1),
+							Some(&ParseError::AmbiguousLong(vec![
+								"verbose".to_owned(),
+								"version".to_owned()
+							]))
+						);
+						assert!(!c.is_flag_true("outer", &cmd));
+						assert_eq!(
+							c.get_flag_value_of("output", &cmd),
+							Some(FlagValue::String("b".into()))
+						);
+					}
+					Some("in") => {
+						assert_eq!(
+							c.first_error().map(|e| &e.1),
+							Some(&ParseError::AmbiguousSub(vec![
+								"install".to_owned(),
+								"init".to_owned()
+							]))
+						);
+					}
+					None => {
+						// フラグの値として取られた場合は曖昧なサブコマンドとして扱わない
+						assert!(c.error_info_list.is_empty());
+						assert_eq!(
+							c.get_flag_value_of("output", &cmd),
+							Some(FlagValue::String("in".into()))
+						);
+					}
+					args => panic!("unexpected args: {args:?}"),
+				}
+				done!()
+			})
+			.sub_command(Command::with_name("install"))
+			.sub_command(Command::with_name("init"))
+			.sub_command(Command::with_name("list").alias("ls").action(|cmd, c| {
+				assert!(c.error_info_list.is_empty());
+				assert!(c.is_flag_true("version", &cmd));
+				done!()
+			}))
+			.sub_command(
+				Command::with_name("leaf")
+					.parser(Parser::default())
+					.action(|cmd, c| {
+						assert!(!c.is_flag_true("verbose", &cmd));
+						assert_eq!(c.error_info_list.len(), 1);
+						done!()
+					}),
+			);
+
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--verb", "--out=a.txt", "arg"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "arg2", "--ver", "--outp", "b"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "in"]));
+		// 値を取らないフラグの後でも曖昧なサブコマンドを報告する
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--verbose", "in"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--verbose", "in", "--outer"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--verbose", "in", "--", "x"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--output", "in"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "li", "--vers"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "leaf", "--verb"]));
+	}
+
+	#[test]
+	fn run_with_flag_suggestion() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_bool("verbose").short_alias('V'))
+			.common_flag(Flag::new_string("color"))
+			.local_flag(Flag::new_string("output-file").alias("output"))
+			.local_flag(Flag::new_int("j"))
+			.action(|_, c| {
+				match c.args.front().map(String::as_str) {
+					Some("single") => {
+						// 1文字の名前でも候補を出す
+						let err = c.first_error().unwrap();
+						assert_eq!(err.1, ParseError::NoExistShort(0));
+						assert_eq!(err.2, ParseError::SimilarExist(vec!["j".to_owned()]));
+					}
+					Some("long") => {
+						let err = c.first_error().unwrap();
+						assert_eq!(
+							err,
+							&(
+								MiddleArg::LongFlag("verbos".into(), FlagValue::None),
+								ParseError::NoExistLong,
+								ParseError::SimilarExist(vec!["verbose".to_owned()])
+							)
+						);
+						assert!(gen_error_description(err).ends_with("Did you mean --verbose?"));
+					}
+					Some("alias") => {
+						assert_eq!(
+							c.first_error().map(|e| &e.2),
+							Some(&ParseError::SimilarExist(vec!["output".to_owned()]))
+						);
+					}
+					Some("short") => {
+						let err = c.first_error().unwrap();
+						assert_eq!(err.1, ParseError::NoExistShort(0));
+						assert_eq!(err.2, ParseError::SimilarExist(vec!["verbose".to_owned()]));
+					}
+					Some("far") => {
+						assert_eq!(
+							c.first_error().map(|e| &e.2),
+							Some(&ParseError::NoExistLong)
+						);
+					}
+					args => panic!("unexpected args: {args:?}"),
+				}
+				done!()
+			})
+			.sub_command(Command::with_name("sub").action(|_, c| {
+				assert_eq!(
+					c.first_error().map(|e| &e.2),
+					Some(&ParseError::SimilarExist(vec!["color".to_owned()]))
+				);
+				done!()
+			}));
+
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "long", "--verbos"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--ouptut=a", "alias"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "short", "-verbos"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "far", "--quiet"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "single", "-J"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "sub", "--colr=red"]));
+	}
+
+	#[test]
+	fn run_with_positionals() {
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_bool("verbose"))
+			.positional(Positional::new_int("count"))
+			.positional(
+				Positional::new_string("files")
+					.variadic(true)
+					.required(false),
+			)
+			.positional(Positional::new_string("dest"))
+			.action(|_, c| {
+				let files = |files: Vec<&str>| {
+					Some(FlagValue::List(
+						files
+							.into_iter()
+							.map(|f| FlagValue::String(f.into()))
+							.collect(),
+					))
+				};
+				match c.args.len() {
+					0 => {
+						assert_eq!(
+							c.error_info_list,
+							Vector::from(vec![
+								(
+									MiddleArg::Normal("count".into()),
+									ParseError::MissingPositional("count".into()),
+									ParseError::None
+								),
+								(
+									MiddleArg::Normal("dest".into()),
+									ParseError::MissingPositional("dest".into()),
+									ParseError::None
+								)
+							])
+						);
+						assert_eq!(c.get_positional_value_of("files"), files(vec![]));
+					}
+					2 if c.args[0] == "x" => {
+						assert_eq!(
+							c.first_error(),
+							Some(&(
+								MiddleArg::Normal("x".into()),
+								ParseError::InvalidPositional("count".into()),
+								ParseError::None
+							))
+						);
+						assert_eq!(c.get_positional_value_of("count"), None);
+						assert_eq!(
+							c.get_positional_value_of("dest"),
+							Some(FlagValue::String("c".into()))
+						);
+					}
+					2 => {
+						assert!(c.error_info_list.is_empty());
+						assert_eq!(c.get_positional_value_of("count"), Some(FlagValue::Int(3)));
+						assert_eq!(c.get_positional_value_of("files"), files(vec!["a"]));
+						assert_eq!(
+							c.get_positional_value_of("dest"),
+							Some(FlagValue::String("c".into()))
+						);
+					}
+					4 => {
+						assert!(c.error_info_list.is_empty());
+						assert_eq!(c.get_positional_value_of("files"), files(vec!["a", "b"]));
+						assert_eq!(
+							c.get_positional_value_of("dest"),
+							Some(FlagValue::String("c".into()))
+						);
+					}
+					len => panic!("unexpected args length: {len}"),
+				}
+				done!()
+			})
+			.sub_command(
+				Command::with_name("one")
+					.positional(Positional::new_string("name"))
+					.action(|_, c| {
+						assert_eq!(
+							c.get_positional_value_of("name"),
+							Some(FlagValue::String("a".into()))
+						);
+						assert_eq!(
+							c.error_info_list,
+							Vector::from(vec![(
+								MiddleArg::Normal("b".into()),
+								ParseError::UnexpectedPositional,
+								ParseError::None
+							)])
+						);
+						done!()
+					}),
+			);
+
+		let _ = root.clone().run(cnv_arg(vec!["exe_path"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "x", "c"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "3", "--verbose", "a", "--", "c"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "3", "a", "b", "c"]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "one", "a", "b"]));
+	}
+
+	#[test]
+	fn run_with_required_flags() {
+		let missing = |name: &str| {
+			(
+				MiddleArg::LongFlag(name.into(), FlagValue::None),
+				ParseError::MissingRequiredFlag,
+				ParseError::None,
+			)
+		};
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_string("name").required(true))
+			.local_flag(Flag::new_bool("yes"))
+			.common_flag(Flag::new_string("token").required(true))
+			.action(|_, c| {
+				match c.args.front().map(String::as_str) {
+					Some("ok") => assert!(c.error_info_list.is_empty()),
+					Some("ng") => {
+						let missing = |name: &str| {
+							(
+								MiddleArg::LongFlag(name.into(), FlagValue::None),
+								ParseError::MissingRequiredFlag,
+								ParseError::None,
+							)
+						};
+						assert_eq!(
+							c.error_info_list,
+							Vector::from(vec![missing("name"), missing("token")])
+						);
+						assert!(
+							gen_error_description(c.first_error().unwrap())
+								.ends_with("The required flag --name is missing.")
+						);
+					}
+					args => panic!("unexpected args: {args:?}"),
+				}
+				done!()
+			})
+			.sub_command(
+				Command::with_name("sub")
+					.local_flag(Flag::new_int("id").required(true))
+					.action(|_, c| {
+						assert_eq!(c.error_info_list.len(), 1);
+						assert_eq!(c.first_error().map(|e| e.0.name()), Some("id"));
+						done!()
+					}),
+			)
+			.sub_command(
+				Command::with_name("strict")
+					.strictness(Strictness::Strict)
+					.action(|_, _| panic!("action must not run")),
+			);
+
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--name", "a", "--token=t", "ok"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "ng"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "sub", "--token", "t"]));
+		match root.run(cnv_arg(vec!["exe_path", "strict"])) {
+			Err(err) => assert_eq!(
+				err.context.error_info_list,
+				Vector::from(vec![missing("token")])
+			),
+			Ok(_) => panic!("missing required flag must be error in strict mode"),
+		}
+	}
+
+	#[test]
+	fn run_with_flag_relations() {
+		use crate::flag::GroupKind;
+
+		let root = Command::with_name("deploy")
+			.env_lookup(|_| None)
+			.common_flag(Flag::new_bool("all").conflicts_with("service"))
+			.local_flag(Flag::new_string("service"))
+			.local_flag(Flag::new_string("tls-key").requires("tls-cert"))
+			.local_flag(Flag::new_string("tls-cert"))
+			.local_flag(Flag::new_string("file").env("DEPLOY_FILE"))
+			.local_flag(Flag::new_bool("stdin"))
+			.group(FlagGroup::exactly_one(vec!["file", "stdin"]))
+			.action(|_, c| {
+				let error = |name: &str, e: ParseError| {
+					(
+						MiddleArg::LongFlag(name.into(), FlagValue::None),
+						e,
+						ParseError::None,
+					)
+				};
+				let group_error = |name: &str| {
+					error(
+						name,
+						ParseError::GroupViolation(
+							GroupKind::ExactlyOne,
+							vec!["file".to_owned(), "stdin".to_owned()],
+						),
+					)
+				};
+				let expected = match c.args.front().map(String::as_str) {
+					Some("ok" | "filled") => Vector::default(),
+					Some("conflict") => Vector::from(vec![error(
+						"all",
+						ParseError::ConflictsWith("service".into()),
+					)]),
+					Some("requires") => Vector::from(vec![error(
+						"tls-key",
+						ParseError::RequiresFlag("tls-cert".into()),
+					)]),
+					Some("none") => Vector::from(vec![group_error("file")]),
+					Some("both") => {
+						assert!(
+							gen_error_description(c.first_error().unwrap())
+								.ends_with("Exactly one of --file, --stdin must be specified.")
+						);
+						Vector::from(vec![group_error("stdin")])
+					}
+					args => panic!("unexpected args: {args:?}"),
+				};
+				assert_eq!(c.error_info_list, expected);
+				done!()
+			});
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"ok",
+			"--file",
+			"a",
+			"--service",
+			"s",
+			"--tls-key",
+			"k",
+			"--tls-cert",
+			"c",
+		]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"conflict",
+			"--all",
+			"--service",
+			"s",
+			"--stdin",
+		]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"requires",
+			"--tls-key",
+			"k",
+			"--file",
+			"f",
+		]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "none"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "both", "--file", "f", "--stdin"]));
+
+		// 環境変数や設定ファイルの値は引数で指定されたフラグと衝突しない
+		let filled = root
+			.env_lookup(|name| (name == "DEPLOY_FILE").then(|| String::from("env")))
+			.config(Config::parse("all = true").unwrap());
+		let _ = filled.clone().run(cnv_arg(vec!["exe_path", "filled"]));
+		let _ = filled.run(cnv_arg(vec![
+			"exe_path",
+			"filled",
+			"--service",
+			"s",
+			"--stdin",
+		]));
+	}
+
+	#[test]
+	fn run_with_env_fallback() {
+		let root = Command::with_name("mytool")
+			.env_prefix("MYTOOL")
+			.env_lookup(|name| {
+				match name {
+					"MYTOOL_TOKEN" => Some("secret"),
+					"MYTOOL_COUNT" => Some("3"),
+					"MYTOOL_LEVEL" => Some("x"),
+					"MYTOOL_VERBOSE" => Some("2"),
+					"CUSTOM_NAME" => Some("custom"),
+					_ => None,
+				}
+				.map(String::from)
+			})
+			.common_flag(Flag::new_string("token"))
+			.local_flag(Flag::new_int("count"))
+			.local_flag(Flag::new_string("name").env("CUSTOM_NAME"))
+			.local_flag(Flag::new_int("level"))
+			.local_flag(Flag::new_string("output"))
+			.local_flag(Flag::new_count("verbose").short_alias('v'))
+			.action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("token", &cmd),
+					Some(FlagValue::String("secret".into()))
+				);
+				assert_eq!(
+					c.get_flag_value_of("name", &cmd),
+					Some(FlagValue::String("custom".into()))
+				);
+				assert_eq!(c.get_inputted_local_flag_value_of("output"), None);
+				match c.args.front().map(String::as_str) {
+					Some("env") => {
+						assert_eq!(c.get_flag_value_of("count", &cmd), Some(FlagValue::Int(3)));
+						// カウントフラグは回数を環境変数で指定できる
+						assert_eq!(
+							c.get_flag_value_of("verbose", &cmd),
+							Some(FlagValue::Int(2))
+						);
+						assert_eq!(
+							c.error_info_list,
+							Vector::from(vec![(
+								MiddleArg::LongFlag("level".into(), FlagValue::String("x".into())),
+								ParseError::InvalidEnv("MYTOOL_LEVEL".into()),
+								ParseError::None
+							)])
+						);
+						assert!(gen_error_description(c.first_error().unwrap()).ends_with(
+							"The value x of environment variable MYTOOL_LEVEL is invalid for the flag --level."
+						));
+					}
+					Some("args") => {
+						assert!(c.error_info_list.is_empty());
+						assert_eq!(c.get_flag_value_of("count", &cmd), Some(FlagValue::Int(5)));
+						assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(1)));
+						assert_eq!(
+							c.get_flag_value_of("verbose", &cmd),
+							Some(FlagValue::Int(1))
+						);
+					}
+					args => panic!("unexpected args: {args:?}"),
+				}
+				done!()
+			})
+			.sub_command(Command::with_name("sub").action(|cmd, c| {
+				assert!(c.error_info_list.is_empty());
+				assert_eq!(
+					c.get_flag_value_of("token", &cmd),
+					Some(FlagValue::String("secret".into()))
+				);
+				done!()
+			}));
+
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "env"]));
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"args",
+			"--count",
+			"5",
+			"--level=1",
+			"-v",
+		]));
+		let _ = root.run(cnv_arg(vec!["exe_path", "sub"]));
+	}
+
+	#[test]
+	fn run_with_config() {
+		use crate::vector::flag::FlagSearch;
+
+		let config = Config::parse(
+			r#"
+token = "from config"
+count = 2
+tags = [a, b]
+
+[mytool]
+name = root
+
+[mytool.sub]
+name = sub
+
+[mytool.sub.leaf]
+level = x
+"#,
+		)
+		.unwrap();
+		let root = Command::with_name("mytool")
+			.config(config)
+			.env_lookup(|name| (name == "TOKEN").then(|| String::from("from env")))
+			.common_flag(Flag::new_string("token").env("TOKEN"))
+			.common_flag(Flag::new_string("name"))
+			.common_flag(Flag::new_int("level").default_value(FlagValue::Int(7)))
+			.common_flag(crate::flag::presets::config_flag())
+			.local_flag(Flag::new_int("count"))
+			.local_flag(Flag::new_list("tags", FlagType::String))
+			.action(|cmd, c| {
+				assert!(c.error_info_list.is_empty());
+				assert_eq!(
+					c.get_flag_value_of("token", &cmd),
+					Some(FlagValue::String("from env".into()))
+				);
+				assert_eq!(c.get_flag_value_of("count", &cmd), Some(FlagValue::Int(2)));
+				assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(7)));
+				assert_eq!(
+					c.get_flag_value_of("tags", &cmd),
+					Some(FlagValue::List(vec![
+						FlagValue::String("a".into()),
+						FlagValue::String("b".into())
+					]))
+				);
+				let name = match c.args.front().map(String::as_str) {
+					Some("cli") => "from cli",
+					_ => "root",
+				};
+				assert_eq!(
+					c.get_flag_value_of("name", &cmd),
+					Some(FlagValue::String(name.into()))
+				);
+				let effective = Config::effective(&cmd, &c);
+				assert_eq!(
+					effective.get("mytool", "name"),
+					Some(&vec![name.to_owned()])
+				);
+				assert_eq!(
+					effective.get("mytool", "tags"),
+					Some(&vec!["a".to_owned(), "b".to_owned()])
+				);
+				// 有効な値を設定として適用し直しても同じ値になる
+				let reloaded = Config::parse(&effective.to_string()).unwrap();
+				assert_eq!(
+					Config::derive_flag_value(
+						cmd.c_flags.find("name").unwrap(),
+						reloaded.get("mytool", "name").unwrap()
+					),
+					FlagValue::String(name.into())
+				);
+				done!()
+			})
+			.sub_command(
+				Command::with_name("sub")
+					.action(|cmd, c| {
+						assert!(c.error_info_list.is_empty());
+						assert_eq!(
+							c.get_flag_value_of("name", &cmd),
+							Some(FlagValue::String("sub".into()))
+						);
+						assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(7)));
+						done!()
+					})
+					.sub_command(Command::with_name("leaf").action(|cmd, c| {
+						assert_eq!(Config::section_path(&cmd, &c), "mytool.sub.leaf");
+						match c.get_inputted_flag_value_of("config") {
+							Some(_) => assert_eq!(
+								c.error_info_list,
+								Vector::from(vec![(
+									MiddleArg::LongFlag(
+										"config".into(),
+										FlagValue::String("/nonexistent/config".into())
+									),
+									ParseError::ConfigLoadFailed(
+										"cannot read /nonexistent/config: No such file or directory (os error 2)"
+											.into()
+									),
+									ParseError::None
+								)])
+							),
+							None => {
+								assert_eq!(
+									c.get_flag_value_of("name", &cmd),
+									Some(FlagValue::String("sub".into()))
+								);
+								assert_eq!(
+									c.error_info_list,
+									Vector::from(vec![(
+										MiddleArg::LongFlag("level".into(), FlagValue::String("x".into())),
+										ParseError::InvalidConfig("mytool.sub.leaf".into()),
+										ParseError::None
+									)])
+								);
+								assert!(gen_error_description(c.first_error().unwrap()).ends_with(
+									"The value x in section [mytool.sub.leaf] of config is invalid for the flag --level."
+								));
+							}
+						}
+						done!()
+					})),
+			);
+
+		let _ = root.clone().run(cnv_arg(vec!["exe_path"]));
+		let _ = root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "cli", "--name", "from cli"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "sub"]));
+		let _ = root.clone().run(cnv_arg(vec!["exe_path", "sub", "leaf"]));
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"sub",
+			"leaf",
+			"--config",
+			"/nonexistent/config",
+		]));
+	}
+
+	#[test]
+	fn run_with_response_files() {
+		use crate::{action::ActionErrorKind, response_file::ResponseFileError};
+		use std::path::PathBuf;
+
+		/// Temporary directory removed when dropped
+		struct TempDir(PathBuf);
+
+		impl Drop for TempDir {
+			fn drop(&mut self) {
+				let _ = std::fs::remove_dir_all(&self.0);
+			}
+		}
+
+		let dir =
+			TempDir(std::env::temp_dir().join(format!("combu_run_response_{}", std::process::id())));
+		std::fs::create_dir_all(&dir.0).unwrap();
+		let path = dir.0.join("args.txt");
+		std::fs::write(&path, "sub # sub command\n--name 'hello world'\nrest\n").unwrap();
+
+		let root = Command::with_name("root")
+			.response_files(true)
+			.common_flag(Flag::new_string("name"))
+			.action(|_, _| panic!("root action must not run"))
+			.sub_command(Command::with_name("sub").action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("name", &cmd),
+					Some(FlagValue::String("hello world".into()))
+				);
+				assert_eq!(
+					c.args,
+					VecDeque::from(vec!["rest".to_owned(), "last".to_owned()])
+				);
+				done!()
+			}));
+
+		let arg = format!("@{}", path.display());
+		assert!(
+			root
+				.clone()
+				.run(cnv_arg(vec!["exe_path", &arg, "last"]))
+				.is_ok()
+		);
+
+		let missing = dir.0.join("missing.txt");
+		let arg = format!("@{}", missing.display());
+		match root.run(cnv_arg(vec!["exe_path", &arg])) {
+			Err(err) => {
+				assert!(matches!(err.kind, ActionErrorKind::InvalidResponseFile));
+				let related = err.related_error.as_ref().unwrap();
+				assert!(matches!(
+					related.downcast_ref::<ResponseFileError>(),
+					Some(ResponseFileError::Unreadable(path, _)) if *path == missing
+				));
+			}
+			Ok(_) => panic!("unreadable response file must be error"),
+		}
+	}
+
+	#[test]
+	fn run_with_validators() {
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_int("port").range(1, 65535))
+			.local_flag(Flag::new_float("ratio").max(1.0))
+			.local_flag(Flag::new_string("id").min_len(2).max_len(4))
+			.local_flag(Flag::new_list("level", FlagType::Int).min(0))
+			// 2^53（f64では2^53 + 1と区別できない）
+			.local_flag(Flag::new_u64("size").max(u64::pow(2, 53)))
+			// 型の異なる上下限も比較でき、比較できない場合は検証に失敗する
+			.local_flag(Flag::new_uint("workers").min(1.5))
+			.local_flag(Flag::new_int("retry").max("many"))
+			.local_flag(
+				Flag::new_string("name").validator(|val| match val.get_string() {
+					Some(name) if name.chars().all(char::is_alphanumeric) => Ok(()),
+					_ => Err(String::from("name must be alphanumeric")),
+				}),
+			)
+			.action(|_, c| {
+				match c.args.front().map(String::as_str) {
+					Some("valid") => assert!(c.error_info_list.is_empty()),
+					Some("invalid") => {
+						let errors = [
+							("port", "0", "value must be between 1 and 65535"),
+							("ratio", "1.5", "value must be at most 1"),
+							("id", "abcde", "length must be between 2 and 4"),
+							("level", "-1", "value must be at least 0"),
+							(
+								"size",
+								"9007199254740993",
+								"value must be at most 9007199254740992",
+							),
+							("workers", "1", "value must be at least 1.5"),
+							("retry", "1", "value must be at most many"),
+							("name", "a b", "name must be alphanumeric"),
+						]
+						.into_iter()
+						.map(|(name, val, message)| {
+							(
+								MiddleArg::LongFlag(name.into(), FlagValue::String(val.into())),
+								ParseError::ValidationFailed(message.into()),
+								ParseError::None,
+							)
+						})
+						.collect::<Vec<ErrorInfo>>();
+						assert_eq!(c.error_info_list, Vector::from(errors));
+						assert!(gen_error_description(c.first_error().unwrap()).ends_with(
+							"The value 0 of the flag --port is invalid: value must be between 1 and 65535."
+						));
+					}
+					args => panic!("unexpected args: {args:?}"),
+				}
+				done!()
+			});
+
+		let _ = root.clone().run(cnv_arg(vec![
+			"exe_path",
+			"valid",
+			"--port",
+			"80",
+			"--ratio",
+			"1",
+			"--id",
+			"ab",
+			"--level",
+			"0",
+			"--size",
+			"9007199254740992",
+			"--workers",
+			"2",
+			"--name",
+			"abc",
+		]));
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"invalid",
+			"--port",
+			"0",
+			"--ratio",
+			"1.5",
+			"--id",
+			"abcde",
+			"--level",
+			"1",
+			"--level=-1",
+			"--size",
+			"9007199254740993",
+			"--workers",
+			"1",
+			"--retry",
+			"1",
+			"--name",
+			"a b",
+		]));
+	}
+
+	#[test]
+	fn run_with_extended_types() {
+		use std::{
+			net::{IpAddr, Ipv4Addr, SocketAddr},
+			path::PathBuf,
+			time::Duration,
+		};
+
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_uint("workers"))
+			.local_flag(Flag::new_i64("offset"))
+			.local_flag(Flag::new_u64("seed"))
+			.local_flag(Flag::new_path("out"))
+			.local_flag(Flag::new_duration("timeout"))
+			.local_flag(Flag::new_byte_size("cache"))
+			.local_flag(Flag::new_ip_addr("host"))
+			.local_flag(Flag::new_socket_addr("listen"))
+			.local_flag(Flag::new_char("sep"))
+			.local_flag(Flag::new_uint("bad"))
+			.action(|cmd, c| {
+				let get = |name: &str| c.get_flag_value_of(name, &cmd).unwrap();
+				assert_eq!(get("workers").get_uint(), Some(4));
+				assert_eq!(get("offset").get_i64(), Some(-3_000_000_000));
+				assert_eq!(get("seed").get_u64(), Some(u64::MAX));
+				assert_eq!(
+					get("out").get_path(),
+					Some(&PathBuf::from("target/out.txt"))
+				);
+				assert_eq!(
+					get("timeout").get_duration(),
+					Some(Duration::from_secs(5400))
+				);
+				assert_eq!(get("cache").get_byte_size(), Some(512 * 1024 * 1024));
+				assert_eq!(
+					get("host").get_ip_addr(),
+					Some(IpAddr::V4(Ipv4Addr::LOCALHOST))
+				);
+				assert_eq!(
+					get("listen").get_socket_addr(),
+					Some(SocketAddr::from(([127, 0, 0, 1], 8080)))
+				);
+				assert_eq!(get("sep").get_char(), Some(','));
+				assert_eq!(get("bad"), FlagValue::UInt(0));
+				assert_eq!(
+					c.error_info_list,
+					Vector::from(vec![(
+						MiddleArg::LongFlag("bad".into(), FlagValue::Invalid("-1".into())),
+						ParseError::InvalidLong("bad".into()),
+						ParseError::NotParsed
+					)])
+				);
+				done!()
+			});
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"--workers",
+			"4",
+			"--offset=-3000000000",
+			"--seed",
+			"18446744073709551615",
+			"--out",
+			"target/out.txt",
+			"--timeout",
+			"1h30m",
+			"--cache",
+			"512MiB",
+			"--host",
+			"127.0.0.1",
+			"--listen",
+			"127.0.0.1:8080",
+			"--sep",
+			",",
+			"--bad=-1",
+		]));
+
+		let duration = |val: &str| FlagType::Duration.get_value_from_string(val.into());
+		assert_eq!(duration("10"), FlagValue::Duration(Duration::from_secs(10)));
+		assert_eq!(
+			duration("1.5h"),
+			FlagValue::Duration(Duration::from_secs(5400))
+		);
+		assert_eq!(
+			duration("1m 500ms"),
+			FlagValue::Duration(Duration::from_millis(60500))
+		);
+		assert_eq!(
+			duration("2d"),
+			FlagValue::Duration(Duration::from_secs(172_800))
+		);
+		assert_eq!(duration("10x"), FlagValue::Invalid("10x".into()));
+		assert_eq!(duration(""), FlagValue::Invalid("".into()));
+		assert_eq!(
+			FlagValue::Duration(Duration::from_millis(5_400_250)).get_string_value(),
+			"1h30m250ms"
+		);
+		let size = |val: &str| FlagType::ByteSize.get_value_from_string(val.into());
+		assert_eq!(size("512"), FlagValue::ByteSize(512));
+		assert_eq!(size("10kb"), FlagValue::ByteSize(10_000));
+		assert_eq!(size("1.5 KiB"), FlagValue::ByteSize(1536));
+		assert_eq!(size("2G"), FlagValue::ByteSize(2_000_000_000));
+		assert_eq!(size("1XB"), FlagValue::Invalid("1XB".into()));
+		assert_eq!(
+			FlagType::Char.get_value_from_string("ab".into()),
+			FlagValue::Invalid("ab".into())
+		);
+		assert_eq!(
+			FlagType::IpAddr.get_value_from_string("::1".into()),
+			FlagValue::IpAddr("::1".parse().unwrap())
+		);
+		assert_eq!(
+			presets::func::type_suffix(&FlagType::list(FlagType::Duration)),
+			" <duration>..."
+		);
+		assert_eq!(presets::func::type_suffix(&FlagType::SocketAddr), " <addr>");
+	}
+
+	#[test]
+	fn run_with_map_flags() {
+		use crate::flag::DuplicateKeys;
+		use std::collections::BTreeMap;
+
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_map("define").short_alias('D'))
+			.local_flag(
+				Flag::new_map("header")
+					.map_separator(':')
+					.duplicate_keys(DuplicateKeys::FirstWins),
+			)
+			.common_flag(Flag::new_map("label").duplicate_keys(DuplicateKeys::Error))
+			.action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("define", &cmd),
+					Some(FlagValue::Map(vec![
+						("opt.level".into(), "2".into()),
+						("debug".into(), "true=yes".into())
+					]))
+				);
+				assert_eq!(
+					c.get_map_of("define", &cmd),
+					Some(BTreeMap::from([
+						("debug".to_owned(), "true=yes".to_owned()),
+						("opt.level".to_owned(), "2".to_owned())
+					]))
+				);
+				assert_eq!(
+					c.get_map_of("header", &cmd),
+					Some(BTreeMap::from([("accept".to_owned(), "json".to_owned())]))
+				);
+				assert_eq!(
+					c.get_flag_value_of("label", &cmd),
+					Some(FlagValue::Map(vec![("env".into(), "dev".into())]))
+				);
+				assert_eq!(c.get_map_of("missing", &cmd), None);
+				assert_eq!(
+					c.error_info_list,
+					Vector::from(vec![
+						(
+							MiddleArg::LongFlag("header".into(), FlagValue::Invalid("broken".into())),
+							ParseError::InvalidLong("header".into()),
+							ParseError::NotParsed
+						),
+						(
+							MiddleArg::LongFlag("label".into(), FlagValue::String("env=prod".into())),
+							ParseError::DuplicateKey("env".into()),
+							ParseError::None
+						)
+					])
+				);
+				assert!(
+					gen_error_description(c.error_info_list.inner().as_ref().unwrap().last().unwrap())
+						.ends_with(
+							"The key env of the flag --label is specified more than once (env=prod)."
+						)
+				);
+				done!()
+			});
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"-D",
+			"opt.level=3",
+			"--define=debug=true=yes",
+			"--header",
+			"accept:json",
+			"--label",
+			"env=dev",
+			"-D",
+			"opt.level=2",
+			"--header",
+			"accept:xml",
+			"--label=env=prod",
+			"--header=broken",
+		]));
+	}
+
+	#[test]
+	fn run_with_value_delimiter() {
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_list("tags", FlagType::String).value_delimiter(','))
+			.local_flag(
+				Flag::new_list("nums", FlagType::Int)
+					.short_alias('n')
+					.value_delimiter(','),
+			)
+			.common_flag(Flag::new_list("ports", FlagType::UInt).value_delimiter(';'))
+			.action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("tags", &cmd),
+					Some(FlagValue::List(vec![
+						FlagValue::String("a".into()),
+						FlagValue::String("b".into()),
+						FlagValue::String("c".into())
+					]))
+				);
+				assert_eq!(
+					c.get_flag_value_of("nums", &cmd),
+					Some(FlagValue::List(vec![FlagValue::Int(1), FlagValue::Int(2)]))
+				);
+				assert_eq!(
+					c.get_flag_value_of("ports", &cmd),
+					Some(FlagValue::List(vec![
+						FlagValue::UInt(80),
+						FlagValue::UInt(443)
+					]))
+				);
+				assert_eq!(
+					c.error_info_list,
+					Vector::from(vec![
+						(
+							MiddleArg::LongFlag("nums".into(), FlagValue::Invalid("3,x,5".into())),
+							ParseError::InvalidElement(1, "x".into()),
+							ParseError::InvalidLong("nums".into())
+						),
+						(
+							MiddleArg::LongFlag("ports".into(), FlagValue::String("8080;-1".into())),
+							ParseError::InvalidElement(1, "-1".into()),
+							ParseError::InvalidLong("ports".into())
+						),
+						// 空白区切りの形式でも同じく要素が検証される
+						(
+							MiddleArg::LongFlag("nums".into(), FlagValue::Invalid("7,y".into())),
+							ParseError::InvalidElement(1, "y".into()),
+							ParseError::InvalidLong("nums".into())
+						),
+						(
+							MiddleArg::LongFlag("nums".into(), FlagValue::Invalid("x,8".into())),
+							ParseError::InvalidElement(0, "x".into()),
+							ParseError::InvalidLong("nums".into())
+						)
+					])
+				);
+				assert!(c.args.is_empty());
+				assert!(gen_error_description(c.first_error().unwrap()).ends_with(
+					"The element x at position 2 of the value 3,x,5 is invalid for the flag --nums."
+				));
+				done!()
+			});
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"--tags",
+			"a,b",
+			"--nums",
+			"1",
+			"--tags=c",
+			"--nums=3,x,5",
+			"--ports",
+			"80;443",
+			"-n",
+			"2",
+			"--ports=8080;-1",
+			"--nums",
+			"7,y",
+			"-n",
+			"x,8",
+		]));
+	}
+
+	#[test]
+	fn run_with_optional_values() {
+		let check = |cmd: Command, c: Context| {
+			assert_eq!(
+				c.get_flag_value_of("color", &cmd),
+				Some(FlagValue::String("always".into()))
+			);
+			assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(3)));
+			assert_eq!(c.args, cnv_arg(vec!["file", "next"]));
+			done!()
+		};
+		let root = Command::with_name("root")
+			.local_flag(
+				Flag::new_string("color")
+					.short_alias('c')
+					.default_value(FlagValue::String("auto".into()))
+					.default_missing_value(FlagValue::String("always".into())),
+			)
+			.common_flag(
+				Flag::new_int("level")
+					.short_alias('l')
+					.default_missing_value(FlagValue::Int(3)),
+			);
+		let _ = root
+			.clone()
+			.action(check)
+			.run(cnv_arg(vec!["exe_path", "--color", "file", "-l", "next"]));
+		// 前にあるフラグがサブコマンド探索中に解析される場合
+		let _ = root
+			.clone()
+			.action(check)
+			.sub_command(Command::with_name("sub").action(|_, _| panic!("not sub")))
+			.run(cnv_arg(vec!["exe_path", "--color", "-l", "file", "next"]));
+
+		let _ = root
+			.clone()
+			.action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("color", &cmd),
+					Some(FlagValue::String("never".into()))
+				);
+				assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(5)));
+				assert_eq!(c.args, cnv_arg(vec!["file"]));
+				done!()
+			})
+			.run(cnv_arg(vec!["exe_path", "--color=never", "-l=5", "file"]));
+		// ショートフラグに続く文字列は値として扱う
+		let attached = |cmd: Command, c: Context| {
+			assert_eq!(
+				c.get_flag_value_of("color", &cmd),
+				Some(FlagValue::String("never".into()))
+			);
+			assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(5)));
+			assert_eq!(c.args, cnv_arg(vec!["file"]));
+			assert!(c.error_info_list.is_empty());
+			done!()
+		};
+		let _ = root
+			.clone()
+			.action(attached)
+			.run(cnv_arg(vec!["exe_path", "-cnever", "-l5", "file"]));
+		let _ = root
+			.clone()
+			.action(attached)
+			.sub_command(Command::with_name("sub").action(|_, _| panic!("not sub")))
+			.run(cnv_arg(vec!["exe_path", "-cnever", "-l5", "file"]));
+		let _ = root
+			.clone()
+			.action(|_, c| {
+				assert_eq!(
+					c.error_info_list,
+					Vector::from(vec![(
+						MiddleArg::ShortFlag("l".into(), FlagValue::String("x".into())),
+						ParseError::NoExistShort(0),
+						ParseError::InvalidShort(0, "level".into()),
+					)])
+				);
+				done!()
+			})
+			.run(cnv_arg(vec!["exe_path", "-lx", "file"]));
+		let _ = root
+			.action(|cmd, c| {
+				assert_eq!(
+					c.get_flag_value_of("color", &cmd),
+					Some(FlagValue::String("auto".into()))
+				);
+				assert_eq!(c.get_flag_value_of("level", &cmd), Some(FlagValue::Int(0)));
+				done!()
+			})
+			.run(cnv_arg(vec!["exe_path", "file"]));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn run_with_non_utf8_args() {
+		use std::{
+			ffi::{OsStr, OsString},
+			os::unix::ffi::OsStrExt,
+		};
+
+		fn os(bytes: &[u8]) -> OsString {
+			OsStr::from_bytes(bytes).to_owned()
+		}
+		fn raw_args() -> Vec<OsString> {
+			vec![
+				os(b"exe_path"),
+				os(b"sub"),
+				os(b"--name"),
+				os(b"a\xffb"),
+				os(b"--path=/tmp/\xfe"),
+				os(b"--count=\xff"),
+				os(b"--sep=\xff"),
+				os(b"f\xff"),
+			]
+		}
+		let root = Command::with_name("root").sub_command(
+			Command::with_name("sub")
+				.local_flag(Flag::new_string("name"))
+				.local_flag(Flag::new_path("path"))
+				.local_flag(Flag::new_int("count"))
+				.local_flag(Flag::new_char("sep"))
+				.positional(Positional::new_string("file"))
+				.action(|cmd, c| {
+					assert_eq!(c.raw_os_args(), raw_args());
+					assert_eq!(
+						c.get_flag_value_of("name", &cmd),
+						Some(FlagValue::String("a\u{FFFD}b".into()))
+					);
+					assert_eq!(c.get_os_flag_value_of("name", &cmd), Some(os(b"a\xffb")));
+					assert_eq!(
+						c.get_flag_value_of("path", &cmd),
+						Some(FlagValue::Path(os(b"/tmp/\xfe").into()))
+					);
+					assert_eq!(c.get_os_positional_value_of("file"), Some(os(b"f\xff")));
+					assert_eq!(c.get_os_flag_value_of("count", &cmd), None);
+					assert_eq!(
+						c.error_info_list,
+						Vector::from(vec![
+							(
+								MiddleArg::LongFlag("count".into(), FlagValue::Invalid("\u{FFFD}".into())),
+								ParseError::NonUtf8Value,
+								ParseError::InvalidLong("count".into())
+							),
+							(
+								MiddleArg::LongFlag("sep".into(), FlagValue::String("\u{FFFD}".into())),
+								ParseError::NonUtf8Value,
+								ParseError::InvalidLong("sep".into())
+							)
+						])
+					);
+					assert!(
+						gen_error_description(c.first_error().unwrap())
+							.ends_with("The value \u{FFFD} of the flag --count is not valid UTF-8.")
+					);
+					done!()
+				}),
+		);
+		let _ = root.run(raw_args());
+
+		// 変換結果が同じになる引数や、置換文字を含む正しい引数も元の値を取り出せる
+		fn same_lossy_args() -> Vec<OsString> {
+			vec![
+				os(b"exe_path"),
+				os(b"--first"),
+				os(b"a\xffb"),
+				os(b"--second=a\xfeb"),
+				os("--label=a\u{FFFD}b".as_bytes()),
+				os(b"--list=x\xff"),
+				os(b"--list=x\xfe"),
+				os(b"f\xff"),
+				os(b"f\xfe"),
+				os(b"--"),
+				os(b"t\xfe"),
+			]
+		}
+		let _ = Command::with_name("root")
+			.local_flag(Flag::new_string("first"))
+			.local_flag(Flag::new_string("second"))
+			.local_flag(Flag::new_string("label"))
+			.local_flag(Flag::new_list("list", FlagType::String))
+			.positional(Positional::new_string("files").variadic(true))
+			.action(|cmd, c| {
+				assert_eq!(c.raw_os_args(), same_lossy_args());
+				assert_eq!(c.get_os_flag_value_of("first", &cmd), Some(os(b"a\xffb")));
+				assert_eq!(c.get_os_flag_value_of("second", &cmd), Some(os(b"a\xfeb")));
+				assert_eq!(
+					c.get_flag_value_of("second", &cmd),
+					Some(FlagValue::String("a\u{FFFD}b".into()))
+				);
+				assert_eq!(
+					c.get_os_flag_value_of("label", &cmd),
+					Some(OsString::from("a\u{FFFD}b"))
+				);
+				assert_eq!(
+					c.get_os_flag_values_of("list", &cmd),
+					Some(vec![os(b"x\xff"), os(b"x\xfe")])
+				);
+				assert_eq!(
+					c.get_os_positional_values_of("files"),
+					Some(vec![os(b"f\xff"), os(b"f\xfe"), os(b"t\xfe")])
+				);
+				assert_eq!(c.os_trailing_args(), vec![os(b"t\xfe")]);
+				assert!(c.error_info_list.is_empty());
+				done!()
+			})
+			.run(same_lossy_args());
+	}
+
+	#[test]
+	fn run_with_typed_access() {
+		use crate::{FlagAccessError, FromFlagValue, action::bundle::Bundle};
+		use std::{net::IpAddr, path::PathBuf};
+
+		#[derive(Debug, PartialEq)]
+		enum Level {
+			Low,
+			High,
+		}
+
+		impl FromFlagValue for Level {
+			fn from_flag_value(val: &FlagValue) -> Option<Self> {
+				match val.get_string()?.as_str() {
+					"low" => Some(Level::Low),
+					"high" => Some(Level::High),
+					_ => None,
+				}
+			}
+
+			fn type_name() -> String {
+				String::from("level")
+			}
+		}
+
+		let root = Command::with_name("root")
+			.local_flag(Flag::new_bool("reverse"))
+			.local_flag(Flag::new_int("count"))
+			.local_flag(Flag::new_string("name"))
+			.local_flag(Flag::new_path("out"))
+			.local_flag(Flag::new_ip_addr("ip"))
+			.local_flag(Flag::new_list("tags", FlagType::String))
+			.local_flag(Flag::new_byte_size("size"))
+			.common_flag(Flag::new_string("level").default_value(FlagValue::String("low".into())))
+			.action(|cmd, c| {
+				assert_eq!(c.get::<bool>("reverse", &cmd), Ok(true));
+				assert_eq!(c.get::<isize>("count", &cmd), Ok(3));
+				assert_eq!(c.get::<u8>("count", &cmd), Ok(3));
+				assert_eq!(c.get::<f64>("count", &cmd).ok(), None);
+				assert_eq!(c.get::<String>("name", &cmd), Ok("x".to_owned()));
+				assert_eq!(c.get::<PathBuf>("out", &cmd), Ok(PathBuf::from("/tmp/o")));
+				assert_eq!(c.get::<Option<IpAddr>>("ip", &cmd), Ok(None));
+				assert_eq!(
+					c.get::<IpAddr>("ip", &cmd),
+					Err(FlagAccessError::Unset("ip".into()))
+				);
+				assert_eq!(
+					c.get::<Vec<String>>("tags", &cmd),
+					Ok(vec!["a".to_owned(), "b".to_owned()])
+				);
+				assert_eq!(c.get::<Level>("level", &cmd), Ok(Level::High));
+				// バイト数は整数として取得できる
+				assert_eq!(c.get::<u64>("size", &cmd), Ok(512 * 1024 * 1024));
+				assert_eq!(c.get::<u8>("size", &cmd).ok(), None);
+				assert_eq!(
+					c.get::<bool>("none", &cmd),
+					Err(FlagAccessError::Unknown("none".into()))
+				);
+				let err = c.get::<bool>("count", &cmd).unwrap_err();
+				assert_eq!(
+					err,
+					FlagAccessError::WrongType {
+						name: "count".into(),
+						expected: "bool".into(),
+						value: FlagValue::Int(3)
+					}
+				);
+				assert_eq!(
+					err.to_string(),
+					"value Int(3) of flag --count cannot be taken as bool"
+				);
+				// 型名は各実装が宣言したものを使う
+				assert!(matches!(
+					c.get::<Vec<u8>>("tags", &cmd),
+					Err(FlagAccessError::WrongType { expected, .. }) if expected == "list of u8"
+				));
+				assert!(matches!(
+					c.get::<Option<Level>>("name", &cmd),
+					Err(FlagAccessError::WrongType { expected, .. }) if expected == "level"
+				));
+				let bundle = Bundle::new(c, cmd);
+				assert_eq!(
+					bundle.get::<Vec<String>>("tags").map(|tags| tags.len()),
+					Ok(2)
+				);
+				assert_eq!(bundle.get::<Level>("level"), Ok(Level::High));
+				done!()
+			});
+		let _ = root.run(cnv_arg(vec![
+			"exe_path",
+			"--reverse",
+			"--count",
+			"3",
+			"--name",
+			"x",
+			"--tags",
+			"a",
+			"--out",
+			"/tmp/o",
+			"--tags",
+			"b",
+			"--level",
+			"high",
+			"--size",
+			"512MiB",
+		]));
+	}
+
+	#[test]
+	fn run_with_strictness() {
+		use crate::action::ActionErrorKind;
+
+		let root = Command::with_name("root")
+			.strictness(Strictness::Strict)
+			.common_flag(Flag::new_bool("verbose"))
+			.action(|_, c| {
+				assert!(c.error_info_list.is_empty());
+				done!()
+			})
+			.sub_command(Command::with_name("sub").action(|_, _| {
+				panic!("action of strict sub command must not run");
+			}))
+			.sub_command(
+				Command::with_name("lenient")
+					.strictness(Strictness::Lenient)
+					.action(|_, c| {
+						assert_eq!(c.error_info_list.len(), 1);
+						done!()
+					}),
+			)
+			.sub_command(
+				Command::with_name("noisy")
+					.strictness(Strictness::StrictWithHelp)
+					.action(|_, _| {
+						panic!("action of strict sub command must not run");
+					}),
+			);
+
+		assert!(
+			root
+				.clone()
+				.run(cnv_arg(vec!["exe_path", "--verbose", "arg"]))
+				.is_ok()
+		);
+		match root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "--unknown", "arg"]))
+		{
+			Err(err) => {
+				assert!(matches!(err.kind, ActionErrorKind::ParseFailed));
+				// ルートコマンドでもサブコマンドと同じく出力される
+				assert!(err.printed);
+				assert_eq!(err.context.error_info_list.len(), 1);
+				assert_eq!(err.to_string(), "The flag --unknown is an unknown flag.");
+			}
+			Ok(_) => panic!("strict command must return error"),
+		}
+		match root
+			.clone()
+			.run(cnv_arg(vec!["exe_path", "sub", "--unknown"]))
+		{
+			Err(err) => {
+				assert!(matches!(err.kind, ActionErrorKind::ParseFailed));
+				assert!(err.printed);
+			}
+			Ok(_) => panic!("strictness must be inherited"),
+		}
+		assert!(
+			root
+				.clone()
+				.run(cnv_arg(vec!["exe_path", "lenient", "--unknown"]))
+				.is_ok()
+		);
+		match root.run(cnv_arg(vec!["exe_path", "noisy", "-x"])) {
+			Err(err) => {
+				assert!(matches!(err.kind, ActionErrorKind::ParseFailed));
+				assert!(err.printed);
+			}
+			Ok(_) => panic!("strict command must return error"),
+		}
+	}
+
+	#[test]
+	fn run_strict_with_help() {
+		use crate::action::ActionErrorKind;
+
+		let root = Command::with_name("root")
+			.strictness(Strictness::Strict)
+			.common_flag(crate::flag::presets::help_flag())
+			.local_flag(Flag::new_string("name").required(true))
+			.local_flag(Flag::new_bool("file"))
+			.local_flag(Flag::new_bool("stdin"))
+			.group(FlagGroup::exactly_one(vec!["file", "stdin"]))
+			.positional(Positional::new_string("target"))
+			.action(|cmd, c| {
+				assert!(c.is_flag_true("help", &cmd));
+				assert!(c.error_info_list.is_empty());
+				done!()
+			});
+
+		// ヘルプ表示時は必須の値や組み合わせが足りなくてもエラーにならない
+		assert!(
+			root
+				.clone()
+				.run(cnv_arg(vec!["exe_path", "--help"]))
+				.is_ok()
+		);
+		assert!(
+			root
+				.clone()
+				.strictness(Strictness::StrictWithHelp)
+				.run(cnv_arg(vec!["exe_path", "-h"]))
+				.is_ok()
+		);
+		match root.run(cnv_arg(vec!["exe_path"])) {
+			Err(err) => {
+				assert!(matches!(err.kind, ActionErrorKind::ParseFailed));
+				assert_eq!(err.context.error_info_list.len(), 3);
+			}
+			Ok(_) => panic!("strict command must return error"),
+		}
+	}
+
+	#[test]
+	fn parse_without_running_action() {
+		use super::{ParseFailure, ParseOutcome};
+		use crate::response_file::ResponseFileError;
+
+		let root = Command::with_name("root")
+			.common_flag(Flag::new_bool("verbose").short_alias('v'))
+			.action(|_, _| panic!("action must not run in parse"))
+			.sub_command(
+				Command::with_name("sub")
+					.local_flag(Flag::new_int("num"))
+					.positional(Positional::new("name", FlagType::String))
+					.action(|_, _| panic!("action must not run in parse"))
+					.sub_command(
+						Command::with_name("leaf")
+							.strictness(Strictness::StrictWithHelp)
+							.action(|_, _| panic!("action must not run in parse")),
+					),
+			)
+			.sub_command(Command::with_name("empty"));
+
+		let outcome = root
+			.parse(cnv_arg(vec![
+				"exe_path", "-v", "sub", "--num", "3", "alice",
+			]))
+			.unwrap();
+		assert_eq!(outcome.routes, vec!["root".to_owned()]);
+		assert_eq!(outcome.path, vec!["root".to_owned(), "sub".to_owned()]);
+		assert_eq!(
+			outcome.local_flags_values,
+			vec![("num".to_owned(), FlagValue::Int(3))]
+		);
+		assert_eq!(
+			outcome.common_flags_values,
+			vec![("verbose".to_owned(), FlagValue::None)]
+		);
+		assert_eq!(outcome.args, VecDeque::from(vec!["alice".to_owned()]));
+		assert_eq!(
+			outcome.positional_values,
+			vec![("name".to_owned(), FlagValue::String("alice".to_owned()))]
+		);
+		assert!(outcome.error_info_list.is_empty());
+		assert_eq!(outcome.context.get::<isize>("num", &outcome.command), Ok(3));
+		assert_eq!(
+			outcome.context.get::<bool>("verbose", &outcome.command),
+			Ok(true)
+		);
+		// 結果のコンテキストはパース専用の状態を引き継がない
+		assert!(!outcome.context.parse_only);
+
+		let ParseOutcome {
+			path,
+			error_info_list,
+			..
+		} = root
+			.parse(cnv_arg(vec!["exe_path", "--unknown", "arg"]))
+			.unwrap();
+		assert_eq!(path, vec!["root".to_owned()]);
+		assert_eq!(error_info_list.len(), 1);
+
+		match root.parse(cnv_arg(vec!["exe_path", "sub", "leaf", "-x"])) {
+			Err(ParseFailure::ParseFailed(outcome)) => {
+				assert_eq!(outcome.path, vec!["root", "sub", "leaf"]);
+				assert_eq!(outcome.error_info_list.len(), 1);
+			}
+			other => panic!("unexpected result: {other:?}"),
+		}
+		match root.parse(cnv_arg(vec!["exe_path", "-v", "empty"])) {
+			Err(err @ ParseFailure::NoActionRegistered(_)) => {
+				assert_eq!(err.to_string(), "root empty does not have its own action.");
+			}
+			other => panic!("unexpected result: {other:?}"),
+		}
+		let missing = std::env::temp_dir().join("combu_parse_missing_response_file.txt");
+		match root.clone().response_files(true).parse(vec![
+			"exe_path".to_owned(),
+			format!("@{}", missing.display()),
+		]) {
+			Err(ParseFailure::InvalidResponseFile(ResponseFileError::Unreadable(path, _))) => {
+				assert_eq!(path, missing);
+			}
+			other => panic!("unexpected result: {other:?}"),
+		}
+		// パース以外のエラーは`Unexpected`になる
+		let err = ActionError::without_related_error(
+			"custom error".to_owned(),
+			ActionErrorKind::Custom,
+			root,
+			Context::from(cnv_arg(vec!["exe_path"])),
+		);
+		match ParseFailure::from(err) {
+			ParseFailure::Unexpected(description) => assert_eq!(description, "custom error"),
+			other => panic!("unexpected result: {other:?}"),
+		}
+	}
+
+	#[test]
+	fn test_flag_type() {
+		let arg = vec![
+			"exe_path".to_string(),
+			"sub".to_string(),
+			"leaf".to_string(),
+			"test".to_string(),
+			"--common".to_string(),
+			"test".to_string(),
+			"-c".to_string(),
+			"--local".to_string(),
+			"-y".to_string(),
+			"-i".to_string(),
+			"111".to_string(),
+			"--float".to_string(),
+			"10.0".to_string(),
+		];
+
+		let leaf = Command::with_name("leaf")
+			.action(|cmd, c| {
+				println!("sub_action: {c:?}");
+				let raw_args = vec![
+					"exe_path".to_string(),
+					"sub".to_string(),
+					"leaf".to_string(),
+					"test".to_string(),
+					"--common".to_string(),
+					"test".to_string(),
+					"-c".to_string(),
+					"--local".to_string(),
+					"-y".to_string(),
+					"-i".to_string(),
+					"111".to_string(),
+					"--float".to_string(),
 					"10.0".to_string(),
 				];
 				let expect_args = VecDeque::from(vec!["test".to_string(), "test".to_owned()]);
@@ -2194,7 +4964,7 @@ pub mod presets {
 
 	/// function presets for command construction.
 	pub mod func {
-		use crate::{FlagType, action_result};
+		use crate::{FlagType, action_result, vector::flag::FlagSearch};
 
 		use super::super::{Command, Context, Flag, Vector};
 		use std::cmp::max;
@@ -2230,6 +5000,7 @@ pub mod presets {
 					if !$nl_list.contains(&&f.name) {
 						$help.push_str(if all_dup { " --" } else { ", --" });
 						all_dup = false;
+						$help.push_str(negation_mark(f));
 						$help.push_str(&f.name);
 						$nl_list.push(&f.name);
 					}
@@ -2258,7 +5029,11 @@ pub mod presets {
 							);
 						}
 						$help.push('\t');
+						$help.push_str(required_mark(f));
 						$help.push_str(&f.description);
+						$help.push_str(&limit_mark(f));
+						$help.push_str(&missing_value_mark(f));
+						$help.push_str(&env_mark(f));
 						$help.push_str(&$suffix);
 						$help.push('\n');
 					}
@@ -2283,7 +5058,7 @@ pub mod presets {
 			} else {
 				help += "   ";
 			}
-			help = help + " --" + &flag.name;
+			help = help + " --" + negation_mark(flag) + &flag.name;
 			if let Vector(Some(long_alias)) = &flag.long_alias {
 				help = long_alias.iter().fold(help, |help, l| {
 					//ロングフラグ出力
@@ -2297,7 +5072,13 @@ pub mod presets {
 				help += &" ".repeat(name_and_alias_min_width - name_and_alias_width);
 			}
 
-			help + "\t" + &flag.description + "\n"
+			help
+				+ "\t" + required_mark(flag)
+				+ &flag.description
+				+ &limit_mark(flag)
+				+ &missing_value_mark(flag)
+				+ &env_mark(flag)
+				+ "\n"
 		}
 		/// Preset of help function(compact version)
 		pub fn help_with_alias_dedup(cmd: &Command, ctx: &Context) -> String {
@@ -2309,8 +5090,12 @@ pub mod presets {
 				help.push_str(description);
 				help.push_str("\n\n");
 			}
-			help += &format!("Usage:\n{}{}\n", &indent, cmd.usage);
+			help += &format!("Usage:\n{}{}\n", &indent, usage_with_positionals(cmd));
 			let name_and_alias_min_width = 12;
+			if cmd.positionals.has_at_least_one() {
+				help.push('\n');
+				help.push_str(&positionals_help(cmd, &indent, name_and_alias_min_width));
+			}
 			let routes = ctx.routes.clone();
 			let mut routes: Vec<String> = if routes.len() < ctx.depth() {
 				let mut routes: Vec<String> = routes.into();
@@ -2411,6 +5196,8 @@ pub mod presets {
 				}
 			}
 
+			help.push_str(&flag_relations_help(cmd, ctx, &indent));
+
 			// サブコマンド出力
 			if let Vector(Some(sub)) = &cmd.sub {
 				let mut iter = sub.iter();
@@ -2488,13 +5275,150 @@ pub mod presets {
 			help
 		}
 
+		/// Returns usage of command. If command has declared positional args, their usage (such as `<name> [name] <name>...`) is appended.
+		pub fn usage_with_positionals(cmd: &Command) -> String {
+			match &cmd.positionals {
+				Vector(Some(positionals)) => positionals.iter().fold(cmd.usage.clone(), |usage, p| {
+					if usage.is_empty() {
+						p.usage_str()
+					} else {
+						usage + " " + &p.usage_str()
+					}
+				}),
+				Vector(None) => cmd.usage.clone(),
+			}
+		}
+
+		/// Returns help of declared positional args. `name_min_width` means min width of positional args' name field.
+		/// 宣言済み位置引数のヘルプを返す。名前表示部分の最小幅を`name_min_width`で指定する
+		pub fn positionals_help(cmd: &Command, indent: &str, name_min_width: usize) -> String {
+			let mut help = String::from("Args: \n");
+			if let Vector(Some(positionals)) = &cmd.positionals {
+				let width = positionals
+					.iter()
+					.map(|p| p.usage_str().len())
+					.fold(name_min_width, max);
+				for p in positionals {
+					help.push_str(indent);
+					help.push_str(&format!("{:width$}", p.usage_str()));
+					help.push('\t');
+					help.push_str(&p.description);
+					help.push('\n');
+				}
+			}
+			help
+		}
+
 		/// Add type suffix for flag help
 		pub fn add_type_suffix(to: String, ft: &FlagType) -> String {
+			to + &type_suffix(ft)
+		}
+
+		/// Returns negation mark "[no-]" if flag can be negated by `--no-<name>`, otherwise returns empty str.
+		pub fn negation_mark(flag: &Flag) -> &'static str {
+			if flag.is_negatable() { "[no-]" } else { "" }
+		}
+
+		/// Returns summary of conflicts and requirements declared by flags available in command, and constraints of command's flag groups.
+		/// If there is nothing to summarize, returns empty string.
+		/// コマンドで使えるフラグの競合・依存関係およびフラググループの制約のまとめを返す。
+		pub fn flag_relations_help(cmd: &Command, ctx: &Context, indent: &str) -> String {
+			let mut lines: Vec<String> = Vec::new();
+			let mut checked: Vec<&String> = Vec::new();
+			let mut conflicts: Vec<(&String, &String)> = Vec::new();
+			let mut flags = cmd.l_flags.all_flags();
+			flags.append(&mut cmd.c_flags.all_flags());
+			flags.append(&mut ctx.common_flags.all_flags());
+			for flag in flags {
+				if checked.contains(&&flag.name) {
+					continue;
+				}
+				checked.push(&flag.name);
+				if let Vector(Some(conflicts_with)) = &flag.conflicts_with {
+					for other in conflicts_with {
+						if !conflicts.contains(&(other, &flag.name)) {
+							conflicts.push((&flag.name, other));
+							lines.push(format!("--{} conflicts with --{}", flag.name, other));
+						}
+					}
+				}
+				if let Vector(Some(requires)) = &flag.requires {
+					for required in requires {
+						lines.push(format!("--{} requires --{}", flag.name, required));
+					}
+				}
+			}
+			if let Vector(Some(groups)) = &cmd.groups {
+				for group in groups {
+					lines.push(group.constraint_str());
+				}
+			}
+			if lines.is_empty() {
+				return String::new();
+			}
+			lines
+				.iter()
+				.fold(String::from("Constraints: \n"), |help, line| {
+					help + indent + line + "\n"
+				})
+		}
+
+		/// Returns environment variable mark such as `" [env: MYTOOL_TOKEN]"` if flag has environment variable name, otherwise returns empty string.
+		pub fn env_mark(flag: &Flag) -> String {
+			match &flag.env {
+				Some(env) => format!(" [env: {env}]"),
+				None => String::new(),
+			}
+		}
+
+		/// Returns mark of flag's range and length limit such as " [range: 1..=10]" (empty if flag has no limit).
+		pub fn limit_mark(flag: &Flag) -> String {
+			let mut mark = String::new();
+			if let Some(range) = flag.range_str() {
+				mark = mark + " [range: " + &range + "]";
+			}
+			if let Some(length) = flag.length_str() {
+				mark = mark + " [length: " + &length + "]";
+			}
+			mark
+		}
+
+		/// Returns mark of flag's value used when it is inputted without value, such as " [if no value: always]" (empty if flag's value is not optional).
+		pub fn missing_value_mark(flag: &Flag) -> String {
+			match &flag.default_missing_value {
+				Some(val) => format!(" [if no value: {}]", val.clone().get_string_value()),
+				None => String::new(),
+			}
+		}
+
+		/// Returns required mark "(required) " if flag is required, otherwise returns empty str.
+		pub fn required_mark(flag: &Flag) -> &'static str {
+			if flag.required { "(required) " } else { "" }
+		}
+
+		/// Returns type suffix of `ft`.
+		/// Repeatable(list or count) flag's suffix ends with "...", such as " <string>...".
+		pub fn type_suffix(ft: &FlagType) -> String {
 			match &ft {
-				FlagType::Bool => to,
-				FlagType::String => to + " <string>",
-				FlagType::Int => to + " <int>",
-				FlagType::Float => to + " <float>",
+				FlagType::Bool => String::new(),
+				FlagType::String => String::from(" <string>"),
+				FlagType::Int => String::from(" <int>"),
+				FlagType::Float => String::from(" <float>"),
+				FlagType::UInt => String::from(" <uint>"),
+				FlagType::I64 => String::from(" <i64>"),
+				FlagType::U64 => String::from(" <u64>"),
+				FlagType::Path => String::from(" <path>"),
+				FlagType::Duration => String::from(" <duration>"),
+				FlagType::ByteSize => String::from(" <size>"),
+				FlagType::IpAddr => String::from(" <ip>"),
+				FlagType::SocketAddr => String::from(" <addr>"),
+				FlagType::Char => String::from(" <char>"),
+				FlagType::Count => String::from(" ..."),
+				FlagType::Map { separator, .. } => format!(" KEY{separator}VALUE..."),
+				FlagType::List(inner) => match inner.as_ref() {
+					FlagType::Bool => String::from(" ..."),
+					inner => type_suffix(inner) + "...",
+				},
 			}
 		}
 
@@ -2508,7 +5432,11 @@ pub mod presets {
 				help.push_str(description);
 				help.push_str("\n\n");
 			}
-			help += &format!("Usage:\n{}{}\n\n", &indent, cmd.usage);
+			help += &format!("Usage:\n{}{}\n\n", &indent, usage_with_positionals(cmd));
+			if cmd.positionals.has_at_least_one() {
+				help.push_str(&positionals_help(cmd, &indent, 7));
+				help.push('\n');
+			}
 
 			//フラグ処理
 			let l_flags: &Vector<Flag> = &cmd.l_flags;
@@ -2612,6 +5540,8 @@ pub mod presets {
 				help += "\n";
 			}
 
+			help.push_str(&flag_relations_help(cmd, ctx, &indent));
+
 			if let Vector(Some(sub_commands)) = &cmd.sub {
 				help += &format!(
 					"Sub Command{}: \n",
@@ -2727,6 +5657,7 @@ pub mod presets {
 			}
 			let prev_help_len = help.len();
 			help.push_str("--");
+			help.push_str(negation_mark(f));
 			help.push_str(&f.name);
 			if let Vector(Some(long_alias)) = &f.long_alias {
 				for l in long_alias {
@@ -2740,7 +5671,11 @@ pub mod presets {
 				help.push_str(&sp.repeat(nl_width - _nl_width));
 			}
 			help.push_str(pre_d_space);
+			help.push_str(required_mark(f));
 			help.push_str(&f.description);
+			help.push_str(&limit_mark(f));
+			help.push_str(&missing_value_mark(f));
+			help.push_str(&env_mark(f));
 			help.push('\n');
 
 			help
@@ -2756,17 +5691,27 @@ pub mod presets {
 				help.push_str(description);
 				help.push_str("\n\n");
 			}
-			help = help + "Usage:\n" + &indent + &cmd.usage + "\n\n";
+			help = help + "Usage:\n" + &indent + &usage_with_positionals(cmd) + "\n\n";
+			if cmd.positionals.has_at_least_one() {
+				help.push_str(&positionals_help(cmd, &indent, 7));
+				help.push('\n');
+			}
 
 			if cmd.l_flags.len() + cmd.c_flags.len() + ctx.common_flags.sum_of_length() > 0 {
 				// フラグが存在するとき
 				help.push_str("Flags(If exist flags have same alias and specified by user, inputted value will be interpreted as the former flag's value): \n");
 
 				let nl_width = |flag: &Flag| match &flag.long_alias {
-					Vector(None) => flag.name.len() + flag_type_suffix_len(&flag.flag_type),
+					Vector(None) => {
+						flag.name.len()
+							+ negation_mark(flag).len()
+							+ flag_type_suffix_len(&flag.flag_type)
+					}
 					Vector(Some(long_aliases)) => {
 						long_aliases.iter().fold(
-							flag.name.len() + flag_type_suffix_len(&flag.flag_type),
+							flag.name.len()
+								+ negation_mark(flag).len()
+								+ flag_type_suffix_len(&flag.flag_type),
 							|width, long_alias| width + long_alias.len(),
 						) + long_aliases.len() * 4
 					}
@@ -2865,6 +5810,8 @@ pub mod presets {
 				}
 			}
 
+			help.push_str(&flag_relations_help(cmd, ctx, &indent));
+
 			if let Vector(Some(sub_commands)) = &cmd.sub {
 				help += "Sub Command";
 				if sub_commands.len() > 1 {
@@ -2955,7 +5902,7 @@ pub mod presets {
 					if !$nl_list.contains(&&f.name) {
 						$nl_list.push(&f.name);
 						dedup_nl.push(&f.name);
-						nl_width = f.name.len();
+						nl_width = f.name.len() + negation_mark(f).len();
 						alias_exist = true;
 					} else {
 						nl_width = 0;
@@ -2976,12 +5923,7 @@ pub mod presets {
 								nl_width += flag_type_suffix_len(&f.flag_type);
 							}
 							x if x > 1 => {
-								nl_width += match &f.flag_type {
-									FlagType::Bool => x * 4,
-									FlagType::String => x * 4 + 9,
-									FlagType::Int => x * 4 + 6,
-									FlagType::Float => x * 4 + 8,
-								};
+								nl_width += x * 4 + type_suffix(&f.flag_type).len();
 							}
 							_ => {}
 						}
@@ -3007,31 +5949,36 @@ pub mod presets {
 
 		/// Return flag type suffix length
 		fn flag_type_suffix_len(ft: &FlagType) -> usize {
-			match &ft {
-				FlagType::Bool => 2,
-				FlagType::String => 11, // 2 + " <string>"
-				FlagType::Int => 8,     // 2 + " <int>"
-				FlagType::Float => 10,  // 2 + " <float>"
-			}
+			2 + type_suffix(ft).len()
 		}
 
 		/// Add long alias (following prev) to append to.
+		/// `first_mark` is inserted before the first long alias (for example, negation mark "[no-]").
 		/// example: "--a, --b"
 		fn add_long_flags_str_to_prev_flags(
 			append_to: &mut String,
+			first_mark: &str,
 			nl_iter: std::vec::IntoIter<&String>,
 		) {
+			let mut mark = first_mark;
 			for nl in nl_iter {
 				append_to.push_str(", --");
+				append_to.push_str(mark);
 				append_to.push_str(nl);
+				mark = "";
 			}
 		}
 
 		/// Add long alias (main,or formal) to `append_to`.
-		fn add_long_flags_str(append_to: &mut String, mut nl_iter: std::vec::IntoIter<&String>) {
+		fn add_long_flags_str(
+			append_to: &mut String,
+			first_mark: &str,
+			mut nl_iter: std::vec::IntoIter<&String>,
+		) {
 			append_to.push_str("--");
+			append_to.push_str(first_mark);
 			append_to.push_str(nl_iter.next().unwrap());
-			add_long_flags_str_to_prev_flags(append_to, nl_iter);
+			add_long_flags_str_to_prev_flags(append_to, "", nl_iter);
 		}
 
 		/// Add all flags' help string to `append_to`.
@@ -3064,17 +6011,25 @@ pub mod presets {
 				append_to.push_str(prefix);
 				let s_list = s_columns.pop_front().unwrap();
 				let nl_list = nl_columns.pop_front().unwrap();
+				let mark = if nl_list.first() == Some(&&f.name) {
+					negation_mark(f)
+				} else {
+					""
+				};
 				if s_list.is_empty() {
 					if !nl_list.is_empty() {
 						append_to.push_str(&sp.repeat(s_col_width));
 						let prev_help_len = append_to.len();
-						add_long_flags_str(&mut append_to, nl_list.into_iter());
+						add_long_flags_str(&mut append_to, mark, nl_list.into_iter());
 						append_to = add_type_suffix(append_to, &f.flag_type);
 						let nl_len = append_to.len() - prev_help_len;
 						append_to = append_to
 							+ &sp.repeat(nl_col_width + gap_width - nl_len)
+							+ required_mark(f)
 							+ &f.description
-							+ suffix;
+							+ &limit_mark(f)
+							+ &missing_value_mark(f)
+							+ &env_mark(f) + suffix;
 					}
 				} else {
 					append_to = append_to + &sp.repeat(s_col_width - (s_list.len() * 4));
@@ -3082,17 +6037,23 @@ pub mod presets {
 					if nl_list.is_empty() {
 						append_to = add_type_suffix(append_to, &f.flag_type)
 							+ &sp.repeat(4 + nl_col_width)
+							+ required_mark(f)
 							+ &f.description
-							+ suffix;
+							+ &limit_mark(f)
+							+ &missing_value_mark(f)
+							+ &env_mark(f) + suffix;
 					} else {
 						let prev_help_len = append_to.len();
-						add_long_flags_str_to_prev_flags(&mut append_to, nl_list.into_iter());
+						add_long_flags_str_to_prev_flags(&mut append_to, mark, nl_list.into_iter());
 						append_to = add_type_suffix(append_to, &f.flag_type);
 						let nl_len = append_to.len() - prev_help_len - 2;
 						append_to = append_to
 							+ &sp.repeat(nl_col_width + gap_width - nl_len)
+							+ required_mark(f)
 							+ &f.description
-							+ suffix;
+							+ &limit_mark(f)
+							+ &missing_value_mark(f)
+							+ &env_mark(f) + suffix;
 					}
 				}
 			}
@@ -3109,7 +6070,11 @@ pub mod presets {
 				help.push_str(description);
 				help.push_str("\n\n");
 			}
-			help = help + "Usage:\n" + &indent + &cmd.usage + "\n";
+			help = help + "Usage:\n" + &indent + &usage_with_positionals(cmd) + "\n";
+			if cmd.positionals.has_at_least_one() {
+				help.push('\n');
+				help.push_str(&positionals_help(cmd, &indent, 7));
+			}
 
 			let flag_num = cmd.l_flags.len() + cmd.c_flags.len() + ctx.common_flags.sum_of_length();
 			if flag_num > 0 {
@@ -3265,6 +6230,12 @@ pub mod presets {
 				}
 			}
 
+			let relations = flag_relations_help(cmd, ctx, &indent);
+			if !relations.is_empty() {
+				help.push('\n');
+				help.push_str(&relations);
+			}
+
 			if let Vector(Some(sub_commands)) = &cmd.sub {
 				help += "\nSub Command";
 				if sub_commands.len() > 1 {
@@ -3357,10 +6328,14 @@ pub mod presets {
 		#[cfg(test)]
 		mod test {
 			use super::super::Command;
-			use super::help_tablize_with_alias_dedup;
+			use super::{
+				help, help_tablize, help_tablize_with_alias_dedup, help_with_alias_dedup, type_suffix,
+				usage_with_positionals,
+			};
 			use crate::{
-				Context, Flag, action_result, checks, copyright, crate_authors, crate_license,
-				crate_version, done, flags, license, preset_help_command, vector,
+				Context, Flag, FlagGroup, FlagType, FlagValue, Positional, action_result, checks,
+				copyright, crate_authors, crate_license, crate_version, done, flags, license,
+				preset_help_command, vector,
 			};
 
 			#[test]
@@ -3387,6 +6362,203 @@ pub mod presets {
 				)
 				.run_from_args(raw_args);
 			}
+
+			#[test]
+			fn list_flag_help_test() {
+				assert_eq!(
+					type_suffix(&FlagType::list(FlagType::String)),
+					" <string>..."
+				);
+				assert_eq!(type_suffix(&FlagType::list(FlagType::Bool)), " ...");
+				assert_eq!(type_suffix(&FlagType::Count), " ...");
+				let cmd = Command::with_name("root")
+					.local_flag(Flag::new_list("include", FlagType::String).short_alias('I'));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				assert!(help(&cmd, &ctx).contains("-I, --include <string>..."));
+			}
+
+			#[test]
+			fn negatable_flag_help_test() {
+				let cmd = Command::with_name("root")
+					.local_flag(Flag::new_bool("color").negatable(true).alias("colour"))
+					.local_flag(Flag::new_bool("force").alias("f"));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("--[no-]color, --colour"));
+					assert!(help.contains("--force, --f"));
+				}
+			}
+
+			#[test]
+			fn required_flag_help_test() {
+				let cmd = Command::with_name("root")
+					.local_flag(
+						Flag::new_string("name")
+							.required(true)
+							.description("user name"),
+					)
+					.local_flag(Flag::new_bool("yes").description("skip confirm"));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("(required) user name\n"));
+					assert!(!help.contains("(required) skip confirm"));
+				}
+			}
+
+			#[test]
+			fn flag_relations_help_test() {
+				let cmd = Command::with_name("deploy")
+					.common_flag(Flag::new_bool("all").conflicts_with("service"))
+					.local_flag(Flag::new_string("service").conflicts_with("all"))
+					.local_flag(Flag::new_string("tls-key").requires("tls-cert"))
+					.group(FlagGroup::at_most_one(vec!["file", "stdin"]));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains(
+						"Constraints: \n   --service conflicts with --all\n   --tls-key requires --tls-cert\n   at most one of --file, --stdin\n"
+					));
+				}
+			}
+
+			#[test]
+			fn env_help_test() {
+				let cmd = Command::with_name("root")
+					.local_flag(
+						Flag::new_string("token")
+							.env("MYTOOL_TOKEN")
+							.description("api token"),
+					)
+					.local_flag(Flag::new_bool("yes").description("skip confirm"));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("api token [env: MYTOOL_TOKEN]\n"));
+					assert!(help.contains("skip confirm\n"));
+				}
+			}
+
+			#[test]
+			fn map_help_test() {
+				let cmd = Command::with_name("root")
+					.local_flag(
+						Flag::new_map("define")
+							.short_alias('D')
+							.description("define"),
+					)
+					.local_flag(Flag::new_map("header").map_separator(':'));
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("--define KEY=VALUE..."));
+					assert!(help.contains("--header KEY:VALUE..."));
+				}
+			}
+
+			#[test]
+			fn limit_help_test() {
+				let cmd = Command::with_name("root")
+					.local_flag(
+						Flag::new_int("port")
+							.range(1, 65535)
+							.env("PORT")
+							.description("port"),
+					)
+					.local_flag(Flag::new_float("ratio").min(0.5).description("ratio"))
+					.local_flag(
+						Flag::new_string("id")
+							.min_len(2)
+							.max_len(8)
+							.description("id"),
+					);
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("port [range: 1..=65535] [env: PORT]\n"));
+					assert!(help.contains("ratio [range: 0.5..]\n"));
+					assert!(help.contains("id [length: 2..=8]\n"));
+				}
+			}
+
+			#[test]
+			fn missing_value_help_test() {
+				let cmd = Command::with_name("root").local_flag(
+					Flag::new_string("color")
+						.default_missing_value(FlagValue::String("always".into()))
+						.description("color"),
+				);
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("color [if no value: always]\n"));
+				}
+			}
+
+			#[test]
+			fn positionals_help_test() {
+				let cmd = Command::with_name("root")
+					.usage("root [OPTIONS]")
+					.positional(Positional::new_string("src").description("source file"))
+					.positional(Positional::new_string("dest").required(false))
+					.positional(
+						Positional::new_string("rest")
+							.variadic(true)
+							.description("rest files"),
+					);
+				let ctx = Context::from(vec!["exe_path".to_owned()]);
+				assert_eq!(
+					usage_with_positionals(&cmd),
+					"root [OPTIONS] <src> [dest] <rest>..."
+				);
+				for help in [
+					help(&cmd, &ctx),
+					help_tablize(&cmd, &ctx),
+					help_with_alias_dedup(&cmd, &ctx),
+					help_tablize_with_alias_dedup(&cmd, &ctx),
+				] {
+					assert!(help.contains("Usage:\n   root [OPTIONS] <src> [dest] <rest>...\n"));
+					assert!(help.contains("Args: \n   <src>"));
+					let lines: Vec<&str> = help.lines().collect();
+					for (usage, description) in [
+						("<src>", "source file"),
+						("[dest]", ""),
+						("<rest>...", "rest files"),
+					] {
+						assert!(lines.iter().any(|line| {
+							line.starts_with(&format!("   {usage}"))
+								&& line.ends_with(&format!("\t{description}"))
+						}));
+					}
+				}
+			}
 		}
 	}
 }
@@ -0,0 +1,477 @@
+use crate::{Command, Context, Flag, FlagType, FlagValue, vector::flag::FlagSearch};
+use std::{
+	error::Error,
+	fmt,
+	path::{Path, PathBuf},
+	str::FromStr,
+};
+
+/// Configuration loaded from simple INI/TOML-subset text.
+/// Keys in section such as `[tool.sub]` are for command of the route (`Context::routes` and command's name joined by `.`), and keys in top-level (before any section) are for all commands.
+/// If a section does not have a key, its parent sections (such as `[tool]` for `[tool.sub]`) and top-level are searched in order.
+/// A value is a string (quoted or bare), a number, a bool, or an array such as `["a", "b"]`.
+/// INI/TOMLのサブセットから読み込んだ設定。`[tool.sub]`のようなセクション内のキーはその経路（`Context::routes`とコマンド名を`.`でつないだもの）のコマンド向け、セクション外のキーは全コマンド向けの値となる。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+	/// Sections of config. Top-level section's name is empty.
+	pub sections: Vec<ConfigSection>,
+}
+
+/// Section of config
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfigSection {
+	/// Section's name (route path such as `tool.sub`). Top-level section's name is empty.
+	pub name: String,
+	/// Entries of section. Each entry is key (flag name) and raw values. Array has several values.
+	pub entries: Vec<(String, Vec<String>)>,
+}
+
+/// `ConfigError` shows error in loading config
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+	/// Line number error occurred (1-origin). If error is not about a line (such as IO error), this is 0.
+	pub line: usize,
+	/// Error message
+	pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.line > 0 {
+			write!(f, "line {}: {}", self.line, self.message)
+		} else {
+			write!(f, "{}", self.message)
+		}
+	}
+}
+
+impl Error for ConfigError {}
+
+impl FromStr for Config {
+	type Err = ConfigError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Config::parse(s)
+	}
+}
+
+impl Config {
+	/// Creates a new empty config.
+	pub fn new() -> Self {
+		Config::default()
+	}
+
+	/// Parses config text.
+	pub fn parse(text: &str) -> Result<Config, ConfigError> {
+		let mut config = Config::new();
+		let mut section = String::new();
+		for (index, line) in text.lines().enumerate() {
+			let error = |message: &str| ConfigError {
+				line: index + 1,
+				message: message.to_owned(),
+			};
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+				continue;
+			}
+			if let Some(name) = line.strip_prefix('[') {
+				match name.split_once(']') {
+					Some((name, rest)) if rest.trim().is_empty() || rest.trim().starts_with('#') => {
+						section = name.trim().to_owned();
+					}
+					_ => return Err(error("invalid section header")),
+				}
+				continue;
+			}
+			let Some((key, value)) = line.split_once('=') else {
+				return Err(error("expected `key = value`"));
+			};
+			let key = key.trim();
+			if key.is_empty() {
+				return Err(error("empty key"));
+			}
+			let values = parse_value(value.trim()).map_err(|message| error(&message))?;
+			config.set(&section, key, values);
+		}
+		Ok(config)
+	}
+
+	/// Loads config from file.
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+		match std::fs::read_to_string(&path) {
+			Ok(text) => Config::parse(&text),
+			Err(err) => Err(ConfigError {
+				line: 0,
+				message: format!("cannot read {}: {}", path.as_ref().display(), err),
+			}),
+		}
+	}
+
+	/// Returns candidate path of config file for `app_name` following XDG base directory (`$XDG_CONFIG_HOME/<app_name>/config`, or `$HOME/.config/<app_name>/config` if `XDG_CONFIG_HOME` is not set).
+	/// Environment variables are looked up by `lookup`.
+	pub fn xdg_path<F: Fn(&str) -> Option<String>>(app_name: &str, lookup: F) -> Option<PathBuf> {
+		let base = match lookup("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+			Some(dir) => PathBuf::from(dir),
+			None => PathBuf::from(lookup("HOME").filter(|dir| !dir.is_empty())?).join(".config"),
+		};
+		Some(base.join(app_name).join("config"))
+	}
+
+	/// Sets values of `key` in `section`. If the key already exists, its values are replaced.
+	pub fn set(&mut self, section: &str, key: &str, values: Vec<String>) {
+		let index = match self.sections.iter().position(|s| s.name == section) {
+			Some(index) => index,
+			None => {
+				self.sections.push(ConfigSection {
+					name: section.to_owned(),
+					entries: Vec::new(),
+				});
+				self.sections.len() - 1
+			}
+		};
+		let entries = &mut self.sections[index].entries;
+		match entries.iter_mut().find(|(k, _)| k == key) {
+			Some(entry) => entry.1 = values,
+			None => entries.push((key.to_owned(), values)),
+		}
+	}
+
+	/// Gets raw values of `key` for command of `section` path.
+	/// If `section` does not have `key`, its parent sections (such as `tool` for `tool.sub`) and top-level are searched in order.
+	pub fn get(&self, section: &str, key: &str) -> Option<&Vec<String>> {
+		let mut section = section;
+		loop {
+			let found = self
+				.sections
+				.iter()
+				.filter(|s| s.name == section)
+				.find_map(|s| s.entries.iter().find(|(k, _)| k == key));
+			if let Some((_, values)) = found {
+				return Some(values);
+			}
+			if section.is_empty() {
+				return None;
+			}
+			section = match section.rfind('.') {
+				Some(index) => &section[..index],
+				None => "",
+			};
+		}
+	}
+
+	/// Returns section path of `cmd`, that is `Context::routes` and `cmd`'s name joined by `.` (such as `tool.sub.leaf`). Empty names are skipped.
+	pub fn section_path(cmd: &Command, ctx: &Context) -> String {
+		ctx.routes
+			.inner()
+			.iter()
+			.flat_map(|routes| routes.iter())
+			.chain(std::iter::once(&cmd.name))
+			.filter(|name| !name.is_empty())
+			.map(String::as_str)
+			.collect::<Vec<&str>>()
+			.join(".")
+	}
+
+	/// Derives value of `flag` from raw values in config. If values are invalid for the flag's type, returns `FlagValue::Invalid`.
+	pub fn derive_flag_value(flag: &Flag, values: &[String]) -> FlagValue {
+		match &flag.flag_type {
+			FlagType::List(_) => {
+				let mut list = Vec::with_capacity(values.len());
+				for val in values {
+					match flag.derive_flag_value_from_string(val.clone()) {
+						FlagValue::List(mut vals) => list.append(&mut vals),
+						val => return val,
+					}
+				}
+				FlagValue::List(list)
+			}
+			FlagType::Map { .. } => {
+				let mut map = Vec::with_capacity(values.len());
+				for val in values {
+					match flag.flag_type.get_value_from_string(val.clone()) {
+						FlagValue::Map(mut entries) => map.append(&mut entries),
+						val => return val,
+					}
+				}
+				FlagValue::Map(map)
+			}
+			_ => match values {
+				[val] => flag.derive_flag_value_from_setting(val.clone()),
+				_ => FlagValue::Invalid(values.join(",")),
+			},
+		}
+	}
+
+	/// Creates config of effective values of flags available in `cmd` (after applying command line, environment variables, config and default values).
+	/// Values are stored in the section of `cmd` as raw values (not quoted), so they can be applied as config as they are.
+	pub fn effective(cmd: &Command, ctx: &Context) -> Config {
+		let section = Config::section_path(cmd, ctx);
+		let mut config = Config::new();
+		let mut flags = cmd.l_flags.all_flags();
+		flags.append(&mut cmd.c_flags.all_flags());
+		flags.append(&mut ctx.common_flags.all_flags());
+		let mut checked: Vec<&String> = Vec::new();
+		for flag in flags {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			let values = match ctx.get_flag_value_of(&flag.name, cmd) {
+				Some(FlagValue::List(vals)) => vals.iter().filter_map(raw_value).collect(),
+				Some(FlagValue::Map(entries)) => {
+					let separator = match flag.flag_type {
+						FlagType::Map { separator, .. } => separator,
+						_ => '=',
+					};
+					entries
+						.into_iter()
+						.map(|(key, value)| format!("{key}{separator}{value}"))
+						.collect()
+				}
+				Some(val) => match raw_value(&val) {
+					Some(val) => vec![val],
+					None => continue,
+				},
+				None => continue,
+			};
+			config.set(&section, &flag.name, values);
+		}
+		config
+	}
+}
+
+impl fmt::Display for Config {
+	/// Formats config as text which can be parsed into the same config. Values are quoted if needed.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut first = true;
+		for section in self.sections.iter() {
+			if !section.name.is_empty() {
+				if !first {
+					writeln!(f)?;
+				}
+				writeln!(f, "[{}]", section.name)?;
+			}
+			first = false;
+			for (key, values) in section.entries.iter() {
+				match values.as_slice() {
+					[val] => writeln!(f, "{} = {}", key, quote_value(val))?,
+					vals => writeln!(
+						f,
+						"{} = [{}]",
+						key,
+						vals
+							.iter()
+							.map(|val| quote_value(val))
+							.collect::<Vec<String>>()
+							.join(", ")
+					)?,
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns raw config value of `FlagValue`. If it cannot be a config value, returns None.
+fn raw_value(val: &FlagValue) -> Option<String> {
+	match val {
+		FlagValue::List(_) | FlagValue::Invalid(_) | FlagValue::None => None,
+		val => Some(val.clone().get_string_value()),
+	}
+}
+
+/// Formats raw value for config text. Values except bools and numbers are quoted and escaped.
+fn quote_value(val: &str) -> String {
+	if val == "true" || val == "false" || val.parse::<f64>().is_ok() {
+		val.to_owned()
+	} else {
+		let mut quoted = String::with_capacity(val.len() + 2);
+		quoted.push('"');
+		for c in val.chars() {
+			match c {
+				'\\' => quoted.push_str("\\\\"),
+				'"' => quoted.push_str("\\\""),
+				'\n' => quoted.push_str("\\n"),
+				'\t' => quoted.push_str("\\t"),
+				c => quoted.push(c),
+			}
+		}
+		quoted.push('"');
+		quoted
+	}
+}
+
+/// Parses raw value of config. Returns several values if it is array.
+fn parse_value(value: &str) -> Result<Vec<String>, String> {
+	if let Some(inner) = value.strip_prefix('[') {
+		let mut values = Vec::new();
+		let mut rest = inner.trim_start();
+		loop {
+			if let Some(after) = rest.strip_prefix(']') {
+				return check_rest(after).map(|_| values);
+			}
+			let (val, after) = parse_scalar(rest, &[',', ']'])?;
+			values.push(val);
+			rest = after.trim_start();
+			if let Some(after) = rest.strip_prefix(',') {
+				rest = after.trim_start();
+			} else if !rest.starts_with(']') {
+				return Err("unterminated array".to_owned());
+			}
+		}
+	} else {
+		let (val, rest) = parse_scalar(value, &[])?;
+		check_rest(rest).map(|_| vec![val])
+	}
+}
+
+/// Parses a scalar (quoted or bare) value from head of `value`, and returns the value and the rest.
+fn parse_scalar<'a>(value: &'a str, terminators: &[char]) -> Result<(String, &'a str), String> {
+	if let Some(inner) = value.strip_prefix('"') {
+		let mut val = String::new();
+		let mut chars = inner.char_indices();
+		while let Some((index, c)) = chars.next() {
+			match c {
+				'"' => return Ok((val, &inner[index + 1..])),
+				'\\' => match chars.next() {
+					Some((_, 'n')) => val.push('\n'),
+					Some((_, 't')) => val.push('\t'),
+					Some((_, c)) => val.push(c),
+					None => break,
+				},
+				c => val.push(c),
+			}
+		}
+		Err("unterminated string".to_owned())
+	} else {
+		let end = value
+			.find(|c| terminators.contains(&c) || c == '#')
+			.unwrap_or(value.len());
+		Ok((value[..end].trim().to_owned(), &value[end..]))
+	}
+}
+
+/// Checks that rest of value is empty or comment.
+fn check_rest(rest: &str) -> Result<(), String> {
+	let rest = rest.trim();
+	if rest.is_empty() || rest.starts_with('#') {
+		Ok(())
+	} else {
+		Err(format!("unexpected characters: {rest}"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Config;
+	use crate::{Flag, FlagType, FlagValue};
+
+	#[test]
+	fn parse_and_get_test() {
+		let config = Config::parse(
+			r#"
+# comment
+name = "root name" # trailing comment
+count = 3
+
+[sub]
+name = bare value
+tags = ["a", "b,c", d]
+
+[sub.leaf]
+yes = true
+"#,
+		)
+		.unwrap();
+		assert_eq!(config.get("", "name"), Some(&vec!["root name".to_owned()]));
+		assert_eq!(
+			config.get("sub", "name"),
+			Some(&vec!["bare value".to_owned()])
+		);
+		assert_eq!(
+			config.get("sub.leaf", "tags"),
+			Some(&vec!["a".to_owned(), "b,c".to_owned(), "d".to_owned()])
+		);
+		assert_eq!(config.get("sub.leaf", "count"), Some(&vec!["3".to_owned()]));
+		assert_eq!(
+			config.get("sub.leaf", "yes"),
+			Some(&vec!["true".to_owned()])
+		);
+		assert_eq!(config.get("", "yes"), None);
+
+		let err = Config::parse("name = \"unterminated").unwrap_err();
+		assert_eq!(err.line, 1);
+		assert!(Config::parse("[sub").is_err());
+		assert!(Config::parse("novalue").is_err());
+	}
+
+	#[test]
+	fn derive_flag_value_test() {
+		let values = |vals: &[&str]| {
+			vals
+				.iter()
+				.map(|val| String::from(*val))
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			Config::derive_flag_value(&Flag::new_map("define"), &values(&["a=1", "b=2"])),
+			FlagValue::Map(vec![("a".into(), "1".into()), ("b".into(), "2".into())])
+		);
+		assert_eq!(
+			Config::derive_flag_value(&Flag::new_map("define"), &values(&["a"])),
+			FlagValue::Invalid("a".into())
+		);
+		assert_eq!(
+			Config::derive_flag_value(&Flag::new_list("num", FlagType::Int), &values(&["1", "2"])),
+			FlagValue::List(vec![FlagValue::Int(1), FlagValue::Int(2)])
+		);
+		assert_eq!(
+			Config::derive_flag_value(&Flag::new_count("verbose"), &values(&["3"])),
+			FlagValue::Int(3)
+		);
+	}
+
+	#[test]
+	fn display_test() {
+		let mut config = Config::new();
+		config.set("", "name", vec!["root".to_owned()]);
+		config.set("", "count", vec!["3".to_owned()]);
+		config.set("sub", "tags", vec!["a".to_owned(), "b".to_owned()]);
+		let text = config.to_string();
+		assert_eq!(
+			text,
+			"name = \"root\"\ncount = 3\n\n[sub]\ntags = [\"a\", \"b\"]\n"
+		);
+	}
+
+	#[test]
+	fn display_round_trip_test() {
+		let mut config = Config::new();
+		config.set("", "comment", vec!["a # b".to_owned()]);
+		config.set("", "escaped", vec!["q\"uote\\ \ttab\nline".to_owned()]);
+		config.set("", "spaced", vec![" padded ".to_owned()]);
+		config.set("", "empty", vec![String::new()]);
+		config.set("", "bracket", vec!["[not array]".to_owned()]);
+		config.set("", "flag", vec!["true".to_owned()]);
+		config.set("tool", "list", vec!["p,q".to_owned(), "r]".to_owned()]);
+		config.set("tool", "none", Vec::new());
+		config.set("tool.sub", "num", vec!["-1.5".to_owned()]);
+		let text = config.to_string();
+		assert_eq!(Config::parse(&text), Ok(config));
+	}
+
+	#[test]
+	fn xdg_path_test() {
+		let path = Config::xdg_path("tool", |name| match name {
+			"XDG_CONFIG_HOME" => Some("/xdg".to_owned()),
+			_ => Some("/home/user".to_owned()),
+		});
+		assert_eq!(path, Some("/xdg/tool/config".into()));
+		let path = Config::xdg_path("tool", |name| match name {
+			"HOME" => Some("/home/user".to_owned()),
+			_ => None,
+		});
+		assert_eq!(path, Some("/home/user/.config/tool/config".into()));
+		assert_eq!(Config::xdg_path("tool", |_| None), None);
+	}
+}
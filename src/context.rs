@@ -1,9 +1,14 @@
 use crate::{
-	Command, Flag, FlagValue, Vector,
+	Command, Flag, FlagType, FlagValue, Vector,
+	flag::{FlagAccessError, FromFlagValue},
+	os_args::{NonUtf8Args, Slot},
 	parser::{ErrorInfo, MiddleArg},
 	vector::flag::FlagSearch,
 };
-use std::collections::VecDeque;
+use std::{
+	collections::{BTreeMap, VecDeque},
+	ffi::OsString,
+};
 
 /// Storage information for command execution.
 /// This storage raw args, non-flag args, flag values, and etc.
@@ -30,6 +35,17 @@ pub struct Context {
 	pub parsing_args: Option<VecDeque<MiddleArg>>,
 	/// error inforamation list of parsing
 	pub error_info_list: Vector<ErrorInfo>,
+	/// args after end-of-options marker (default: --). They are not parsed as flags.
+	/// If end-of-options marker does not appear, this is `Vector(None)`.
+	pub trailing_args: Vector<String>,
+	/// storage of result of parsing declared positional args. Variadic positional arg's value is `FlagValue::List`.
+	pub positional_values: Vector<(String, FlagValue)>,
+	/// originals of args which are not valid UTF-8 (inputted by `Command::run_from_os_args`). They are kept by slot after parsing.
+	pub(crate) non_utf8_args: NonUtf8Args,
+	/// If this is true, actions are not run and the routed command and this context are returned instead (set by `Command::parse`).
+	pub(crate) parse_only: bool,
+	/// Names of flags whose values are filled from environment variables or config (not inputted by args)
+	pub(crate) filled_flags: Vec<String>,
 }
 
 impl Context {
@@ -51,6 +67,11 @@ impl Context {
 			local_flags_values: Vector::default(),
 			parsing_args: None,
 			error_info_list: Vector::default(),
+			trailing_args: Vector::default(),
+			positional_values: Vector::default(),
+			non_utf8_args: NonUtf8Args::default(),
+			parse_only: false,
+			filled_flags: Vec::new(),
 		}
 	}
 
@@ -76,6 +97,11 @@ impl Context {
 			local_flags_values,
 			parsing_args,
 			error_info_list,
+			trailing_args: Vector::default(),
+			positional_values: Vector::default(),
+			non_utf8_args: NonUtf8Args::default(),
+			parse_only: false,
+			filled_flags: Vec::new(),
 		}
 	}
 
@@ -85,6 +111,17 @@ impl Context {
 		self
 	}
 
+	/// Set trailing args
+	pub fn trailing_args(mut self, trailing_args: Vector<String>) -> Self {
+		self.trailing_args = trailing_args;
+		self
+	}
+
+	/// Returns true if end-of-options marker appeared in args.
+	pub fn has_trailing_args(&self) -> bool {
+		self.trailing_args.has_inner_vec()
+	}
+
 	/// Get `exe_path` as &str
 	pub fn exe_path(&self) -> &str {
 		&self.exe_path
@@ -181,15 +218,7 @@ impl Context {
 	pub fn take_inputted_local_flag_value_of(&mut self, flag_name: &str) -> Option<FlagValue> {
 		match self.local_flags_values {
 			Vector(None) => None,
-			Vector(Some(ref mut local)) => {
-				match local.iter().position(|(name, _)| name == flag_name) {
-					Some(index) => {
-						let (_, val) = local.remove(index);
-						Some(val)
-					}
-					None => None,
-				}
-			}
+			Vector(Some(ref mut local)) => take_inputted_value(local, flag_name),
 		}
 	}
 
@@ -198,15 +227,7 @@ impl Context {
 	pub fn take_inputted_common_flag_value_of(&mut self, flag_name: &str) -> Option<FlagValue> {
 		match self.common_flags_values {
 			Vector(None) => None,
-			Vector(Some(ref mut common)) => {
-				match common.iter().position(|(name, _)| name == flag_name) {
-					Some(index) => {
-						let (_, val) = common.remove(index);
-						Some(val)
-					}
-					None => None,
-				}
-			}
+			Vector(Some(ref mut common)) => take_inputted_value(common, flag_name),
 		}
 	}
 
@@ -224,6 +245,28 @@ impl Context {
 		}
 	}
 
+	/// Gets value of the flag matches `flag_name` as `T` (such as `bool`, `String`, `Option<isize>` or `Vec<PathBuf>`).
+	/// If the flag is not defined, not set (not inputted and does not have default value) or its value cannot be converted into `T`, returns `FlagAccessError`.
+	/// フラグ値を型`T`として取得する。未定義・未設定・型不一致の場合は`FlagAccessError`を返す
+	pub fn get<T: FromFlagValue>(
+		&self,
+		flag_name: &str,
+		current_command: &Command,
+	) -> Result<T, FlagAccessError> {
+		let Some(value) = self.get_flag_value_of(flag_name, current_command) else {
+			return Err(FlagAccessError::Unknown(flag_name.to_owned()));
+		};
+		match T::from_flag_value(&value) {
+			Some(val) => Ok(val),
+			None if value == FlagValue::None => Err(FlagAccessError::Unset(flag_name.to_owned())),
+			None => Err(FlagAccessError::WrongType {
+				name: flag_name.to_owned(),
+				expected: T::type_name(),
+				value,
+			}),
+		}
+	}
+
 	/// Gets `FlagValue`'s clone of the inputted flag matches `flag_name` from context.
 	/// contextからユーザから指定された場合のフラグ値のcloneを取得する。ユーザから入力されていない場合はNoneを返す。
 	pub fn get_inputted_flag_value_of(&self, flag_name: &str) -> Option<FlagValue> {
@@ -247,7 +290,7 @@ impl Context {
 				.map(|f| f.default_value.clone()),
 			Some(FlagValue::None) => (&current_command.c_flags, &self.common_flags)
 				.find(flag_name)
-				.map(|f| f.derive_flag_value_if_no_value()),
+				.map(|f| derive_value_if_no_value(f, &self.common_flags_values)),
 			val => val,
 		}
 	}
@@ -268,7 +311,7 @@ impl Context {
 			Some(FlagValue::None) => current_command
 				.l_flags
 				.find(flag_name)
-				.map(|f| f.derive_flag_value_if_no_value()),
+				.map(|f| derive_value_if_no_value(f, &self.local_flags_values)),
 			val => val,
 		}
 	}
@@ -278,10 +321,7 @@ impl Context {
 	pub fn get_inputted_local_flag_value_of(&self, flag_name: &str) -> Option<FlagValue> {
 		match &self.local_flags_values {
 			Vector(None) => None,
-			Vector(Some(local)) => local
-				.iter()
-				.find(|(name, _)| name == flag_name)
-				.map(|(_, flag_val)| flag_val.to_owned()),
+			Vector(Some(local)) => find_inputted_value(local, flag_name),
 		}
 	}
 
@@ -290,10 +330,208 @@ impl Context {
 	pub fn get_inputted_common_flag_value_of(&self, flag_name: &str) -> Option<FlagValue> {
 		match &self.common_flags_values {
 			Vector(None) => None,
-			Vector(Some(common)) => common
+			Vector(Some(common)) => find_inputted_value(common, flag_name),
+		}
+	}
+
+	/// Gets values of the flag matches `flag_name` as Vec. Values of list flag specified repeatedly are returned in order.
+	/// If the flag is not list flag, returns Vec has only its value. If it is not defined, returns None.
+	/// フラグ値をVecとして取得する。繰り返し指定されたリストフラグの場合は指定順に全ての値を返し、リストフラグでない場合は値1つだけのVecを返す。定義されていない場合はNoneを返す。
+	pub fn get_flag_values_of(
+		&self,
+		flag_name: &str,
+		current_command: &Command,
+	) -> Option<Vec<FlagValue>> {
+		self
+			.get_flag_value_of(flag_name, current_command)
+			.map(FlagValue::into_vec)
+	}
+
+	/// Gets value of the map flag matches `flag_name` as map ordered by key. If it is not defined or not a map flag, returns None.
+	/// マップ型フラグの値をキー順のマップとして取得する。定義されていない場合やマップ型フラグでない場合はNoneを返す。
+	pub fn get_map_of(
+		&self,
+		flag_name: &str,
+		current_command: &Command,
+	) -> Option<BTreeMap<String, String>> {
+		match self.get_flag_value_of(flag_name, current_command)? {
+			FlagValue::Map(entries) => Some(entries.into_iter().collect()),
+			_ => None,
+		}
+	}
+
+	/// Gets the value of the declared positional arg matches `name`. If it is not declared or not inputted, returns None.
+	/// `name`と名前が一致する宣言済み位置引数の値を返す。宣言されていない場合や入力されていない場合は`None`を返す。
+	pub fn get_positional_value_of(&self, name: &str) -> Option<FlagValue> {
+		match &self.positional_values {
+			Vector(None) => None,
+			Vector(Some(values)) => values
+				.iter()
+				.find(|(positional_name, _)| positional_name == name)
+				.map(|(_, val)| val.clone()),
+		}
+	}
+
+	/// Returns raw args as `OsString` without loss.
+	pub fn raw_os_args(&self) -> Vec<OsString> {
+		self
+			.raw_args
+			.iter()
+			.enumerate()
+			.map(|(i, arg)| self.os_string_of(Slot::RawArg(i), arg))
+			.collect()
+	}
+
+	/// Returns non-flag args as `OsString` without loss.
+	pub fn os_args(&self) -> Vec<OsString> {
+		self
+			.args
+			.iter()
+			.enumerate()
+			.map(|(i, arg)| self.os_string_of(Slot::Arg(i), arg))
+			.collect()
+	}
+
+	/// Returns args after end-of-options marker as `OsString` without loss.
+	pub fn os_trailing_args(&self) -> Vec<OsString> {
+		self
+			.trailing_args
+			.inner()
+			.iter()
+			.flatten()
+			.enumerate()
+			.map(|(i, arg)| self.os_string_of(Slot::TrailingArg(i), arg))
+			.collect()
+	}
+
+	/// Gets value of the string or path flag matches `flag_name` as `OsString` without loss.
+	/// If it is not defined, not string or path flag, or list flag, returns None.
+	pub fn get_os_flag_value_of(
+		&self,
+		flag_name: &str,
+		current_command: &Command,
+	) -> Option<OsString> {
+		let slot = self.inputted_flag_slots(flag_name).first().copied();
+		self
+			.get_flag_value_of(flag_name, current_command)
+			.and_then(|val| self.to_os_string(val, slot))
+	}
+
+	/// Gets values of the string or path flag (or list of them) matches `flag_name` as `OsString` without loss.
+	/// If it is not defined or not string or path flag, returns None.
+	pub fn get_os_flag_values_of(
+		&self,
+		flag_name: &str,
+		current_command: &Command,
+	) -> Option<Vec<OsString>> {
+		let slots = self.inputted_flag_slots(flag_name);
+		self
+			.get_flag_values_of(flag_name, current_command)?
+			.into_iter()
+			.enumerate()
+			.map(|(i, val)| self.to_os_string(val, slots.get(i).copied()))
+			.collect()
+	}
+
+	/// Gets value of the declared string or path positional arg matches `name` as `OsString` without loss.
+	/// If it is not declared, not inputted, not string or path positional arg, or variadic, returns None.
+	pub fn get_os_positional_value_of(&self, name: &str) -> Option<OsString> {
+		let index = self.positional_index(name)?;
+		self
+			.get_positional_value_of(name)
+			.and_then(|val| self.to_os_string(val, Some(Slot::Positional(index, 0))))
+	}
+
+	/// Gets values of the declared string or path positional arg (or variadic one) matches `name` as `OsString` without loss.
+	/// If it is not declared, not inputted or not string or path positional arg, returns None.
+	pub fn get_os_positional_values_of(&self, name: &str) -> Option<Vec<OsString>> {
+		let index = self.positional_index(name)?;
+		self
+			.get_positional_value_of(name)?
+			.into_vec()
+			.into_iter()
+			.enumerate()
+			.map(|(i, val)| self.to_os_string(val, Some(Slot::Positional(index, i))))
+			.collect()
+	}
+
+	/// Returns original of the value stored in `slot`. If it is not taken from non-UTF-8 arg, returns `val` as `OsString`.
+	fn os_string_of(&self, slot: Slot, val: &str) -> OsString {
+		match self.non_utf8_args.original(slot) {
+			Some(original) => original.clone(),
+			None => OsString::from(val),
+		}
+	}
+
+	/// Converts string or path value stored in `slot` into `OsString` without loss. If `val` is neither of them, returns None.
+	fn to_os_string(&self, val: FlagValue, slot: Option<Slot>) -> Option<OsString> {
+		match (val, slot) {
+			(FlagValue::String(val), Some(slot)) => Some(self.os_string_of(slot, &val)),
+			(FlagValue::String(val), None) => Some(OsString::from(val)),
+			(FlagValue::Path(path), _) => Some(path.into_os_string()),
+			_ => None,
+		}
+	}
+
+	/// Returns slots of inputted values of the flag matches `flag_name` in the same order as `get_flag_values_of`.
+	/// Local flag values are prior to common flag values.
+	fn inputted_flag_slots(&self, flag_name: &str) -> Vec<Slot> {
+		let slots = |values: &Vector<(String, FlagValue)>, slot: fn(usize, usize) -> Slot| {
+			let entries = values
+				.inner()
 				.iter()
-				.find(|(name, _)| name == flag_name)
-				.map(|(_, flag_val)| flag_val.to_owned()),
+				.flatten()
+				.enumerate()
+				.filter(|(_, (name, _))| name == flag_name);
+			let mut list = Vec::new();
+			let mut first = None;
+			for (i, (_, val)) in entries {
+				match val {
+					FlagValue::List(vals) => list.extend((0..vals.len()).map(|j| slot(i, j))),
+					_ => {
+						first.get_or_insert(slot(i, 0));
+					}
+				}
+			}
+			if list.is_empty() {
+				first.into_iter().collect()
+			} else {
+				list
+			}
+		};
+		match slots(&self.local_flags_values, Slot::LocalFlag) {
+			local if local.is_empty() => slots(&self.common_flags_values, Slot::CommonFlag),
+			local => local,
+		}
+	}
+
+	/// Returns index of the declared positional arg matches `name` in `positional_values`.
+	fn positional_index(&self, name: &str) -> Option<usize> {
+		self
+			.positional_values
+			.inner()
+			.iter()
+			.flatten()
+			.position(|(positional_name, _)| positional_name == name)
+	}
+
+	/// Gets value of the declared positional arg matches `name` as `T`. If it is not inputted, `FlagValue::None` is converted (so `Option<T>` and `Vec<T>` can be taken).
+	/// If its value cannot be converted into `T`, returns `FlagAccessError`.
+	/// 位置引数の値を型`T`として取得する
+	pub fn get_positional<T: FromFlagValue>(&self, name: &str) -> Result<T, FlagAccessError> {
+		let value = self
+			.get_positional_value_of(name)
+			.unwrap_or(FlagValue::None);
+		match T::from_flag_value(&value) {
+			Some(val) => Ok(val),
+			None if value == FlagValue::None => {
+				Err(FlagAccessError::MissingPositional(name.to_owned()))
+			}
+			None => Err(FlagAccessError::InvalidPositional {
+				name: name.to_owned(),
+				expected: T::type_name(),
+				value,
+			}),
 		}
 	}
 
@@ -328,6 +566,83 @@ impl Context {
 	}
 }
 
+/// Derives value of flag inputted without value. In case of count flag, this returns the number of its occurrences in `values`.
+fn derive_value_if_no_value(flag: &Flag, values: &Vector<(String, FlagValue)>) -> FlagValue {
+	match (&flag.flag_type, values) {
+		(FlagType::Count, Vector(Some(values))) => FlagValue::Int(
+			values
+				.iter()
+				.filter(|(name, val)| name == &flag.name && val == &FlagValue::None)
+				.count() as isize,
+		),
+		_ => flag.derive_flag_value_if_no_value(),
+	}
+}
+
+/// Finds inputted value of `flag_name` from `values`. If list (or map) values exist, returns list (or map) of all of them in order.
+fn find_inputted_value(values: &[(String, FlagValue)], flag_name: &str) -> Option<FlagValue> {
+	let mut first: Option<&FlagValue> = None;
+	let mut list: Option<Vec<FlagValue>> = None;
+	let mut map: Option<Vec<(String, String)>> = None;
+	for (_, val) in values.iter().filter(|(name, _)| name == flag_name) {
+		match val {
+			FlagValue::List(vals) => list
+				.get_or_insert_with(Vec::new)
+				.extend(vals.iter().cloned()),
+			FlagValue::Map(entries) => map
+				.get_or_insert_with(Vec::new)
+				.extend(entries.iter().cloned()),
+			val => {
+				first.get_or_insert(val);
+			}
+		}
+	}
+	match (list, map) {
+		(Some(list), _) => Some(FlagValue::List(list)),
+		(None, Some(map)) => Some(FlagValue::Map(map)),
+		(None, None) => first.cloned(),
+	}
+}
+
+/// Takes inputted value of `flag_name` from `values`. If list (or map) values exist, takes all of them and returns list (or map) of them in order.
+fn take_inputted_value(
+	values: &mut Vec<(String, FlagValue)>,
+	flag_name: &str,
+) -> Option<FlagValue> {
+	if values
+		.iter()
+		.any(|(name, val)| name == flag_name && matches!(val, FlagValue::List(_)))
+	{
+		let mut list = Vec::new();
+		values.retain(|entry| match entry {
+			(name, FlagValue::List(vals)) if name == flag_name => {
+				list.extend(vals.iter().cloned());
+				false
+			}
+			_ => true,
+		});
+		Some(FlagValue::List(list))
+	} else if values
+		.iter()
+		.any(|(name, val)| name == flag_name && matches!(val, FlagValue::Map(_)))
+	{
+		let mut map = Vec::new();
+		values.retain(|entry| match entry {
+			(name, FlagValue::Map(entries)) if name == flag_name => {
+				map.extend(entries.iter().cloned());
+				false
+			}
+			_ => true,
+		});
+		Some(FlagValue::Map(map))
+	} else {
+		values
+			.iter()
+			.position(|(name, _)| name == flag_name)
+			.map(|index| values.remove(index).1)
+	}
+}
+
 impl From<Vec<String>> for Context {
 	fn from(raw_args: Vec<String>) -> Context {
 		let args = VecDeque::from(raw_args.clone());
@@ -345,6 +660,11 @@ impl From<Vec<String>> for Context {
 			local_flags_values: Vector::default(),
 			parsing_args: None,
 			error_info_list: Vector::default(),
+			trailing_args: Vector::default(),
+			positional_values: Vector::default(),
+			non_utf8_args: NonUtf8Args::default(),
+			parse_only: false,
+			filled_flags: Vec::new(),
 		}
 	}
 }
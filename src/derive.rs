@@ -0,0 +1,54 @@
+use crate::{Action, Command, Context, Flag, Positional, flag::FlagAccessError};
+
+/// Trait for types which can be built from context and command run. This is implemented by `#[derive(CombuArgs)]` and `#[derive(CombuSubcommands)]`.
+/// コンテキストと実行されたコマンドから構築できる型のためのトレイト
+pub trait FromContext: Sized {
+	/// Builds Self from `ctx` and `cmd` (the command whose action is run).
+	fn from_context(ctx: &Context, cmd: &Command) -> Result<Self, FlagAccessError>;
+}
+
+/// Trait for struct whose fields are flags or positional args of a command. This is implemented by `#[derive(CombuArgs)]`.
+/// フィールドがフラグや位置引数を表す構造体のためのトレイト
+pub trait CombuArgs: FromContext {
+	/// Returns local flags declared by fields.
+	fn local_flags() -> Vec<Flag>;
+
+	/// Returns common flags declared by fields (with `#[combu(common)]`).
+	fn common_flags() -> Vec<Flag>;
+
+	/// Returns positional args declared by fields (with `#[combu(positional)]`).
+	fn positionals() -> Vec<Positional>;
+
+	/// Adds flags and positional args declared by fields to `cmd`.
+	fn augment(cmd: Command) -> Command {
+		let cmd = Self::positionals()
+			.into_iter()
+			.fold(cmd, Command::positional);
+		let cmd = Self::common_flags()
+			.into_iter()
+			.fold(cmd, Command::common_flag);
+		Self::local_flags()
+			.into_iter()
+			.fold(cmd, Command::local_flag)
+	}
+}
+
+/// Trait for enum whose variants are sub commands. This is implemented by `#[derive(CombuSubcommands)]`.
+/// 列挙子がサブコマンドを表す列挙型のためのトレイト
+pub trait CombuSubcommands: FromContext {
+	/// Returns sub commands corresponding to variants. `action` is set to each of them (and their sub commands).
+	/// In `action`, the variant can be built by `FromContext::from_context`.
+	fn sub_commands(action: Action) -> Vec<Command>;
+
+	/// Builds the variant corresponding to the first name in `route` which matches one of sub commands.
+	/// `route` is names of commands from root to the command run (see `route_of`).
+	fn from_route(route: &[String], ctx: &Context, cmd: &Command) -> Result<Self, FlagAccessError>;
+}
+
+/// Returns names of commands from root to `cmd` (the command whose action is run).
+/// ルートから実行されたコマンドまでのコマンド名を返す
+pub fn route_of(ctx: &Context, cmd: &Command) -> Vec<String> {
+	let mut route: Vec<String> = ctx.routes.inner().iter().flatten().cloned().collect();
+	route.push(cmd.name.clone());
+	route
+}
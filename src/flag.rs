@@ -1,4 +1,12 @@
 use crate::{Vector, flag_type, flag_value};
+use std::{
+	cmp::Ordering,
+	error::Error,
+	fmt, mem,
+	net::{IpAddr, SocketAddr},
+	path::PathBuf,
+	time::Duration,
+};
 
 /// Struct for Flag setting's information
 /// フラグ（オプション）情報格納のための構造体です。
@@ -16,6 +24,98 @@ pub struct Flag {
 	pub default_value: FlagValue,
 	/// This flag's `flag_type`
 	pub flag_type: FlagType,
+	/// If this is true and this is a bool flag, `--no-<name>` sets this flag false.
+	pub negatable: bool,
+	/// If this is true, numeric short flag such as `-5` (like `head -5`) is interpreted as this flag's value.
+	pub numeric_shorthand: bool,
+	/// If this is true, parse error occurs when this flag is not inputted.
+	pub required: bool,
+	/// Names of flags which cannot be inputted with this flag
+	pub conflicts_with: Vector<String>,
+	/// Names of flags which must be inputted when this flag is inputted
+	pub requires: Vector<String>,
+	/// Name of environment variable whose value is used when this flag is not inputted
+	pub env: Option<String>,
+	/// Minimum value of this flag in its own type (for numeric flag, and each value of list flag)
+	pub min: Option<FlagValue>,
+	/// Maximum value of this flag in its own type (for numeric flag, and each value of list flag)
+	pub max: Option<FlagValue>,
+	/// Minimum length (count of chars) of this flag's value (for string flag, and each value of list flag)
+	pub min_len: Option<usize>,
+	/// Maximum length (count of chars) of this flag's value (for string flag, and each value of list flag)
+	pub max_len: Option<usize>,
+	/// Custom validator of this flag's value. If it returns Err, the message is reported as parse error.
+	pub validator: Option<Validator>,
+	/// Delimiter of values in one arg (for list flag). If this is set, `--tags a,b,c` is parsed as three values.
+	pub value_delimiter: Option<char>,
+	/// Value used when this flag is inputted without value. If this is set, this flag's value is optional and only value attached by eq (such as `--color=always`) is taken as its value.
+	pub default_missing_value: Option<FlagValue>,
+}
+
+/// Custom validator for flag value. The inner function returns Err with message if the value is invalid.
+/// Validators are equal if their functions have the same address (same function may have different addresses, so this is only for comparing flags loosely).
+/// フラグ値のカスタムバリデータ。値が不正な場合はメッセージをErrで返す
+#[derive(Clone, Copy, Debug)]
+pub struct Validator(pub fn(&FlagValue) -> Result<(), String>);
+
+impl PartialEq for Validator {
+	fn eq(&self, other: &Self) -> bool {
+		std::ptr::fn_addr_eq(self.0, other.0)
+	}
+}
+
+/// Returns range expression such as `1..=10`, `1..` or `..=10`.
+fn range_str<T: fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+	let min = min.map(|min| min.to_string()).unwrap_or_default();
+	match max {
+		Some(max) => format!("{min}..={max}"),
+		None => format!("{min}.."),
+	}
+}
+
+/// Compares flag value `val` with `limit`. Integers of any variants are compared exactly, and float is compared with any number.
+/// Other values are compared only with the same variant. If they cannot be compared, returns None.
+fn compare_limit(val: &FlagValue, limit: &FlagValue) -> Option<Ordering> {
+	if let (Some(val), Some(limit)) = (integer_of(val), integer_of(limit)) {
+		return Some(val.cmp(&limit));
+	}
+	match (val, limit) {
+		(FlagValue::Float(val), limit) => val.partial_cmp(&float_of(limit)?),
+		(val, FlagValue::Float(limit)) => float_of(val)?.partial_cmp(limit),
+		(val, limit) if mem::discriminant(val) == mem::discriminant(limit) => val.partial_cmp(limit),
+		_ => None,
+	}
+}
+
+/// Returns integer value of `val` (including byte size) as i128, which can hold all of them exactly.
+fn integer_of(val: &FlagValue) -> Option<i128> {
+	match val {
+		FlagValue::Int(i) => i128::try_from(*i).ok(),
+		FlagValue::UInt(u) => i128::try_from(*u).ok(),
+		FlagValue::I64(i) => Some(i128::from(*i)),
+		FlagValue::U64(u) | FlagValue::ByteSize(u) => Some(i128::from(*u)),
+		_ => None,
+	}
+}
+
+/// Returns numeric value of `val` as f64 to compare it with float.
+fn float_of(val: &FlagValue) -> Option<f64> {
+	match val {
+		FlagValue::Float(f) => Some(*f),
+		// floatとの比較なので精度の損失は許容する
+		#[allow(clippy::cast_precision_loss)]
+		val => integer_of(val).map(|i| i as f64),
+	}
+}
+
+/// Returns description of limits such as `between 1 and 10`, `at least 1` or `at most 10`.
+fn limits_str<T: fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+	match (min, max) {
+		(Some(min), Some(max)) => format!("between {min} and {max}"),
+		(Some(min), None) => format!("at least {min}"),
+		(None, Some(max)) => format!("at most {max}"),
+		(None, None) => String::new(),
+	}
 }
 
 /// Enum shows `FlagType`
@@ -35,6 +135,60 @@ pub enum FlagType {
 	/// Variant shows float
 	/// float型用Variant
 	Float,
+	/// Variant shows repeatable flag. Values of each occurrence are collected in order as inner type.
+	/// 繰り返し指定可能なフラグ用Variant（各指定の値を内部の型として順に収集する）
+	List(Box<FlagType>),
+	/// Variant shows count of occurrences, such as verbosity(`-vvv`). Its value is `FlagValue::Int`.
+	/// 指定回数を数えるフラグ用Variant（値は`FlagValue::Int`）
+	Count,
+	/// Variant shows unsigned int
+	/// 符号なし整数型用Variant
+	UInt,
+	/// Variant shows 64-bit int
+	/// 64ビット整数型用Variant
+	I64,
+	/// Variant shows 64-bit unsigned int
+	/// 64ビット符号なし整数型用Variant
+	U64,
+	/// Variant shows file system path
+	/// パス型用Variant
+	Path,
+	/// Variant shows duration such as `10s`, `1h30m` or `500ms`
+	/// 時間長（`10s`, `1h30m`, `500ms`など）用Variant
+	Duration,
+	/// Variant shows size in bytes such as `512`, `10KB` or `512MiB`
+	/// バイト数（`512`, `10KB`, `512MiB`など）用Variant
+	ByteSize,
+	/// Variant shows IP address
+	/// `IPアドレス型用Variant`
+	IpAddr,
+	/// Variant shows socket address (IP address and port)
+	/// ソケットアドレス（IPアドレスとポート）型用Variant
+	SocketAddr,
+	/// Variant shows char
+	/// char型用Variant
+	Char,
+	/// Variant shows map of `KEY=VALUE` pairs accumulated from each occurrence. `separator` splits key and value, and `duplicates` decides how duplicate keys are treated.
+	/// 各指定の`KEY=VALUE`を蓄積するマップ型用Variant。`separator`でキーと値を分け、`duplicates`で重複キーの扱いを決める
+	Map {
+		/// Separator of key and value. Default is `=`.
+		separator: char,
+		/// Policy for duplicate keys
+		duplicates: DuplicateKeys,
+	},
+}
+
+/// Policy for duplicate keys of map flag
+/// マップ型フラグの重複キーの扱い
+#[derive(PartialOrd, PartialEq, Clone, Copy, Debug, Default)]
+pub enum DuplicateKeys {
+	/// The last value of the key is used.
+	#[default]
+	LastWins,
+	/// The first value of the key is used.
+	FirstWins,
+	/// Duplicate key is parse error.
+	Error,
 }
 
 impl FlagType {
@@ -46,9 +200,22 @@ impl FlagType {
 			FlagType::String => "String",
 			FlagType::Int => "Int",
 			FlagType::Float => "Float",
+			FlagType::List(_) => "List",
+			FlagType::Count => "Count",
+			FlagType::UInt => "UInt",
+			FlagType::I64 => "I64",
+			FlagType::U64 => "U64",
+			FlagType::Path => "Path",
+			FlagType::Duration => "Duration",
+			FlagType::ByteSize => "ByteSize",
+			FlagType::IpAddr => "IpAddr",
+			FlagType::SocketAddr => "SocketAddr",
+			FlagType::Char => "Char",
+			FlagType::Map { .. } => "Map",
 		}
 	}
 	/// Get this `FlagType` variant's default value
+	/// For `IpAddr`, `SocketAddr` and `Char`, which do not have natural default, this returns `FlagValue::None`.
 	/// `FlagType`のデフォルト値を取得
 	pub fn default_flag_value(&self) -> FlagValue {
 		match self {
@@ -56,13 +223,38 @@ impl FlagType {
 			FlagType::String => FlagValue::String(String::default()),
 			FlagType::Int => FlagValue::Int(isize::default()),
 			FlagType::Float => FlagValue::Float(f64::default()),
+			FlagType::List(_) => FlagValue::List(Vec::new()),
+			FlagType::Count => FlagValue::Int(0),
+			FlagType::UInt => FlagValue::UInt(usize::default()),
+			FlagType::I64 => FlagValue::I64(i64::default()),
+			FlagType::U64 => FlagValue::U64(u64::default()),
+			FlagType::Path => FlagValue::Path(PathBuf::default()),
+			FlagType::Duration => FlagValue::Duration(Duration::default()),
+			FlagType::ByteSize => FlagValue::ByteSize(u64::default()),
+			FlagType::IpAddr | FlagType::SocketAddr | FlagType::Char => FlagValue::None,
+			FlagType::Map { .. } => FlagValue::Map(Vec::new()),
+		}
+	}
+
+	/// Creates `FlagType::List` whose inner type is `inner`.
+	/// 内部の型が`inner`である`FlagType::List`を作成する
+	pub fn list(inner: FlagType) -> FlagType {
+		FlagType::List(Box::new(inner))
+	}
+
+	/// Creates `FlagType::Map` with separator `=` and `DuplicateKeys::LastWins`.
+	/// 区切り文字が`=`で、重複キーは後勝ちの`FlagType::Map`を作成する
+	pub fn map() -> FlagType {
+		FlagType::Map {
+			separator: '=',
+			duplicates: DuplicateKeys::LastWins,
 		}
 	}
 
 	/// If val's type is &self, returns true
 	/// valが&selfが示すタイプと一致するか判定する
 	pub fn is_type_of(&self, val: &FlagValue) -> bool {
-		Some(self) == val.get_type()
+		val.is_type(self)
 	}
 
 	/// Get `FlagValue` from val as &self type.
@@ -79,15 +271,68 @@ impl FlagType {
 				Ok(f) => FlagValue::Float(f),
 				Err(_) => FlagValue::Invalid(val),
 			},
+			FlagType::List(inner) => match inner.get_value_from_string(val) {
+				FlagValue::Invalid(val) => FlagValue::Invalid(val),
+				val => FlagValue::List(vec![val]),
+			},
+			FlagType::Count => FlagValue::Invalid(val),
+			FlagType::UInt => match val.parse::<usize>() {
+				Ok(u) => FlagValue::UInt(u),
+				Err(_) => FlagValue::Invalid(val),
+			},
+			FlagType::I64 => match val.parse::<i64>() {
+				Ok(i) => FlagValue::I64(i),
+				Err(_) => FlagValue::Invalid(val),
+			},
+			FlagType::U64 => match val.parse::<u64>() {
+				Ok(u) => FlagValue::U64(u),
+				Err(_) => FlagValue::Invalid(val),
+			},
+			FlagType::Path => FlagValue::Path(PathBuf::from(val)),
+			FlagType::Duration => match parse_duration(&val) {
+				Some(d) => FlagValue::Duration(d),
+				None => FlagValue::Invalid(val),
+			},
+			FlagType::ByteSize => match parse_byte_size(&val) {
+				Some(size) => FlagValue::ByteSize(size),
+				None => FlagValue::Invalid(val),
+			},
+			FlagType::IpAddr => match val.parse::<IpAddr>() {
+				Ok(addr) => FlagValue::IpAddr(addr),
+				Err(_) => FlagValue::Invalid(val),
+			},
+			FlagType::SocketAddr => match val.parse::<SocketAddr>() {
+				Ok(addr) => FlagValue::SocketAddr(addr),
+				Err(_) => FlagValue::Invalid(val),
+			},
+			FlagType::Char => {
+				let mut chars = val.chars();
+				match (chars.next(), chars.next()) {
+					(Some(c), None) => FlagValue::Char(c),
+					_ => FlagValue::Invalid(val),
+				}
+			}
+			FlagType::Map { separator, .. } => match val.split_once(*separator) {
+				Some((key, value)) if !key.is_empty() => {
+					FlagValue::Map(vec![(key.to_owned(), value.to_owned())])
+				}
+				_ => FlagValue::Invalid(val),
+			},
 		}
 	}
 
 	/// Returns &self type value in case of no specified value.
+	/// In case of `FlagType::Count`, this returns the value of one occurrence.
 	/// フラグとして指定された値がなかった場合、&self型のフラグはどの値として扱うかを取得する
 	pub fn get_value_if_no_value(&self) -> FlagValue {
 		match &self {
 			FlagType::Bool => FlagValue::Bool(true),
+			FlagType::Count => FlagValue::Int(1),
 			FlagType::String => FlagValue::String(String::new()),
+			FlagType::List(inner) => match inner.get_value_if_no_value() {
+				FlagValue::None => FlagValue::None,
+				val => FlagValue::List(vec![val]),
+			},
 			_ => FlagValue::None,
 		}
 	}
@@ -111,6 +356,30 @@ impl FlagType {
 	pub fn is_bool(&self) -> bool {
 		*self == FlagType::Bool
 	}
+
+	/// Returns true if flag of &self type requires value (it is not bool or count, or list of them).
+	pub fn takes_value(&self) -> bool {
+		match self {
+			FlagType::Bool | FlagType::Count => false,
+			FlagType::List(inner) => inner.takes_value(),
+			_ => true,
+		}
+	}
+
+	/// Returns true if &self is `FlagType::List`
+	pub fn is_list(&self) -> bool {
+		matches!(self, FlagType::List(_))
+	}
+
+	/// Returns true if &self equals `FlagType::Count`
+	pub fn is_count(&self) -> bool {
+		*self == FlagType::Count
+	}
+
+	/// Returns true if &self is `FlagType::Map`
+	pub fn is_map(&self) -> bool {
+		matches!(self, FlagType::Map { .. })
+	}
 }
 
 /// Enum for storage `FlagValue`
@@ -129,6 +398,39 @@ pub enum FlagValue {
 	/// Variant for float flag value
 	/// Float値保存用
 	Float(f64),
+	/// Variant for list flag value
+	/// List値（繰り返し指定されたフラグの値）保存用
+	List(Vec<FlagValue>),
+	/// Variant for unsigned int flag value
+	/// `UInt値保存用`
+	UInt(usize),
+	/// Variant for 64-bit int flag value
+	/// I64値保存用
+	I64(i64),
+	/// Variant for 64-bit unsigned int flag value
+	/// U64値保存用
+	U64(u64),
+	/// Variant for path flag value
+	/// Path値保存用
+	Path(PathBuf),
+	/// Variant for duration flag value
+	/// Duration値保存用
+	Duration(Duration),
+	/// Variant for byte size flag value (in bytes)
+	/// バイト数保存用
+	ByteSize(u64),
+	/// Variant for IP address flag value
+	/// IPアドレス保存用
+	IpAddr(IpAddr),
+	/// Variant for socket address flag value
+	/// ソケットアドレス保存用
+	SocketAddr(SocketAddr),
+	/// Variant for char flag value
+	/// char値保存用
+	Char(char),
+	/// Variant for map flag value. Pairs of key and value are kept in inputted order.
+	/// マップ値（キーと値の組を入力順に保持）保存用
+	Map(Vec<(String, String)>),
 	/// Variant for invalid flag value
 	/// 間違った値が指定されていた時にString値で指定されたフラグ値の保存用
 	Invalid(String),
@@ -150,15 +452,48 @@ impl From<&str> for FlagValue {
 	}
 }
 
+/// Implements `From` for numeric types (mainly for range limits such as `Flag::min`).
+macro_rules! impl_from_number_for_flag_value {
+	($($t:ty => $variant:ident($inner:ty)),*) => {
+		$(
+			impl From<$t> for FlagValue {
+				fn from(val: $t) -> Self {
+					FlagValue::$variant(<$inner>::from(val))
+				}
+			}
+		)*
+	};
+}
+
+impl_from_number_for_flag_value!(
+	i32 => I64(i64),
+	i64 => I64(i64),
+	isize => Int(isize),
+	u32 => U64(u64),
+	u64 => U64(u64),
+	usize => UInt(usize),
+	f32 => Float(f64),
+	f64 => Float(f64)
+);
+
 impl FlagValue {
-	/// Get &self's corresponding type of `FlagType`. Returns None if  &self is a invalid flag value.
-	/// `FlagValue`に対応する`FlagType`を取得する。無効なフラグ値の場合はNoneを返す。
+	/// Get &self's corresponding type of `FlagType`. Returns None if  &self is a invalid flag value or a list flag value.
+	/// `FlagValue`に対応する`FlagType`を取得する。無効なフラグ値かList値の場合はNoneを返す。
 	pub fn get_type(&self) -> Option<&FlagType> {
 		match self {
 			FlagValue::Bool(_) => Some(&FlagType::Bool),
 			FlagValue::String(_) => Some(&FlagType::String),
 			FlagValue::Int(_) => Some(&FlagType::Int),
 			FlagValue::Float(_) => Some(&FlagType::Float),
+			FlagValue::UInt(_) => Some(&FlagType::UInt),
+			FlagValue::I64(_) => Some(&FlagType::I64),
+			FlagValue::U64(_) => Some(&FlagType::U64),
+			FlagValue::Path(_) => Some(&FlagType::Path),
+			FlagValue::Duration(_) => Some(&FlagType::Duration),
+			FlagValue::ByteSize(_) => Some(&FlagType::ByteSize),
+			FlagValue::IpAddr(_) => Some(&FlagType::IpAddr),
+			FlagValue::SocketAddr(_) => Some(&FlagType::SocketAddr),
+			FlagValue::Char(_) => Some(&FlagType::Char),
 			_ => None,
 		}
 	}
@@ -168,17 +503,24 @@ impl FlagValue {
 		match self {
 			FlagValue::Bool(_) => &FlagType::Bool == flag_type,
 			FlagValue::String(_) => &FlagType::String == flag_type,
-			FlagValue::Int(_) => &FlagType::Int == flag_type,
+			FlagValue::Int(_) => &FlagType::Int == flag_type || &FlagType::Count == flag_type,
 			FlagValue::Float(_) => &FlagType::Float == flag_type,
-			_ => false,
+			FlagValue::List(vals) => match flag_type {
+				FlagType::List(inner) => vals.iter().all(|val| val.is_type(inner)),
+				_ => false,
+			},
+			FlagValue::Map(_) => flag_type.is_map(),
+			FlagValue::Invalid(_) | FlagValue::None => false,
+			val => val.get_type() == Some(flag_type),
 		}
 	}
 
-	/// Gets bool `FlagValue` from string
+	/// Gets bool `FlagValue` from string.
+	/// Accepts "true"/"false", "yes"/"no", "on"/"off" and "1"/"0" (ignoring case).
 	pub fn get_bool_value_from_string(val: String) -> FlagValue {
-		match val.as_str() {
-			"true" => FlagValue::Bool(true),
-			"false" => FlagValue::Bool(false),
+		match val.to_ascii_lowercase().as_str() {
+			"true" | "yes" | "on" | "1" => FlagValue::Bool(true),
+			"false" | "no" | "off" | "0" => FlagValue::Bool(false),
 			_ => FlagValue::Invalid(val),
 		}
 	}
@@ -190,6 +532,24 @@ impl FlagValue {
 			FlagValue::Bool(b) => b.to_string(),
 			FlagValue::Int(i) => i.to_string(),
 			FlagValue::Float(f) => f.to_string(),
+			FlagValue::UInt(u) => u.to_string(),
+			FlagValue::I64(i) => i.to_string(),
+			FlagValue::U64(u) | FlagValue::ByteSize(u) => u.to_string(),
+			FlagValue::Path(path) => path.to_string_lossy().into_owned(),
+			FlagValue::Duration(d) => format_duration(d),
+			FlagValue::IpAddr(addr) => addr.to_string(),
+			FlagValue::SocketAddr(addr) => addr.to_string(),
+			FlagValue::Char(c) => c.to_string(),
+			FlagValue::Map(entries) => entries
+				.into_iter()
+				.map(|(key, value)| key + "=" + &value)
+				.collect::<Vec<String>>()
+				.join(","),
+			FlagValue::List(vals) => vals
+				.into_iter()
+				.map(FlagValue::get_string_value)
+				.collect::<Vec<String>>()
+				.join(","),
 			FlagValue::Invalid(val) => val,
 			FlagValue::None => String::default(),
 		}
@@ -247,6 +607,446 @@ impl FlagValue {
 			_ => None,
 		}
 	}
+
+	/// Returns inner usize value. If self is not `FlagValue::UInt(val)`, returns None.
+	pub fn get_uint(&self) -> Option<usize> {
+		match self {
+			FlagValue::UInt(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner i64 value. If self is not `FlagValue::I64(val)`, returns None.
+	pub fn get_i64(&self) -> Option<i64> {
+		match self {
+			FlagValue::I64(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner u64 value. If self is not `FlagValue::U64(val)`, returns None.
+	pub fn get_u64(&self) -> Option<u64> {
+		match self {
+			FlagValue::U64(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns reference of inner path. If self is not `FlagValue::Path(val)`, returns None.
+	pub fn get_path(&self) -> Option<&PathBuf> {
+		match self {
+			FlagValue::Path(val) => Some(val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner duration. If self is not `FlagValue::Duration(val)`, returns None.
+	pub fn get_duration(&self) -> Option<Duration> {
+		match self {
+			FlagValue::Duration(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner size in bytes. If self is not `FlagValue::ByteSize(val)`, returns None.
+	pub fn get_byte_size(&self) -> Option<u64> {
+		match self {
+			FlagValue::ByteSize(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner IP address. If self is not `FlagValue::IpAddr(val)`, returns None.
+	pub fn get_ip_addr(&self) -> Option<IpAddr> {
+		match self {
+			FlagValue::IpAddr(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner socket address. If self is not `FlagValue::SocketAddr(val)`, returns None.
+	pub fn get_socket_addr(&self) -> Option<SocketAddr> {
+		match self {
+			FlagValue::SocketAddr(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns inner char. If self is not `FlagValue::Char(val)`, returns None.
+	pub fn get_char(&self) -> Option<char> {
+		match self {
+			FlagValue::Char(val) => Some(*val),
+			_ => None,
+		}
+	}
+
+	/// Returns reference of inner values. If self is not `FlagValue::List(vals)`, returns None.
+	pub fn get_list(&self) -> Option<&Vec<FlagValue>> {
+		match self {
+			FlagValue::List(vals) => Some(vals),
+			_ => None,
+		}
+	}
+
+	/// Returns reference of inner pairs of key and value. If self is not `FlagValue::Map(entries)`, returns None.
+	pub fn get_map(&self) -> Option<&Vec<(String, String)>> {
+		match self {
+			FlagValue::Map(entries) => Some(entries),
+			_ => None,
+		}
+	}
+
+	/// Converts self into Vec. If self is `FlagValue::List(vals)` returns vals, otherwise returns a Vec has only self.
+	pub fn into_vec(self) -> Vec<FlagValue> {
+		match self {
+			FlagValue::List(vals) => vals,
+			val => vec![val],
+		}
+	}
+}
+
+/// Trait for types which can be taken from `FlagValue` by `Context::get`.
+/// Implement this for own types to take them from flag values.
+/// `FlagValue`から値を取り出せる型のためのトレイト。独自の型に実装することで`Context::get`で取得できるようになる
+pub trait FromFlagValue: Sized {
+	/// Converts `val` into Self. If `val` cannot be converted, returns None.
+	fn from_flag_value(val: &FlagValue) -> Option<Self>;
+
+	/// Returns name of this type shown in `FlagAccessError` when value cannot be converted, such as `string` or `list of u32`.
+	/// 変換できなかった場合にエラーで表示する型名
+	fn type_name() -> String;
+}
+
+impl FromFlagValue for FlagValue {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		Some(val.clone())
+	}
+
+	fn type_name() -> String {
+		String::from("flag value")
+	}
+}
+
+impl FromFlagValue for bool {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_bool()
+	}
+
+	fn type_name() -> String {
+		String::from("bool")
+	}
+}
+
+/// Implements `FromFlagValue` for integer types. Any integer value (including byte size) fits in the type can be converted.
+macro_rules! impl_from_flag_value_for_int {
+	($($t:ty),*) => {
+		$(
+			impl FromFlagValue for $t {
+				fn from_flag_value(val: &FlagValue) -> Option<Self> {
+					match val {
+						FlagValue::Int(val) => <$t>::try_from(*val).ok(),
+						FlagValue::UInt(val) => <$t>::try_from(*val).ok(),
+						FlagValue::I64(val) => <$t>::try_from(*val).ok(),
+						FlagValue::U64(val) | FlagValue::ByteSize(val) => <$t>::try_from(*val).ok(),
+						_ => None,
+					}
+				}
+
+				fn type_name() -> String {
+					String::from(stringify!($t))
+				}
+			}
+		)*
+	};
+}
+
+impl_from_flag_value_for_int!(
+	i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl FromFlagValue for f64 {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_float()
+	}
+
+	fn type_name() -> String {
+		String::from("float")
+	}
+}
+
+impl FromFlagValue for f32 {
+	#[allow(clippy::cast_possible_truncation)]
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_float().map(|val| val as f32)
+	}
+
+	fn type_name() -> String {
+		String::from("float")
+	}
+}
+
+impl FromFlagValue for char {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_char()
+	}
+
+	fn type_name() -> String {
+		String::from("char")
+	}
+}
+
+impl FromFlagValue for String {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_string()
+	}
+
+	fn type_name() -> String {
+		String::from("string")
+	}
+}
+
+impl FromFlagValue for PathBuf {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		match val {
+			FlagValue::Path(path) => Some(path.clone()),
+			FlagValue::String(path) => Some(PathBuf::from(path)),
+			_ => None,
+		}
+	}
+
+	fn type_name() -> String {
+		String::from("path")
+	}
+}
+
+impl FromFlagValue for Duration {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_duration()
+	}
+
+	fn type_name() -> String {
+		String::from("duration")
+	}
+}
+
+impl FromFlagValue for IpAddr {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_ip_addr()
+	}
+
+	fn type_name() -> String {
+		String::from("IP address")
+	}
+}
+
+impl FromFlagValue for SocketAddr {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		val.get_socket_addr()
+	}
+
+	fn type_name() -> String {
+		String::from("socket address")
+	}
+}
+
+/// `FlagValue::None` (flag which is not inputted and does not have default value) is converted into None.
+impl<T: FromFlagValue> FromFlagValue for Option<T> {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		match val {
+			FlagValue::None => Some(None),
+			val => T::from_flag_value(val).map(Some),
+		}
+	}
+
+	fn type_name() -> String {
+		T::type_name()
+	}
+}
+
+/// List value is converted element by element. Other value is converted into Vec which has only it, and `FlagValue::None` is converted into empty Vec.
+impl<T: FromFlagValue> FromFlagValue for Vec<T> {
+	fn from_flag_value(val: &FlagValue) -> Option<Self> {
+		match val {
+			FlagValue::List(vals) => vals.iter().map(T::from_flag_value).collect(),
+			FlagValue::None => Some(Vec::new()),
+			val => T::from_flag_value(val).map(|val| vec![val]),
+		}
+	}
+
+	fn type_name() -> String {
+		format!("list of {}", T::type_name())
+	}
+}
+
+/// `FlagAccessError` shows why flag (or positional arg) value cannot be taken by `Context::get` (or `Context::get_positional`).
+/// New variants may be added in the future, so matches on it must have a wildcard arm.
+/// `Context::get`でフラグ値を取得できなかった理由を表す
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum FlagAccessError {
+	/// Shows that the flag is not defined. Inner is the flag's name.
+	Unknown(String),
+	/// Shows that the flag is not inputted and does not have default value. Inner is the flag's name.
+	Unset(String),
+	/// Shows that the flag's value cannot be converted into requested type.
+	WrongType {
+		/// The flag's name
+		name: String,
+		/// Name of requested type (`FromFlagValue::type_name`)
+		expected: String,
+		/// The flag's value
+		value: FlagValue,
+	},
+	/// Shows that the positional arg is not declared or not inputted. Inner is the positional arg's name.
+	MissingPositional(String),
+	/// Shows that the positional arg's value cannot be converted into requested type.
+	InvalidPositional {
+		/// The positional arg's name
+		name: String,
+		/// Name of requested type (`FromFlagValue::type_name`)
+		expected: String,
+		/// The positional arg's value
+		value: FlagValue,
+	},
+	/// Shows that value corresponding to sub command is requested, but no sub command is run. Inner is the command's name which is run.
+	MissingSubCommand(String),
+}
+
+impl fmt::Display for FlagAccessError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			FlagAccessError::Unknown(name) => write!(f, "flag --{name} is not defined"),
+			FlagAccessError::Unset(name) => write!(f, "flag --{name} is not set"),
+			FlagAccessError::WrongType {
+				name,
+				expected,
+				value,
+			} => write!(
+				f,
+				"value {value:?} of flag --{name} cannot be taken as {expected}"
+			),
+			FlagAccessError::MissingPositional(name) => {
+				write!(f, "positional arg <{name}> is not inputted")
+			}
+			FlagAccessError::InvalidPositional {
+				name,
+				expected,
+				value,
+			} => write!(
+				f,
+				"value {value:?} of positional arg <{name}> cannot be taken as {expected}"
+			),
+			FlagAccessError::MissingSubCommand(name) => {
+				write!(f, "sub command of {name} is not run")
+			}
+		}
+	}
+}
+
+impl Error for FlagAccessError {}
+
+/// Parses humanized duration such as `10s`, `1.5h`, `1h30m` or `500ms`.
+/// Available units are `ns`, `us`(`µs`), `ms`, `s`, `m`(`min`), `h` and `d`. Number without unit means seconds.
+/// `10s`、`1h30m`、`500ms`のような時間長をパースする。単位のない数値は秒とみなす
+pub fn parse_duration(val: &str) -> Option<Duration> {
+	let val = val.trim();
+	if let Ok(secs) = val.parse::<f64>() {
+		return Duration::try_from_secs_f64(secs).ok();
+	}
+	let mut rest = val;
+	let mut total = Duration::ZERO;
+	while !rest.is_empty() {
+		let num_end = rest
+			.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+			.unwrap_or(rest.len());
+		let (num, after) = rest.split_at(num_end);
+		let unit_end = after
+			.find(|c: char| c.is_ascii_digit() || c == '.')
+			.unwrap_or(after.len());
+		let (unit, after) = after.split_at(unit_end);
+		let secs_per_unit = match unit.trim() {
+			"ns" => 1e-9,
+			"us" | "µs" => 1e-6,
+			"ms" => 1e-3,
+			"s" | "sec" => 1.0,
+			"m" | "min" => 60.0,
+			"h" => 3600.0,
+			"d" => 86400.0,
+			_ => return None,
+		};
+		let num = num.parse::<f64>().ok()?;
+		total = total.checked_add(Duration::try_from_secs_f64(num * secs_per_unit).ok()?)?;
+		rest = after.trim_start();
+	}
+	if val.is_empty() { None } else { Some(total) }
+}
+
+/// Formats duration in the form parsed by `parse_duration`, such as `1h30m` or `500ms`.
+pub fn format_duration(d: Duration) -> String {
+	let mut secs = d.as_secs();
+	let nanos = d.subsec_nanos();
+	if secs == 0 && nanos == 0 {
+		return String::from("0s");
+	}
+	let mut formatted = String::new();
+	for (unit, unit_secs) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+		if secs >= unit_secs {
+			formatted.push_str(&format!("{}{}", secs / unit_secs, unit));
+			secs %= unit_secs;
+		}
+	}
+	if nanos > 0 {
+		if nanos.is_multiple_of(1_000_000) {
+			formatted.push_str(&format!("{}ms", nanos / 1_000_000));
+		} else if nanos.is_multiple_of(1000) {
+			formatted.push_str(&format!("{}us", nanos / 1000));
+		} else {
+			formatted.push_str(&format!("{nanos}ns"));
+		}
+	}
+	formatted
+}
+
+/// Parses size in bytes such as `512`, `512B`, `10KB`, `1.5GiB`.
+/// Decimal units (`K`/`KB`, `M`/`MB`, `G`/`GB`, `T`/`TB`, `P`/`PB`) are powers of 1000, and binary units (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`) are powers of 1024. Units are case insensitive.
+/// `512`、`10KB`、`1.5GiB`のようなバイト数をパースする
+pub fn parse_byte_size(val: &str) -> Option<u64> {
+	let val = val.trim();
+	let num_end = val
+		.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+		.unwrap_or(val.len());
+	let (num, unit) = val.split_at(num_end);
+	let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+		"" | "b" => 1,
+		"k" | "kb" => 1000,
+		"m" | "mb" => 1000u64.pow(2),
+		"g" | "gb" => 1000u64.pow(3),
+		"t" | "tb" => 1000u64.pow(4),
+		"p" | "pb" => 1000u64.pow(5),
+		"ki" | "kib" => 1 << 10,
+		"mi" | "mib" => 1 << 20,
+		"gi" | "gib" => 1 << 30,
+		"ti" | "tib" => 1 << 40,
+		"pi" | "pib" => 1 << 50,
+		_ => return None,
+	};
+	match num.parse::<u64>() {
+		Ok(num) => num.checked_mul(multiplier),
+		Err(_) => {
+			#[allow(
+				clippy::cast_precision_loss,
+				clippy::cast_possible_truncation,
+				clippy::cast_sign_loss
+			)]
+			let size = (num.parse::<f64>().ok()? * multiplier as f64).round();
+			#[allow(
+				clippy::cast_precision_loss,
+				clippy::cast_possible_truncation,
+				clippy::cast_sign_loss
+			)]
+			(size.is_finite() && size <= u64::MAX as f64).then_some(size as u64)
+		}
+	}
 }
 
 /// Creates a new instance of Flag
@@ -274,6 +1074,19 @@ impl Flag {
 			short_alias: Vector::default(),
 			flag_type,
 			default_value,
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		}
 	}
 
@@ -293,6 +1106,19 @@ impl Flag {
 			long_alias,
 			flag_type,
 			default_value,
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		}
 	}
 
@@ -305,6 +1131,19 @@ impl Flag {
 			long_alias: Vector::default(),
 			flag_type: FlagType::default(),
 			default_value: FlagValue::Bool(bool::default()),
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		}
 	}
 
@@ -318,6 +1157,19 @@ impl Flag {
 			long_alias: Vector::default(),
 			flag_type,
 			default_value,
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		}
 	}
 
@@ -341,6 +1193,66 @@ impl Flag {
 		new_typed_flag!(name, Float)
 	}
 
+	/// Creates a new instance of unsigned int Flag
+	pub fn new_uint<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::UInt)
+	}
+
+	/// Creates a new instance of 64-bit int Flag
+	pub fn new_i64<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::I64)
+	}
+
+	/// Creates a new instance of 64-bit unsigned int Flag
+	pub fn new_u64<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::U64)
+	}
+
+	/// Creates a new instance of path Flag
+	pub fn new_path<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::Path)
+	}
+
+	/// Creates a new instance of duration Flag, whose value is such as `10s` or `1h30m`.
+	pub fn new_duration<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::Duration)
+	}
+
+	/// Creates a new instance of byte size Flag, whose value is such as `512MiB`.
+	pub fn new_byte_size<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::ByteSize)
+	}
+
+	/// Creates a new instance of IP address Flag
+	pub fn new_ip_addr<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::IpAddr)
+	}
+
+	/// Creates a new instance of socket address Flag, whose value is such as `127.0.0.1:8080`.
+	pub fn new_socket_addr<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::SocketAddr)
+	}
+
+	/// Creates a new instance of char Flag
+	pub fn new_char<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::Char)
+	}
+
+	/// Creates a new instance of map Flag, which can be specified repeatedly (such as `-D key=value`) and accumulates pairs of key and value.
+	pub fn new_map<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::map())
+	}
+
+	/// Creates a new instance of count Flag, whose value is the number of occurrences.
+	pub fn new_count<T: Into<String>>(name: T) -> Self {
+		Flag::with_name_and_type(name, FlagType::Count)
+	}
+
+	/// Creates a new instance of list Flag, which can be specified repeatedly and collects values as `inner` type.
+	pub fn new_list<T: Into<String>>(name: T, inner: FlagType) -> Self {
+		Flag::with_name_and_type(name, FlagType::list(inner))
+	}
+
 	/// Add an short alias to this Flag
 	pub fn short_alias<T: Into<char>>(mut self, a: T) -> Self {
 		self.short_alias.push(a.into());
@@ -353,6 +1265,231 @@ impl Flag {
 		self
 	}
 
+	/// Set whether this flag can be negated by `--no-<name>` (only for bool flag)
+	pub fn negatable(mut self, negatable: bool) -> Self {
+		self.negatable = negatable;
+		self
+	}
+
+	/// Returns true if this flag is bool flag and can be negated by `--no-<name>`
+	pub fn is_negatable(&self) -> bool {
+		self.negatable && self.flag_type.is_bool()
+	}
+
+	/// Set whether numeric short flag such as `-5` is interpreted as this flag's value
+	pub fn numeric_shorthand(mut self, numeric_shorthand: bool) -> Self {
+		self.numeric_shorthand = numeric_shorthand;
+		self
+	}
+
+	/// Set whether this flag must be inputted
+	pub fn required(mut self, required: bool) -> Self {
+		self.required = required;
+		self
+	}
+
+	/// Add name of flag which cannot be inputted with this flag
+	pub fn conflicts_with<T: Into<String>>(mut self, name: T) -> Self {
+		self.conflicts_with.push(name.into());
+		self
+	}
+
+	/// Add name of flag which must be inputted when this flag is inputted
+	pub fn requires<T: Into<String>>(mut self, name: T) -> Self {
+		self.requires.push(name.into());
+		self
+	}
+
+	/// Set name of environment variable whose value is used when this flag is not inputted
+	pub fn env<T: Into<String>>(mut self, name: T) -> Self {
+		self.env = Some(name.into());
+		self
+	}
+
+	/// Set separator of key and value (for map flag)
+	pub fn map_separator(mut self, separator: char) -> Self {
+		if let FlagType::Map { separator: sep, .. } = &mut self.flag_type {
+			*sep = separator;
+		}
+		self
+	}
+
+	/// Set policy for duplicate keys (for map flag)
+	pub fn duplicate_keys(mut self, duplicates: DuplicateKeys) -> Self {
+		if let FlagType::Map {
+			duplicates: dup, ..
+		} = &mut self.flag_type
+		{
+			*dup = duplicates;
+		}
+		self
+	}
+
+	/// Set delimiter of values in one arg (for list flag), such as `,` for `--tags a,b,c`.
+	pub fn value_delimiter(mut self, delimiter: char) -> Self {
+		self.value_delimiter = Some(delimiter);
+		self
+	}
+
+	/// Returns true if this is list flag with value delimiter.
+	pub fn is_delimited_list(&self) -> bool {
+		self.flag_type.is_list() && self.value_delimiter.is_some()
+	}
+
+	/// Returns index and content of the first invalid element in `arg` split by value delimiter.
+	/// If this is not list flag with value delimiter or all elements are valid, returns None.
+	pub fn find_invalid_element(&self, arg: &str) -> Option<(usize, String)> {
+		let (FlagType::List(inner), Some(delimiter)) = (&self.flag_type, self.value_delimiter) else {
+			return None;
+		};
+		arg.split(delimiter).enumerate().find_map(|(index, elem)| {
+			match inner.get_value_from_string(elem.to_owned()) {
+				FlagValue::Invalid(elem) => Some((index, elem)),
+				_ => None,
+			}
+		})
+	}
+
+	/// Set minimum value of this flag (for numeric flag, such as `.min(1)` or `.min(0.5)`).
+	/// Integer limits are compared with integer values exactly, and float is compared with any number.
+	/// If this flag's value cannot be compared with `min` (such as string limit for int flag), validation of the value fails.
+	/// 最小値を設定する。比較できない型の値は検証に失敗する
+	pub fn min(mut self, min: impl Into<FlagValue>) -> Self {
+		self.min = Some(min.into());
+		self
+	}
+
+	/// Set maximum value of this flag (for numeric flag, such as `.max(10)` or `.max(1.0)`). See `Flag::min`.
+	/// 最大値を設定する
+	pub fn max(mut self, max: impl Into<FlagValue>) -> Self {
+		self.max = Some(max.into());
+		self
+	}
+
+	/// Set range (inclusive) of this flag's value (for numeric flag). See `Flag::min`.
+	pub fn range(self, min: impl Into<FlagValue>, max: impl Into<FlagValue>) -> Self {
+		self.min(min).max(max)
+	}
+
+	/// Set minimum length (count of chars) of this flag's value (for string flag)
+	pub fn min_len(mut self, min_len: usize) -> Self {
+		self.min_len = Some(min_len);
+		self
+	}
+
+	/// Set maximum length (count of chars) of this flag's value (for string flag)
+	pub fn max_len(mut self, max_len: usize) -> Self {
+		self.max_len = Some(max_len);
+		self
+	}
+
+	/// Set custom validator of this flag's value
+	pub fn validator(mut self, validator: fn(&FlagValue) -> Result<(), String>) -> Self {
+		self.validator = Some(Validator(validator));
+		self
+	}
+
+	/// Validates `val` by range, length limit and custom validator of this flag. If `val` is a list, range and length limit are checked for each value.
+	/// 範囲、長さ制限、カスタムバリデータで値を検証する。リストの場合、範囲と長さ制限は各値についてチェックする
+	pub fn validate(&self, val: &FlagValue) -> Result<(), String> {
+		match val {
+			FlagValue::List(vals) => {
+				for val in vals {
+					self.validate_limits(val)?;
+				}
+			}
+			val => self.validate_limits(val)?,
+		}
+		match self.validator {
+			Some(Validator(validator)) => validator(val),
+			None => Ok(()),
+		}
+	}
+
+	fn validate_limits(&self, val: &FlagValue) -> Result<(), String> {
+		if let FlagValue::String(s) = val {
+			let len = s.chars().count();
+			return if self.min_len.is_some_and(|min| len < min)
+				|| self.max_len.is_some_and(|max| len > max)
+			{
+				Err(format!(
+					"length must be {}",
+					limits_str(self.min_len, self.max_len)
+				))
+			} else {
+				Ok(())
+			};
+		}
+		if matches!(val, FlagValue::None | FlagValue::Invalid(_)) {
+			return Ok(());
+		}
+		// 比較できない場合（型が合わない場合）も範囲外とする
+		let below = self.min.as_ref().is_some_and(|min| {
+			!matches!(
+				compare_limit(val, min),
+				Some(Ordering::Greater | Ordering::Equal)
+			)
+		});
+		let above = self.max.as_ref().is_some_and(|max| {
+			!matches!(
+				compare_limit(val, max),
+				Some(Ordering::Less | Ordering::Equal)
+			)
+		});
+		if below || above {
+			Err(format!(
+				"value must be {}",
+				limits_str(self.min_str(), self.max_str())
+			))
+		} else {
+			Ok(())
+		}
+	}
+
+	fn min_str(&self) -> Option<String> {
+		self.min.clone().map(FlagValue::get_string_value)
+	}
+
+	fn max_str(&self) -> Option<String> {
+		self.max.clone().map(FlagValue::get_string_value)
+	}
+
+	/// Returns range of this flag's value such as `1..=10`, `1..` or `..=10`. If no limit is set, returns None.
+	pub fn range_str(&self) -> Option<String> {
+		match (self.min_str(), self.max_str()) {
+			(None, None) => None,
+			(min, max) => Some(range_str(min, max)),
+		}
+	}
+
+	/// Returns range of this flag's value length such as `1..=10`, `1..` or `..=10`. If no limit is set, returns None.
+	pub fn length_str(&self) -> Option<String> {
+		match (self.min_len, self.max_len) {
+			(None, None) => None,
+			(min, max) => Some(range_str(min, max)),
+		}
+	}
+
+	/// Derives environment variable name from `prefix` and this flag's name, such as `MYTOOL_DRY_RUN` for `dry-run` with prefix `MYTOOL`.
+	pub fn derive_env_name(&self, prefix: &str) -> String {
+		format!(
+			"{}_{}",
+			prefix.trim_end_matches('_'),
+			self.name.to_uppercase().replace('-', "_")
+		)
+	}
+
+	/// Returns true if this flag requires value, so value can be attached to its short alias (such as `-ofile`).
+	/// Flag whose value is optional (declared by `default_missing_value`) does not require value.
+	pub fn takes_value(&self) -> bool {
+		self.default_missing_value.is_none() && self.flag_type.takes_value()
+	}
+
+	/// Returns true if this flag's value is optional (`default_missing_value` is set).
+	pub fn has_optional_value(&self) -> bool {
+		self.default_missing_value.is_some()
+	}
+
 	/// Set this flag's default value
 	pub fn default_value(mut self, default_value: FlagValue) -> Self {
 		if self.flag_type.is_type_of(&default_value) {
@@ -366,6 +1503,22 @@ impl Flag {
 		self
 	}
 
+	/// Set value used when this flag is inputted without value (such as `--color` for `--color=always`).
+	/// After this is set, this flag's value is optional, so next arg is never taken as its value.
+	/// Its value can be given by eq (such as `--color=never`) or attached to its short alias (such as `-cnever`).
+	/// 値なしで指定された場合の値を設定する（値の指定は省略可能になる）
+	pub fn default_missing_value(mut self, default_missing_value: FlagValue) -> Self {
+		if self.flag_type.is_type_of(&default_missing_value) {
+			self.default_missing_value = Some(default_missing_value)
+		} else {
+			println!(
+				"not match flag_type: {}. default_missing_value is not changed.",
+				self.flag_type.name()
+			);
+		}
+		self
+	}
+
 	/// Set this flag's description
 	pub fn description<T: Into<String>>(mut self, description: T) -> Self {
 		self.description = description.into();
@@ -409,23 +1562,119 @@ impl Flag {
 		}
 	}
 
+	/// Returns true if &self's name equals name of arg ignoring case.
+	pub fn is_ignore_case(&self, name: &str) -> bool {
+		self.name.to_lowercase() == name.to_lowercase()
+	}
+
+	/// Returns true if alias equals one of short alias ignoring case.
+	pub fn is_short_ignore_case(&self, alias: &char) -> bool {
+		match &self.short_alias {
+			Vector(None) => false,
+			Vector(Some(short_alias)) => short_alias
+				.iter()
+				.any(|s| s.to_lowercase().eq(alias.to_lowercase())),
+		}
+	}
+
+	/// Returns true if alias equals one of long alias ignoring case.
+	pub fn is_long_ignore_case(&self, alias: &str) -> bool {
+		match &self.long_alias {
+			Vector(None) => false,
+			Vector(Some(long_alias)) => {
+				let alias = alias.to_lowercase();
+				long_alias.iter().any(|s| s.to_lowercase() == alias)
+			}
+		}
+	}
+
+	/// Returns true if name or one of long alias starts with `prefix`.
+	pub fn has_long_prefix(&self, prefix: &str) -> bool {
+		self.name.starts_with(prefix)
+			|| match &self.long_alias {
+				Vector(None) => false,
+				Vector(Some(long_alias)) => long_alias.iter().any(|l| l.starts_with(prefix)),
+			}
+	}
+
+	/// Returns true if name or one of long alias starts with `prefix` ignoring case.
+	pub fn has_long_prefix_ignore_case(&self, prefix: &str) -> bool {
+		let prefix = prefix.to_lowercase();
+		self.name.to_lowercase().starts_with(&prefix)
+			|| match &self.long_alias {
+				Vector(None) => false,
+				Vector(Some(long_alias)) => long_alias
+					.iter()
+					.any(|l| l.to_lowercase().starts_with(&prefix)),
+			}
+	}
+
 	/// Get this Flag's name's clone
 	pub fn get_name_clone(&self) -> String {
 		self.name.clone()
 	}
 
+	/// Derives this Flag's value from value given outside of args (environment variable or config).
+	/// Count flag takes the count itself (such as `3`), otherwise this is same as `derive_flag_value_from_string`.
+	/// 環境変数や設定ファイルの値からフラグ値を導出する。カウントフラグは回数そのものを値とする
+	pub fn derive_flag_value_from_setting(&self, val: String) -> FlagValue {
+		if self.flag_type.is_count() {
+			match val.parse::<isize>() {
+				Ok(count) => FlagValue::Int(count),
+				Err(_) => FlagValue::Invalid(val),
+			}
+		} else {
+			self.derive_flag_value_from_string(val)
+		}
+	}
+
 	/// Derives this Flag's value from arg
-	/// Alias of `self.flag_type.get_value_from_string(arg)`
-	#[inline]
+	/// If this is list flag with value delimiter, arg is split by the delimiter and each element is converted as inner type (if any element is invalid, returns `FlagValue::Invalid` of whole arg).
+	/// Otherwise, this is same as `self.flag_type.get_value_from_string(arg)`.
 	pub fn derive_flag_value_from_string(&self, arg: String) -> FlagValue {
-		self.flag_type.get_value_from_string(arg)
+		let (FlagType::List(inner), Some(delimiter)) = (&self.flag_type, self.value_delimiter) else {
+			return self.flag_type.get_value_from_string(arg);
+		};
+		let mut vals = Vec::new();
+		for elem in arg.split(delimiter) {
+			match inner.get_value_from_string(elem.to_owned()) {
+				FlagValue::Invalid(_) => return FlagValue::Invalid(arg),
+				val => vals.push(val),
+			}
+		}
+		FlagValue::List(vals)
+	}
+
+	/// Derives this Flag's value from next arg of this flag (such as `value` of `--flag value`).
+	/// If this flag's value is optional, next arg is not taken as value, so this returns `FlagValue::Invalid(arg)`.
+	/// Bool flag takes only "true" or "false" as next arg (other literals such as "yes" or "1" are accepted only in `--flag=value` form).
+	/// List flag with value delimiter takes arg including the delimiter even if it has invalid element. Such value is returned as `FlagValue::List(vec![FlagValue::Invalid(arg)])`, and reported by `Parser::locate_invalid_elements` in the same way as `--flag=value` form.
+	pub fn derive_flag_value_from_next_arg(&self, arg: String) -> FlagValue {
+		if self.has_optional_value() {
+			FlagValue::Invalid(arg)
+		} else if self.is_delimited_list() && self.value_delimiter.is_some_and(|d| arg.contains(d)) {
+			match self.derive_flag_value_from_string(arg) {
+				FlagValue::Invalid(arg) => FlagValue::List(vec![FlagValue::Invalid(arg)]),
+				val => val,
+			}
+		} else if self.flag_type.is_bool() {
+			match arg.as_str() {
+				"true" => FlagValue::Bool(true),
+				"false" => FlagValue::Bool(false),
+				_ => FlagValue::Invalid(arg),
+			}
+		} else {
+			self.derive_flag_value_from_string(arg)
+		}
 	}
 
 	/// Derives this Flag's value in case of no value
-	/// Alias of `self.flag_type.get_value_if_no_value()`
-	#[inline]
+	/// If `default_missing_value` is set, returns it. Otherwise, this is same as `self.flag_type.get_value_if_no_value()`.
 	pub fn derive_flag_value_if_no_value(&self) -> FlagValue {
-		self.flag_type.get_value_if_no_value()
+		match &self.default_missing_value {
+			Some(val) => val.clone(),
+			None => self.flag_type.get_value_if_no_value(),
+		}
 	}
 }
 
@@ -438,6 +1687,19 @@ impl From<String> for Flag {
 			long_alias: Vector::default(),
 			default_value: FlagValue::default(),
 			flag_type: FlagType::default(),
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		}
 	}
 }
@@ -451,8 +1713,86 @@ impl From<&str> for Flag {
 			long_alias: Vector::default(),
 			default_value: FlagValue::default(),
 			flag_type: FlagType::default(),
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
+		}
+	}
+}
+
+/// Enum shows constraint of `FlagGroup`
+/// フラググループの制約を示すEnumです。
+#[derive(PartialOrd, PartialEq, Clone, Copy, Debug)]
+pub enum GroupKind {
+	/// At least one flag of group must be inputted.
+	AtLeastOne,
+	/// Exactly one flag of group must be inputted.
+	ExactlyOne,
+	/// At most one flag of group can be inputted.
+	AtMostOne,
+}
+
+/// Struct for group of flags constrained how many of them can be inputted
+/// 指定できる数に制約があるフラグのグループ
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlagGroup {
+	/// Constraint of this group
+	pub kind: GroupKind,
+	/// Names of flags in this group
+	pub flags: Vec<String>,
+}
+
+impl FlagGroup {
+	/// Creates a new instance of `FlagGroup`
+	pub fn new<T: Into<String>>(kind: GroupKind, flags: Vec<T>) -> Self {
+		FlagGroup {
+			kind,
+			flags: flags.into_iter().map(Into::into).collect(),
 		}
 	}
+
+	/// Creates a new group whose flags at least one of must be inputted
+	pub fn at_least_one<T: Into<String>>(flags: Vec<T>) -> Self {
+		FlagGroup::new(GroupKind::AtLeastOne, flags)
+	}
+
+	/// Creates a new group whose flags exactly one of must be inputted
+	pub fn exactly_one<T: Into<String>>(flags: Vec<T>) -> Self {
+		FlagGroup::new(GroupKind::ExactlyOne, flags)
+	}
+
+	/// Creates a new group whose flags at most one of can be inputted
+	pub fn at_most_one<T: Into<String>>(flags: Vec<T>) -> Self {
+		FlagGroup::new(GroupKind::AtMostOne, flags)
+	}
+
+	/// Returns description of this group's constraint, such as "exactly one of --file, --stdin".
+	pub fn constraint_str(&self) -> String {
+		format!(
+			"{} of {}",
+			match self.kind {
+				GroupKind::AtLeastOne => "at least one",
+				GroupKind::ExactlyOne => "exactly one",
+				GroupKind::AtMostOne => "at most one",
+			},
+			self
+				.flags
+				.iter()
+				.map(|f| format!("--{f}"))
+				.collect::<Vec<String>>()
+				.join(", ")
+		)
+	}
 }
 
 /// Flag's presets
@@ -478,6 +1818,16 @@ pub mod presets {
 		crate::version_flag!(->description.into())
 	}
 
+	/// Creates preset config flag (`--config <path>`). Config file of the path is used instead of command's config.
+	pub fn config_flag() -> Flag {
+		Flag::new_string("config").description("Path of config file to use")
+	}
+
+	/// Creates preset config flag with description specified.
+	pub fn config_flag_with_description<T: Into<String>>(description: T) -> Flag {
+		Flag::new_string("config").description(description)
+	}
+
 	/// Creates preset authors flag
 	pub fn authors_flag() -> Flag {
 		crate::authors_flag!()
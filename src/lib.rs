@@ -20,24 +20,40 @@ pub mod action;
 /// command is a module about command
 pub mod command;
 
+/// config is a module about config file
+pub mod config;
 /// context is a module about context
 mod context;
+/// derive is a module about traits implemented by derive macros (`combu-derive`)
+pub mod derive;
 /// flag is a module about flag
 pub mod flag;
+/// `os_args` is a module about conversion of args which may not be valid UTF-8
+pub mod os_args;
 /// parser is a module about command args parser
 pub mod parser;
+/// positional is a module about positional arg
+pub mod positional;
+/// `response_file` is a module about response file (`@path` arg) expansion
+pub mod response_file;
 /// vector is a moudle about vector
 pub mod vector;
 
 pub use action::{
 	Action, ActionError, ActionResult, ActionResult::Done, ActionResult::ParentActionRequest,
 };
-pub use command::Command;
+pub use command::{Command, ParseFailure, ParseOutcome, Strictness};
 pub use context::Context;
-pub use flag::{Flag, FlagType, FlagValue};
+pub use flag::{Flag, FlagAccessError, FlagGroup, FlagType, FlagValue, FromFlagValue};
 // pub use hook::Hook;
+pub use config::Config;
 pub use parser::Parser;
+pub use positional::Positional;
 pub use vector::Vector;
 
+/// Derive macros to build commands from structs and enums (enabled by `derive` feature)
+#[cfg(feature = "derive")]
+pub use combu_derive::{CombuArgs, CombuSubcommands};
+
 /// Macros for combu
 pub mod pub_macros;
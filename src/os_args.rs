@@ -0,0 +1,243 @@
+use std::{
+	ffi::{OsStr, OsString},
+	ops::RangeInclusive,
+};
+
+/// Range of chars used as markers (supplementary private use areas).
+const MARKERS: RangeInclusive<u32> = 0xF_0000..=0x10_FFFD;
+
+/// Args which are not valid UTF-8 (inputted by `Command::run_from_os_args`) and their originals.
+/// Each of them is converted into string whose invalid sequences are replaced by its own marker (a private use char which is not used in any arg),
+/// so values taken from it while parsing can be traced back to it without ambiguity.
+/// After parsing, originals are kept by slot (such as index of args or flag value) and markers are replaced by `U+FFFD`.
+/// UTF-8として不正な引数とその元の値。パース中は引数ごとの目印で元の引数を特定し、パース後は格納場所ごとに元の値を保持する。
+#[derive(Debug, Clone, Default)]
+pub struct NonUtf8Args {
+	/// Tuples of marker, converted arg (including marker) and the original
+	marked: Vec<(char, String, OsString)>,
+	/// Originals kept by slot after parsing
+	originals: Vec<(Slot, OsString)>,
+}
+
+/// Slot of context where a value taken from non-UTF-8 arg is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Slot {
+	/// Index of `raw_args`
+	RawArg(usize),
+	/// Index of `args`
+	Arg(usize),
+	/// Index of `trailing_args`
+	TrailingArg(usize),
+	/// Index of `local_flags_values` and index of element in the value (0 if it is not list)
+	LocalFlag(usize, usize),
+	/// Index of `common_flags_values` and index of element in the value (0 if it is not list)
+	CommonFlag(usize, usize),
+	/// Index of `positional_values` and index of element in the value (0 if it is not variadic)
+	Positional(usize, usize),
+}
+
+/// Converts `raw_args` into Strings. Args which are not valid UTF-8 are converted with markers, and returned as `NonUtf8Args` with their originals.
+/// 引数をStringに変換する。UTF-8として不正な引数は不正部分を目印に置き換えて変換し、元の引数とともに返す。
+pub fn convert(raw_args: Vec<OsString>) -> (Vec<String>, NonUtf8Args) {
+	let used: Vec<char> = raw_args
+		.iter()
+		.flat_map(|arg| arg.to_string_lossy().chars().collect::<Vec<char>>())
+		.filter(|c| MARKERS.contains(&u32::from(*c)))
+		.collect();
+	let mut markers = MARKERS
+		.filter_map(char::from_u32)
+		.filter(|c| !used.contains(c));
+	let mut non_utf8_args = NonUtf8Args::default();
+	let args = raw_args
+		.into_iter()
+		.map(|arg| match arg.into_string() {
+			Ok(arg) => arg,
+			Err(original) => {
+				let marker = markers.next().unwrap_or(char::REPLACEMENT_CHARACTER);
+				let converted = mark(&original, marker);
+				non_utf8_args
+					.marked
+					.push((marker, converted.clone(), original));
+				converted
+			}
+		})
+		.collect();
+	(args, non_utf8_args)
+}
+
+impl NonUtf8Args {
+	/// Returns true if there is no non-UTF-8 arg.
+	pub fn is_empty(&self) -> bool {
+		self.marked.is_empty()
+	}
+
+	/// Returns the entry of non-UTF-8 arg whose marker is `c`.
+	fn entry(&self, c: char) -> Option<&(char, String, OsString)> {
+		self.marked.iter().find(|(marker, _, _)| *marker == c)
+	}
+
+	/// Returns true if `val` includes part taken from non-UTF-8 arg (before markers are replaced).
+	pub(crate) fn is_marked(&self, val: &str) -> bool {
+		val.chars().any(|c| self.entry(c).is_some())
+	}
+
+	/// Restores original `OsString` of `val`, which must be a converted arg itself or its part after valid UTF-8 prefix (such as value of `--flag=value` or `-fvalue`).
+	/// If `val` is not taken from non-UTF-8 arg or cannot be restored, returns None.
+	pub(crate) fn restore(&self, val: &str) -> Option<OsString> {
+		let (_, converted, original) = val.chars().find_map(|c| self.entry(c))?;
+		match converted.strip_suffix(val)? {
+			"" => Some(original.clone()),
+			prefix if self.is_marked(prefix) => None,
+			prefix => strip_prefix(original, prefix),
+		}
+	}
+
+	/// Keeps original of `val` for `slot` if `val` is taken from non-UTF-8 arg and can be restored. Returns true if kept.
+	pub(crate) fn keep(&mut self, slot: Slot, val: &str) -> bool {
+		match self.restore(val) {
+			Some(original) => {
+				self.originals.push((slot, original));
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Returns original kept for `slot`.
+	pub(crate) fn original(&self, slot: Slot) -> Option<&OsString> {
+		self
+			.originals
+			.iter()
+			.find(|(kept, _)| *kept == slot)
+			.map(|(_, original)| original)
+	}
+
+	/// Replaces markers in `val` by `U+FFFD`.
+	pub(crate) fn unmark(&self, val: &mut String) {
+		if self.is_marked(val) {
+			*val = val.chars().map(|c| self.unmark_char(c)).collect();
+		}
+	}
+
+	/// Returns `U+FFFD` if `c` is marker, otherwise returns `c`.
+	pub(crate) fn unmark_char(&self, c: char) -> char {
+		if self.entry(c).is_some() {
+			char::REPLACEMENT_CHARACTER
+		} else {
+			c
+		}
+	}
+}
+
+/// Converts `os` into String whose invalid sequences are replaced by `marker`.
+#[cfg(unix)]
+fn mark(os: &OsStr, marker: char) -> String {
+	use std::os::unix::ffi::OsStrExt;
+	let mut converted = String::new();
+	for chunk in os.as_bytes().utf8_chunks() {
+		converted.push_str(chunk.valid());
+		if !chunk.invalid().is_empty() {
+			converted.push(marker);
+		}
+	}
+	converted
+}
+
+/// Converts `os` into String whose invalid sequences are replaced by `marker`.
+#[cfg(windows)]
+fn mark(os: &OsStr, marker: char) -> String {
+	use std::os::windows::ffi::OsStrExt;
+	char::decode_utf16(os.encode_wide())
+		.map(|c| c.unwrap_or(marker))
+		.collect()
+}
+
+/// Converts `os` into String whose invalid sequences are replaced by `marker`.
+#[cfg(not(any(unix, windows)))]
+fn mark(os: &OsStr, marker: char) -> String {
+	os.to_string_lossy()
+		.replace(char::REPLACEMENT_CHARACTER, &marker.to_string())
+}
+
+/// Removes valid UTF-8 `prefix` from `os`. If `os` does not start with `prefix`, returns None.
+#[cfg(unix)]
+fn strip_prefix(os: &OsStr, prefix: &str) -> Option<OsString> {
+	use std::os::unix::ffi::OsStrExt;
+	os.as_bytes()
+		.strip_prefix(prefix.as_bytes())
+		.map(|rest| OsStr::from_bytes(rest).to_owned())
+}
+
+/// Removes valid UTF-8 `prefix` from `os`. If `os` does not start with `prefix`, returns None.
+#[cfg(windows)]
+fn strip_prefix(os: &OsStr, prefix: &str) -> Option<OsString> {
+	use std::os::windows::ffi::{OsStrExt, OsStringExt};
+	let wide: Vec<u16> = os.encode_wide().collect();
+	let prefix: Vec<u16> = prefix.encode_utf16().collect();
+	wide
+		.strip_prefix(prefix.as_slice())
+		.map(OsString::from_wide)
+}
+
+/// Removes valid UTF-8 `prefix` from `os`. On this platform, part of non-UTF-8 arg cannot be restored.
+#[cfg(not(any(unix, windows)))]
+fn strip_prefix(_: &OsStr, _: &str) -> Option<OsString> {
+	None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::{Slot, convert};
+	use std::{
+		ffi::{OsStr, OsString},
+		os::unix::ffi::OsStrExt,
+	};
+
+	fn os(bytes: &[u8]) -> OsString {
+		OsStr::from_bytes(bytes).to_owned()
+	}
+
+	#[test]
+	fn convert_and_restore_test() {
+		let (mut args, mut non_utf8_args) = convert(vec![
+			os(b"exe"),
+			os(b"a\xffb"),
+			os(b"--name=c\xfe"),
+			os(b"plain\xef\xbf\xbd"),
+			os(b"a\xfeb"),
+		]);
+		assert_eq!(args[0], "exe");
+		assert_eq!(args[3], "plain\u{FFFD}");
+		// 同じ変換結果になる引数も区別できる
+		assert_ne!(args[1], args[4]);
+		assert_eq!(non_utf8_args.restore(&args[1]), Some(os(b"a\xffb")));
+		assert_eq!(non_utf8_args.restore(&args[4]), Some(os(b"a\xfeb")));
+		assert_eq!(non_utf8_args.restore(&args[2][7..]), Some(os(b"c\xfe")));
+		// 正しいUTF-8の引数は置換文字を含んでいても復元対象にならない
+		assert!(!non_utf8_args.is_marked(&args[3]));
+		assert_eq!(non_utf8_args.restore(&args[3]), None);
+		let rest: String = args[1].chars().skip(1).collect();
+		assert_eq!(non_utf8_args.restore(&rest), Some(os(b"\xffb")));
+		// 不正な部分を含む接頭辞は取り除けない
+		let last: String = args[1].chars().skip(2).collect();
+		assert_eq!(non_utf8_args.restore(&last), None);
+
+		assert!(non_utf8_args.keep(Slot::Arg(0), &args[4]));
+		assert!(!non_utf8_args.keep(Slot::Arg(1), &args[3]));
+		assert_eq!(non_utf8_args.original(Slot::Arg(0)), Some(&os(b"a\xfeb")));
+		assert_eq!(non_utf8_args.original(Slot::Arg(1)), None);
+		for arg in &mut args {
+			non_utf8_args.unmark(arg);
+		}
+		assert_eq!(
+			args,
+			vec![
+				"exe",
+				"a\u{FFFD}b",
+				"--name=c\u{FFFD}",
+				"plain\u{FFFD}",
+				"a\u{FFFD}b"
+			]
+		);
+	}
+}
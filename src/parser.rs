@@ -1,10 +1,13 @@
 use crate::{
-	Context, FlagValue,
+	Config, Context, Flag, FlagGroup, FlagType, FlagValue, Positional, Vector,
+	flag::{DuplicateKeys, GroupKind},
+	os_args::{NonUtf8Args, Slot},
 	vector::flag::{FlagSearch, LongFound},
 };
-use std::collections::VecDeque;
+use std::{collections::VecDeque, path::PathBuf};
 
 /// Struct of information for parse
+#[derive(Clone, Debug, PartialEq)]
 pub struct Parser {
 	/// `flag_pattern`. Default is '-'.
 	pub flag_pattern: char,
@@ -12,6 +15,12 @@ pub struct Parser {
 	pub long_flag_prefix: String,
 	/// equal symbol. Default is "="
 	pub eq: char,
+	/// Symbols treated as same as eq. Default is empty.
+	pub eq_alias: Vector<char>,
+	/// If this is true, long flag names (and short aliases) are matched ignoring case. Default is false.
+	pub ignore_case: bool,
+	/// If this is true, unambiguous prefix of long flag or sub command (such as `--verb` for `--verbose`) is accepted. Default is false.
+	pub abbreviation: bool,
 }
 
 impl Default for Parser {
@@ -20,6 +29,9 @@ impl Default for Parser {
 			flag_pattern: '-',
 			long_flag_prefix: String::from("--"),
 			eq: '=',
+			eq_alias: Vector::default(),
+			ignore_case: false,
+			abbreviation: false,
 		}
 	}
 }
@@ -30,6 +42,9 @@ impl From<char> for Parser {
 			flag_pattern,
 			long_flag_prefix: flag_pattern.to_string().repeat(2),
 			eq: '=',
+			eq_alias: Vector::default(),
+			ignore_case: false,
+			abbreviation: false,
 		}
 	}
 }
@@ -40,6 +55,9 @@ impl From<(char, char)> for Parser {
 			flag_pattern,
 			long_flag_prefix: flag_pattern.to_string().repeat(2),
 			eq,
+			eq_alias: Vector::default(),
+			ignore_case: false,
+			abbreviation: false,
 		}
 	}
 }
@@ -168,6 +186,9 @@ impl Parser {
 			flag_pattern,
 			long_flag_prefix: String::from(long_flag_prefix),
 			eq: '=',
+			eq_alias: Vector::default(),
+			ignore_case: false,
+			abbreviation: false,
 		}
 	}
 
@@ -183,12 +204,324 @@ impl Parser {
 		str.starts_with(self.flag_pattern)
 	}
 
+	/// Returns true if str is end-of-options marker, which is doubled flag prefix only (default: --).
+	/// オプション終端（フラグのプレフィックス2つのみ）か判定する
+	pub fn is_end_of_options(&self, str: &str) -> bool {
+		let mut chars = str.chars();
+		chars.next() == Some(self.flag_pattern)
+			&& chars.next() == Some(self.flag_pattern)
+			&& chars.next().is_none()
+	}
+
+	/// Moves all rest args of c to `trailing_args` of c. It is used when end-of-options marker appeared.
+	/// オプション終端以降の引数をパースせずに`trailing_args`へ移す
+	pub fn pass_through_rest_args(&self, mut c: Context) -> Context {
+		c.trailing_args
+			.append(Vector::from(core::mem::take(&mut c.args)));
+		c
+	}
+
+	/// Creates a new Parser for DOS(Windows) style flags like `/v`, `/out:file.txt` and `/HELP`.
+	/// A single prefix `/` introduces long names, both `:` and `=` are value separators and flag names are matched ignoring case.
+	/// If a long name is one character and no long flag matches it, it is matched with short aliases.
+	pub fn dos_style() -> Parser {
+		Parser {
+			flag_pattern: '/',
+			long_flag_prefix: String::from("/"),
+			eq: ':',
+			eq_alias: Vector::with_first_elem('='),
+			ignore_case: true,
+			abbreviation: false,
+		}
+	}
+
+	/// Adds a symbol treated as same as eq.
+	pub fn eq_alias(mut self, eq: char) -> Self {
+		self.eq_alias.push(eq);
+		self
+	}
+
+	/// Sets whether flag names are matched ignoring case.
+	pub fn ignore_case(mut self, ignore_case: bool) -> Self {
+		self.ignore_case = ignore_case;
+		self
+	}
+
+	/// Sets whether unambiguous prefix of long flag or sub command is accepted.
+	pub fn abbreviation(mut self, abbreviation: bool) -> Self {
+		self.abbreviation = abbreviation;
+		self
+	}
+
+	/// Returns true if long-flag prefix is same as (single) flag prefix, as DOS style.
+	pub fn is_single_prefix(&self) -> bool {
+		let mut chars = self.long_flag_prefix.chars();
+		chars.next() == Some(self.flag_pattern) && chars.next().is_none()
+	}
+
+	/// Returns true if c is eq or one of `eq_alias`.
+	pub fn is_eq(&self, c: char) -> bool {
+		c == self.eq
+			|| match &self.eq_alias {
+				Vector(Some(eq_alias)) => eq_alias.contains(&c),
+				Vector(None) => false,
+			}
+	}
+
+	/// Returns the byte index of the first eq (or one of `eq_alias`) in str.
+	pub fn find_eq(&self, str: &str) -> Option<usize> {
+		str.find(|c| self.is_eq(c))
+	}
+
+	/// Searches long flag matches `name_or_alias` from flags with this parser's setting.
+	/// If the hit is not exact match of name (ignoring case or one character name matches short alias in single prefix style), this returns `LongFound::Long`.
+	pub fn search_long_flag<'a, T: FlagSearch>(
+		&self,
+		flags: &'a T,
+		name_or_alias: &str,
+	) -> LongFound<&'a Flag> {
+		let found = if self.ignore_case {
+			flags.find_long_flag_ignore_case(name_or_alias)
+		} else {
+			flags.find_long_flag(name_or_alias)
+		};
+		match found {
+			LongFound::None if self.is_single_prefix() => {
+				let mut chars = name_or_alias.chars();
+				match (chars.next(), chars.next()) {
+					(Some(short_alias), None) => match self.search_short_flag(flags, &short_alias) {
+						Some(flag) => LongFound::Long(flag),
+						None => LongFound::None,
+					},
+					_ => LongFound::None,
+				}
+			}
+			found => found,
+		}
+	}
+
+	/// Searches short flag matches `short_alias` from flags with this parser's setting.
+	pub fn search_short_flag<'a, T: FlagSearch>(
+		&self,
+		flags: &'a T,
+		short_alias: &char,
+	) -> Option<&'a Flag> {
+		if self.ignore_case {
+			flags.find_short_flag_ignore_case(short_alias)
+		} else {
+			flags.find_short_flag(short_alias)
+		}
+	}
+
+	/// Searches negatable bool flag negated by `name_or_alias` (for example, "no-color" negates "color") from flags.
+	pub fn search_negated_flag<'a, T: FlagSearch>(
+		&self,
+		flags: &'a T,
+		name_or_alias: &str,
+	) -> Option<&'a Flag> {
+		let rest = match name_or_alias.get(..3) {
+			Some(prefix)
+				if prefix == "no-" || (self.ignore_case && prefix.eq_ignore_ascii_case("no-")) =>
+			{
+				&name_or_alias[3..]
+			}
+			_ => return None,
+		};
+		match self.search_long_flag(flags, rest) {
+			LongFound::Name(flag) | LongFound::Long(flag) if flag.is_negatable() => Some(flag),
+			_ => None,
+		}
+	}
+
+	/// Returns the value if `short_name` (short flag without prefix) is numeric shorthand such as "5" of "-5" and flags have a flag accepts it.
+	pub fn search_numeric_shorthand<'a, T: FlagSearch>(
+		&self,
+		flags: &'a T,
+		short_name: &str,
+	) -> Option<(&'a Flag, FlagValue)> {
+		if short_name.is_empty() || !short_name.chars().all(|c| c.is_ascii_digit()) {
+			return None;
+		}
+		match flags.find_numeric_shorthand() {
+			Some(flag) => match flag.derive_flag_value_from_string(short_name.to_owned()) {
+				FlagValue::Invalid(_) => None,
+				val => Some((flag, val)),
+			},
+			None => None,
+		}
+	}
+
+	/// Splits `short_name` (short flags without prefix, such as "vofile" of "-vofile") into flags and attached value ("vo" and "file"),
+	/// if a short flag takes value and it is followed by chars except eq.
+	/// Flag whose value is optional also takes the rest as its value (such as "never" of "-cnever"), because its value can be given only by attaching.
+	pub fn split_attached_short_value<T: FlagSearch, S: FlagSearch>(
+		&self,
+		short_name: &str,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+	) -> Option<(String, String)> {
+		let mut chars = short_name.char_indices();
+		while let Some((_, s)) = chars.next() {
+			if self.is_eq(s) {
+				return None;
+			}
+			let takes_value = match self.search_short_flag(local_flags, &s) {
+				Some(l_flag) => l_flag.flag_type.takes_value(),
+				None => self
+					.search_short_flag(&(current_common_flags, &c.common_flags), &s)
+					.is_some_and(|c_flag| c_flag.flag_type.takes_value()),
+			};
+			if takes_value {
+				return match chars.next() {
+					Some((j, next)) if !self.is_eq(next) => {
+						Some((short_name[..j].to_owned(), short_name[j..].to_owned()))
+					}
+					_ => None,
+				};
+			}
+		}
+		None
+	}
+
+	/// Returns true if `short_name` (short flags without prefix) has attached value or is numeric shorthand.
+	pub fn has_attached_short_value<T: FlagSearch, S: FlagSearch>(
+		&self,
+		short_name: &str,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+	) -> bool {
+		self
+			.search_numeric_shorthand(local_flags, short_name)
+			.is_some()
+			|| self
+				.search_numeric_shorthand(&(current_common_flags, &c.common_flags), short_name)
+				.is_some()
+			|| self
+				.split_attached_short_value(short_name, local_flags, current_common_flags, c)
+				.is_some()
+	}
+
+	/// Resolves abbreviation of long flag.
+	/// If abbreviation is enabled and `name_or_alias` does not match any flag but is a prefix of only one flag's name or long alias, returns the flag's name.
+	/// If it is a prefix of several flags, returns Err with their names.
+	pub fn resolve_long_flag_abbreviation<T: FlagSearch, S: FlagSearch>(
+		&self,
+		name_or_alias: &str,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+	) -> Result<Option<String>, Vec<String>> {
+		let common_flags = (current_common_flags, &c.common_flags);
+		if !self.abbreviation
+			|| name_or_alias.is_empty()
+			|| !matches!(
+				self.search_long_flag(local_flags, name_or_alias),
+				LongFound::None
+			) || !matches!(
+			self.search_long_flag(&common_flags, name_or_alias),
+			LongFound::None
+		) || self
+			.search_negated_flag(local_flags, name_or_alias)
+			.is_some()
+			|| self
+				.search_negated_flag(&common_flags, name_or_alias)
+				.is_some()
+		{
+			return Ok(None);
+		}
+		let mut candidates = if self.ignore_case {
+			local_flags.find_long_flags_by_prefix_ignore_case(name_or_alias)
+		} else {
+			local_flags.find_long_flags_by_prefix(name_or_alias)
+		};
+		candidates.append(&mut if self.ignore_case {
+			common_flags.find_long_flags_by_prefix_ignore_case(name_or_alias)
+		} else {
+			common_flags.find_long_flags_by_prefix(name_or_alias)
+		});
+		let mut names: Vec<String> = Vec::new();
+		for flag in candidates {
+			if !names.contains(&flag.name) {
+				names.push(flag.get_name_clone());
+			}
+		}
+		match names.len() {
+			0 => Ok(None),
+			1 => Ok(names.pop()),
+			_ => Err(names),
+		}
+	}
+
+	/// Returns names and long aliases of local and common flags similar to `name` (edit distance based, at least 1 edit is allowed even for short name), nearest first.
+	pub fn suggest_long_flags<T: FlagSearch, S: FlagSearch>(
+		&self,
+		name: &str,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+	) -> Vec<String> {
+		let normalize = |s: &str| {
+			if self.ignore_case {
+				s.to_lowercase()
+			} else {
+				s.to_owned()
+			}
+		};
+		let target = normalize(name);
+		let max_distance = ((target.chars().count() + 1) / 3).max(1);
+		let common_flags = (current_common_flags, &c.common_flags);
+		let mut flags = local_flags.all_flags();
+		flags.append(&mut common_flags.all_flags());
+		let mut suggestions: Vec<(usize, &String)> = Vec::new();
+		for flag in flags {
+			let long_alias = match &flag.long_alias {
+				Vector(Some(long_alias)) => long_alias.as_slice(),
+				Vector(None) => &[],
+			};
+			for candidate in std::iter::once(&flag.name).chain(long_alias) {
+				let distance = edit_distance(&target, &normalize(candidate));
+				if distance <= max_distance && !suggestions.iter().any(|(_, s)| *s == candidate) {
+					suggestions.push((distance, candidate));
+				}
+			}
+		}
+		suggestions.sort_by_key(|(distance, _)| *distance);
+		suggestions
+			.into_iter()
+			.map(|(_, candidate)| candidate.to_owned())
+			.collect()
+	}
+
+	/// Generates error info for `flag_arg` that exists neither in local flags nor in common flags.
+	/// If similar flags exist, the last element of error info becomes `ParseError::SimilarExist` with their names.
+	pub fn no_exist_error<T: FlagSearch, S: FlagSearch>(
+		&self,
+		flag_arg: MiddleArg,
+		error: ParseError,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+	) -> ErrorInfo {
+		let suggestions =
+			self.suggest_long_flags(flag_arg.name(), local_flags, current_common_flags, c);
+		if suggestions.is_empty() {
+			(flag_arg, error.clone(), error)
+		} else {
+			(flag_arg, error, ParseError::SimilarExist(suggestions))
+		}
+	}
+
 	/// Builds a new Parser with all options
 	pub fn with_all_field(flag_pattern: char, long_flag_prefix: String, eq: char) -> Parser {
 		Parser {
 			flag_pattern,
 			long_flag_prefix,
 			eq,
+			eq_alias: Vector::default(),
+			ignore_case: false,
+			abbreviation: false,
 		}
 	}
 
@@ -222,6 +555,10 @@ impl Parser {
 				self,
 				args.pop_front(),
 			long_flag=>long_flag{
+				if self.is_end_of_options(&long_flag) {
+					args.push_front(long_flag);
+					break (None, args, inter_mediate_args, last);
+				}
 				inter_mediate_args.push_back(last);
 				last = self.long_middle(long_flag);
 			},
@@ -240,7 +577,7 @@ impl Parser {
 
 	/// Converts `long_flag` to `MiddleArg::LongFlag`.
 	pub fn long_middle(&self, mut long_flag: String) -> MiddleArg {
-		match &long_flag.find(self.eq) {
+		match &self.find_eq(&long_flag) {
 			Some(index) => {
 				let after_eq = long_flag.split_off(index + 1);
 				long_flag.pop();
@@ -255,7 +592,7 @@ impl Parser {
 
 	/// Converts `short_flag` to `MiddleArg::ShortFlag`.
 	pub fn short_middle(&self, mut short_flag: String) -> MiddleArg {
-		match &short_flag.find(self.eq) {
+		match &self.find_eq(&short_flag) {
 			Some(index) => {
 				let after_eq = short_flag.split_off(index + 1);
 				short_flag.pop();
@@ -316,7 +653,24 @@ impl Parser {
 		mut c_flags: VecDeque<(String, FlagValue)>,
 		mut e_list: VecDeque<ErrorInfo>,
 	) -> ParseMiddleFlagResult {
-		match local_flags.find_long_flag(&name_or_alias) {
+		let name_or_alias = match self.resolve_long_flag_abbreviation(
+			&name_or_alias,
+			local_flags,
+			current_common_flags,
+			c,
+		) {
+			Ok(Some(full_name)) => full_name,
+			Ok(None) => name_or_alias,
+			Err(candidates) => {
+				e_list.push_front((
+					MiddleArg::LongFlag(name_or_alias, val),
+					ParseError::AmbiguousLong(candidates.clone()),
+					ParseError::AmbiguousLong(candidates),
+				));
+				return (l_flags, c_flags, e_list);
+			}
+		};
+		match self.search_long_flag(local_flags, &name_or_alias) {
 			LongFound::Name(l_flag) => {
 				match val {
 					FlagValue::String(_) if l_flag.flag_type.is_string() => {
@@ -364,7 +718,7 @@ impl Parser {
 				}
 			},
 			LongFound::None => {
-				match (current_common_flags, &c.common_flags).find_long_flag(&name_or_alias) {
+				match self.search_long_flag(&(current_common_flags, &c.common_flags), &name_or_alias) {
 					LongFound::Name(c_flag) => match val {
 						FlagValue::None => {
 							c_flags.push_front((name_or_alias, FlagValue::None));
@@ -407,13 +761,35 @@ impl Parser {
 							c_flags.push_front((c_flag.get_name_clone(), val));
 						}
 					},
-					LongFound::None => {
-						e_list.push_front((
+					LongFound::None => match val {
+						FlagValue::None => match self.search_negated_flag(local_flags, &name_or_alias) {
+							Some(l_flag) => {
+								l_flags.push_front((l_flag.get_name_clone(), FlagValue::Bool(false)))
+							}
+							None => match self.search_negated_flag(
+								&(current_common_flags, &c.common_flags),
+								&name_or_alias,
+							) {
+								Some(c_flag) => {
+									c_flags.push_front((c_flag.get_name_clone(), FlagValue::Bool(false)))
+								}
+								None => e_list.push_front(self.no_exist_error(
+									MiddleArg::LongFlag(name_or_alias, val),
+									ParseError::NoExistLong,
+									local_flags,
+									current_common_flags,
+									c,
+								)),
+							},
+						},
+						val => e_list.push_front(self.no_exist_error(
 							MiddleArg::LongFlag(name_or_alias, val),
 							ParseError::NoExistLong,
-							ParseError::NoExistLong,
-						));
-					}
+							local_flags,
+							current_common_flags,
+							c,
+						)),
+					},
 				}
 			}
 		}
@@ -432,8 +808,50 @@ impl Parser {
 		mut c_flags: VecDeque<(String, FlagValue)>,
 		mut e_list: VecDeque<ErrorInfo>,
 	) -> ParseMiddleFlagResult {
+		let flag_val = match flag_val {
+			FlagValue::None => {
+				if let Some((l_flag, val)) = self.search_numeric_shorthand(local_flags, &short_alias) {
+					l_flags.push_front((l_flag.get_name_clone(), val));
+					return (l_flags, c_flags, e_list);
+				}
+				if let Some((c_flag, val)) =
+					self.search_numeric_shorthand(&(current_common_flags, &c.common_flags), &short_alias)
+				{
+					c_flags.push_front((c_flag.get_name_clone(), val));
+					return (l_flags, c_flags, e_list);
+				}
+				match self.split_attached_short_value(
+					&short_alias,
+					local_flags,
+					current_common_flags,
+					c,
+				) {
+					Some((flags, value)) => {
+						short_alias = flags;
+						FlagValue::String(value)
+					}
+					None => FlagValue::None,
+				}
+			}
+			FlagValue::String(after_eq) => {
+				match self.split_attached_short_value(
+					&short_alias,
+					local_flags,
+					current_common_flags,
+					c,
+				) {
+					// eqが付随する値の中にあった場合
+					Some((flags, value)) => {
+						short_alias = flags;
+						FlagValue::String(format!("{}{}{}", value, self.eq, after_eq))
+					}
+					None => FlagValue::String(after_eq),
+				}
+			}
+			val => val,
+		};
 		match short_alias.pop() {
-			Some(last) => match local_flags.find_short_flag(&last) {
+			Some(last) => match self.search_short_flag(local_flags, &last) {
 				Some(l_flag) => match flag_val {
 					FlagValue::String(_) if l_flag.flag_type.is_string() => {
 						l_flags.push_front((l_flag.get_name_clone(), flag_val));
@@ -465,7 +883,7 @@ impl Parser {
 						l_flags.push_front((l_flag.get_name_clone(), val));
 					}
 				},
-				None => match (current_common_flags, &c.common_flags).find_short_flag(&last) {
+				None => match self.search_short_flag(&(current_common_flags, &c.common_flags), &last) {
 					Some(c_flag) => match flag_val {
 						FlagValue::String(_) if c_flag.flag_type.is_string() => {
 							c_flags.push_front((c_flag.get_name_clone(), flag_val));
@@ -497,10 +915,12 @@ impl Parser {
 							c_flags.push_front((c_flag.get_name_clone(), val));
 						}
 					},
-					None => e_list.push_front((
+					None => e_list.push_front(self.no_exist_error(
 						MiddleArg::ShortFlag(str_char!(short_alias, last), flag_val),
 						ParseError::NoExistShort(short_alias.len()),
-						ParseError::NoExistShort(short_alias.len()),
+						local_flags,
+						current_common_flags,
+						c,
 					)),
 				},
 			},
@@ -509,6 +929,50 @@ impl Parser {
 			}
 		}
 
+		self.parse_middle_short_flag_rest(
+			short_alias,
+			local_flags,
+			current_common_flags,
+			c,
+			l_flags,
+			c_flags,
+			e_list,
+		)
+	}
+
+	/// Parses clustered short flags except the last one (for example, "ab" of "-abc"), which have no value.
+	pub fn parse_middle_short_flag_rest<T: FlagSearch, S: FlagSearch>(
+		&self,
+		short_alias: String,
+		local_flags: &T,
+		current_common_flags: &S,
+		c: &Context,
+		mut l_flags: VecDeque<(String, FlagValue)>,
+		mut c_flags: VecDeque<(String, FlagValue)>,
+		mut e_list: VecDeque<ErrorInfo>,
+	) -> ParseMiddleFlagResult {
+		for (i, s) in short_alias
+			.chars()
+			.enumerate()
+			.collect::<Vec<_>>()
+			.into_iter()
+			.rev()
+		{
+			match self.search_short_flag(local_flags, &s) {
+				Some(l_flag) => l_flags.push_front((l_flag.get_name_clone(), FlagValue::None)),
+				None => match self.search_short_flag(&(current_common_flags, &c.common_flags), &s) {
+					Some(c_flag) => c_flags.push_front((c_flag.get_name_clone(), FlagValue::None)),
+					None => e_list.push_front(self.no_exist_error(
+						MiddleArg::ShortFlag(short_alias.clone(), FlagValue::None),
+						ParseError::NoExistShort(i),
+						local_flags,
+						current_common_flags,
+						c,
+					)),
+				},
+			}
+		}
+
 		(l_flags, c_flags, e_list)
 	}
 
@@ -529,82 +993,96 @@ impl Parser {
 	) -> ParseMiddleArgResult {
 		match inter_mediate_args.pop_back() {
 			//ロングフラグが前にあり、その値である可能性があるとき
-			Some(MiddleArg::LongFlag(long_flag_name, FlagValue::None)) => {
-				match local_flags.find_long_flag(&long_flag_name) {
-					LongFound::Name(l_flag) => match l_flag.derive_flag_value_from_string(normal_arg) {
-						FlagValue::Invalid(normal_arg) => {
-							if flag_only {
-								c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
-							} else {
-								non_flag_args.push_front(normal_arg);
+			Some(MiddleArg::LongFlag(mut long_flag_name, FlagValue::None)) => {
+				if let Ok(Some(full_name)) = self.resolve_long_flag_abbreviation(
+					&long_flag_name,
+					local_flags,
+					current_common_flags,
+					&c,
+				) {
+					long_flag_name = full_name;
+				}
+				match self.search_long_flag(local_flags, &long_flag_name) {
+					LongFound::Name(l_flag) => {
+						match l_flag.derive_flag_value_from_next_arg(normal_arg) {
+							FlagValue::Invalid(normal_arg) => {
+								if flag_only {
+									c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
+								} else {
+									non_flag_args.push_front(normal_arg);
+								}
+								l_flags.push_front((long_flag_name, FlagValue::None));
+								self.parse_next_if_middle_arg(
+									inter_mediate_args,
+									non_flag_args,
+									local_flags,
+									current_common_flags,
+									c,
+									l_flags,
+									c_flags,
+									e_list,
+									flag_only,
+								)
 							}
-							l_flags.push_front((long_flag_name, FlagValue::None));
-							self.parse_next_if_middle_arg(
-								inter_mediate_args,
-								non_flag_args,
-								local_flags,
-								current_common_flags,
-								c,
-								l_flags,
-								c_flags,
-								e_list,
-								flag_only,
-							)
-						}
-						val => {
-							l_flags.push_front((long_flag_name, val));
-							self.parse_next_if_middle_arg(
-								inter_mediate_args,
-								non_flag_args,
-								local_flags,
-								current_common_flags,
-								c,
-								l_flags,
-								c_flags,
-								e_list,
-								flag_only,
-							)
-						}
-					},
-					LongFound::Long(l_flag) => match l_flag.derive_flag_value_from_string(normal_arg) {
-						FlagValue::Invalid(normal_arg) => {
-							l_flags.push_front((l_flag.get_name_clone(), FlagValue::None));
-							if flag_only {
-								c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
-							} else {
-								non_flag_args.push_front(normal_arg);
+							val => {
+								l_flags.push_front((long_flag_name, val));
+								self.parse_next_if_middle_arg(
+									inter_mediate_args,
+									non_flag_args,
+									local_flags,
+									current_common_flags,
+									c,
+									l_flags,
+									c_flags,
+									e_list,
+									flag_only,
+								)
 							}
-							self.parse_next_if_middle_arg(
-								inter_mediate_args,
-								non_flag_args,
-								local_flags,
-								current_common_flags,
-								c,
-								l_flags,
-								c_flags,
-								e_list,
-								flag_only,
-							)
 						}
-						val => {
-							l_flags.push_front((l_flag.get_name_clone(), val));
-							self.parse_next_if_middle_arg(
-								inter_mediate_args,
-								non_flag_args,
-								local_flags,
-								current_common_flags,
-								c,
-								l_flags,
-								c_flags,
-								e_list,
-								flag_only,
-							)
+					}
+					LongFound::Long(l_flag) => {
+						match l_flag.derive_flag_value_from_next_arg(normal_arg) {
+							FlagValue::Invalid(normal_arg) => {
+								l_flags.push_front((l_flag.get_name_clone(), FlagValue::None));
+								if flag_only {
+									c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
+								} else {
+									non_flag_args.push_front(normal_arg);
+								}
+								self.parse_next_if_middle_arg(
+									inter_mediate_args,
+									non_flag_args,
+									local_flags,
+									current_common_flags,
+									c,
+									l_flags,
+									c_flags,
+									e_list,
+									flag_only,
+								)
+							}
+							val => {
+								l_flags.push_front((l_flag.get_name_clone(), val));
+								self.parse_next_if_middle_arg(
+									inter_mediate_args,
+									non_flag_args,
+									local_flags,
+									current_common_flags,
+									c,
+									l_flags,
+									c_flags,
+									e_list,
+									flag_only,
+								)
+							}
 						}
-					},
+					}
 					LongFound::None => {
-						match (current_common_flags, &c.common_flags).find_long_flag(&long_flag_name) {
+						match self
+							.search_long_flag(&(current_common_flags, &c.common_flags), &long_flag_name)
+						{
 							LongFound::Name(c_flag) => {
-								match c_flag.derive_flag_value_from_string(normal_arg) {
+								match c_flag.derive_flag_value_from_next_arg(normal_arg) {
 									FlagValue::Invalid(normal_arg) => {
 										if flag_only {
 											c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
@@ -641,7 +1119,7 @@ impl Parser {
 								}
 							}
 							LongFound::Long(c_flag) => {
-								match c_flag.derive_flag_value_from_string(normal_arg) {
+								match c_flag.derive_flag_value_from_next_arg(normal_arg) {
 									FlagValue::Invalid(normal_arg) => {
 										if flag_only {
 											non_flag_args.push_front(normal_arg);
@@ -678,11 +1156,16 @@ impl Parser {
 							}
 							LongFound::None => {
 								non_flag_args.push_front(normal_arg);
-								e_list.push_front((
-									MiddleArg::LongFlag(long_flag_name, FlagValue::None),
-									ParseError::NoExistLong,
-									ParseError::NoExistLong,
-								));
+								let (l_flags, c_flags, e_list) = self.parse_middle_long_flag(
+									long_flag_name,
+									FlagValue::None,
+									local_flags,
+									current_common_flags,
+									&c,
+									l_flags,
+									c_flags,
+									e_list,
+								);
 								self.parse_next_if_middle_arg(
 									inter_mediate_args,
 									non_flag_args,
@@ -729,11 +1212,18 @@ impl Parser {
 				)
 			}
 			//ショートフラグの引数である可能性があるとき
-			Some(MiddleArg::ShortFlag(mut short_str, FlagValue::None)) => {
+			Some(MiddleArg::ShortFlag(mut short_str, FlagValue::None))
+				if !self.has_attached_short_value(
+					&short_str,
+					local_flags,
+					current_common_flags,
+					&c,
+				) =>
+			{
 				let short_alias = short_str.pop();
 				if let Some(short_alias) = short_alias {
-					match local_flags.find_short_flag(&short_alias) {
-						Some(l_flag) => match l_flag.derive_flag_value_from_string(normal_arg) {
+					match self.search_short_flag(local_flags, &short_alias) {
+						Some(l_flag) => match l_flag.derive_flag_value_from_next_arg(normal_arg) {
 							FlagValue::Invalid(normal_arg) => {
 								non_flag_args.push_front(normal_arg);
 								l_flags.push_front((l_flag.get_name_clone(), FlagValue::None));
@@ -743,8 +1233,10 @@ impl Parser {
 							}
 						},
 						None => {
-							match (current_common_flags, &c.common_flags).find_short_flag(&short_alias) {
-								Some(c_flag) => match c_flag.derive_flag_value_from_string(normal_arg) {
+							match self
+								.search_short_flag(&(current_common_flags, &c.common_flags), &short_alias)
+							{
+								Some(c_flag) => match c_flag.derive_flag_value_from_next_arg(normal_arg) {
 									FlagValue::Invalid(normal_arg) => {
 										non_flag_args.push_front(normal_arg);
 										c_flags.push_front((c_flag.get_name_clone(), FlagValue::None));
@@ -756,15 +1248,26 @@ impl Parser {
 								None => {
 									non_flag_args.push_front(normal_arg);
 									let i = short_str.len() - 1;
-									e_list.push_back((
-										MiddleArg::ShortFlag(short_str, FlagValue::None),
-										ParseError::NoExistShort(i),
+									e_list.push_back(self.no_exist_error(
+										MiddleArg::ShortFlag(short_str.clone(), FlagValue::None),
 										ParseError::NoExistShort(i),
+										local_flags,
+										current_common_flags,
+										&c,
 									));
 								}
 							}
 						}
 					};
+					let (l_flags, c_flags, e_list) = self.parse_middle_short_flag_rest(
+						short_str,
+						local_flags,
+						current_common_flags,
+						&c,
+						l_flags,
+						c_flags,
+						e_list,
+					);
 
 					self.parse_next_if_middle_arg(
 						inter_mediate_args,
@@ -781,7 +1284,13 @@ impl Parser {
 					panic!("short alias is not existed")
 				}
 			}
+			//ショートフラグに値が指定もしくは付随していて、引数である可能性がないとき
 			Some(MiddleArg::ShortFlag(short_str, val)) => {
+				if flag_only {
+					c = self.push_normal_arg_in_flag_only_error(c, normal_arg);
+				} else {
+					non_flag_args.push_front(normal_arg);
+				}
 				let (l_flags, c_flags, e_list) = self.parse_middle_short_flag(
 					short_str,
 					val,
@@ -930,6 +1439,597 @@ impl Parser {
 		}
 	}
 
+	/// Fills values of flags which are not inputted but have environment variable name (`Flag::env`) from `lookup`.
+	/// Value of environment variable is parsed as the flag's type, and if it is invalid, `ParseError::InvalidEnv` is pushed to context's `error_info_list`.
+	pub fn parse_env_values<T: FlagSearch, S: FlagSearch, F: Fn(&str) -> Option<String>>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		lookup: F,
+		mut c: Context,
+	) -> Context {
+		let env_value = |flag: &Flag, errors: &mut Vec<ErrorInfo>| {
+			let env = flag.env.as_ref()?;
+			match flag.derive_flag_value_from_setting(lookup(env)?) {
+				FlagValue::Invalid(val) => {
+					errors.push((
+						MiddleArg::LongFlag(flag.get_name_clone(), FlagValue::String(val)),
+						ParseError::InvalidEnv(env.clone()),
+						ParseError::None,
+					));
+					None
+				}
+				val => Some((flag.get_name_clone(), val)),
+			}
+		};
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		let mut local_values: Vec<(String, FlagValue)> = Vec::new();
+		for flag in local_flags.all_flags() {
+			if c.get_inputted_local_flag_value_of(&flag.name).is_none() {
+				local_values.extend(env_value(flag, &mut errors));
+			}
+		}
+		let mut common_values: Vec<(String, FlagValue)> = Vec::new();
+		let mut checked: Vec<&String> = Vec::new();
+		let common_flags = (current_common_flags, &c.common_flags);
+		for flag in common_flags.all_flags() {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			if c.get_inputted_common_flag_value_of(&flag.name).is_none() {
+				common_values.extend(env_value(flag, &mut errors));
+			}
+		}
+		for val in local_values {
+			c.filled_flags.push(val.0.clone());
+			c.local_flags_values.push(val);
+		}
+		for val in common_values {
+			c.filled_flags.push(val.0.clone());
+			c.common_flags_values.push(val);
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
+	/// Fills values of flags which are not inputted (and not set by environment variables) from `config`.
+	/// Values are searched from `section` and its parent sections in order. If a value is invalid, `ParseError::InvalidConfig` is pushed to context's `error_info_list`.
+	pub fn parse_config_values<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		config: &Config,
+		section: &str,
+		mut c: Context,
+	) -> Context {
+		let config_value = |flag: &Flag, errors: &mut Vec<ErrorInfo>| {
+			let values = config.get(section, &flag.name)?;
+			match Config::derive_flag_value(flag, values) {
+				FlagValue::Invalid(val) => {
+					errors.push((
+						MiddleArg::LongFlag(flag.get_name_clone(), FlagValue::String(val)),
+						ParseError::InvalidConfig(section.to_owned()),
+						ParseError::None,
+					));
+					None
+				}
+				val => Some((flag.get_name_clone(), val)),
+			}
+		};
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		let mut local_values: Vec<(String, FlagValue)> = Vec::new();
+		for flag in local_flags.all_flags() {
+			if c.get_inputted_local_flag_value_of(&flag.name).is_none() {
+				local_values.extend(config_value(flag, &mut errors));
+			}
+		}
+		let mut common_values: Vec<(String, FlagValue)> = Vec::new();
+		let mut checked: Vec<&String> = Vec::new();
+		let common_flags = (current_common_flags, &c.common_flags);
+		for flag in common_flags.all_flags() {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			if c.get_inputted_common_flag_value_of(&flag.name).is_none() {
+				common_values.extend(config_value(flag, &mut errors));
+			}
+		}
+		for val in local_values {
+			c.filled_flags.push(val.0.clone());
+			c.local_flags_values.push(val);
+		}
+		for val in common_values {
+			c.filled_flags.push(val.0.clone());
+			c.common_flags_values.push(val);
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
+	/// Merges values of each map flag stored in context into one map, resolving duplicate keys by the flag's `DuplicateKeys` policy.
+	/// If policy is `DuplicateKeys::Error`, `ParseError::DuplicateKey` is pushed to context's `error_info_list` for each duplicate key (and the first value is kept).
+	pub fn resolve_map_values<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		mut c: Context,
+	) -> Context {
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		let resolve =
+			|flag: &Flag, values: &mut Vector<(String, FlagValue)>, errors: &mut Vec<ErrorInfo>| {
+				let FlagType::Map {
+					separator,
+					duplicates,
+				} = flag.flag_type
+				else {
+					return;
+				};
+				let Vector(Some(values)) = values else {
+					return;
+				};
+				if !values.iter().any(|(name, _)| name == &flag.name) {
+					return;
+				}
+				let mut resolved: Vec<(String, String)> = Vec::new();
+				values.retain(|entry| match entry {
+					(name, FlagValue::Map(entries)) if name == &flag.name => {
+						resolved.extend(entries.iter().cloned());
+						false
+					}
+					_ => true,
+				});
+				let mut map: Vec<(String, String)> = Vec::with_capacity(resolved.len());
+				for (key, value) in resolved {
+					match map.iter_mut().find(|(k, _)| k == &key) {
+						None => map.push((key, value)),
+						Some(entry) => match duplicates {
+							DuplicateKeys::LastWins => entry.1 = value,
+							DuplicateKeys::FirstWins => {}
+							DuplicateKeys::Error => errors.push((
+								MiddleArg::LongFlag(
+									flag.get_name_clone(),
+									FlagValue::String(format!("{key}{separator}{value}")),
+								),
+								ParseError::DuplicateKey(key),
+								ParseError::None,
+							)),
+						},
+					}
+				}
+				values.push((flag.get_name_clone(), FlagValue::Map(map)));
+			};
+		for flag in local_flags.all_flags() {
+			resolve(flag, &mut c.local_flags_values, &mut errors);
+		}
+		let mut checked: Vec<&String> = Vec::new();
+		let common_flags = (current_common_flags, &c.common_flags);
+		for flag in common_flags.all_flags() {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			resolve(flag, &mut c.common_flags_values, &mut errors);
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
+	/// Adds position of the invalid element to errors of list flags with value delimiter (such as `x` in `--nums=1,x,3`).
+	/// Such error info becomes `(arg, ParseError::InvalidElement(index, element), invalid)`, where `invalid` is the original `ParseError::InvalidLong` or `ParseError::InvalidShort`.
+	/// Invalid values taken from next arg (such as `--nums 1,x,3`, stored as `FlagValue::List(vec![FlagValue::Invalid(arg)])`) are also removed from flag values and reported in the same way.
+	pub fn locate_invalid_elements<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		mut c: Context,
+	) -> Context {
+		let common_flags = (current_common_flags, &c.common_flags);
+		let find = |name: &str| match local_flags.find_long_flag(name) {
+			LongFound::Name(flag) | LongFound::Long(flag) => Some(flag),
+			LongFound::None => match common_flags.find_long_flag(name) {
+				LongFound::Name(flag) | LongFound::Long(flag) => Some(flag),
+				LongFound::None => None,
+			},
+		};
+		let mut located: Vec<(usize, ErrorInfo)> = Vec::new();
+		for (i, (arg, first, second)) in c.error_info_list.inner().iter().flatten().enumerate() {
+			let invalid = match (first, second) {
+				(ParseError::InvalidLong(_) | ParseError::InvalidShort(..), _) => first,
+				(
+					ParseError::NoExistLong | ParseError::NoExistShort(_),
+					ParseError::InvalidLong(_) | ParseError::InvalidShort(..),
+				) => second,
+				_ => continue,
+			};
+			let (ParseError::InvalidLong(name) | ParseError::InvalidShort(_, name)) = invalid else {
+				continue;
+			};
+			let Some((_, val)) = arg.inner_if_string_val() else {
+				continue;
+			};
+			if let Some((index, elem)) = find(name).and_then(|flag| flag.find_invalid_element(val)) {
+				located.push((
+					i,
+					(
+						arg.clone(),
+						ParseError::InvalidElement(index, elem),
+						invalid.clone(),
+					),
+				));
+			}
+		}
+		if let Vector(Some(error_info_list)) = &mut c.error_info_list {
+			for (i, error_info) in located {
+				error_info_list[i] = error_info;
+			}
+		}
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		let mut take_invalid = |(name, val): &(String, FlagValue)| {
+			let FlagValue::List(vals) = val else {
+				return true;
+			};
+			let [FlagValue::Invalid(arg)] = vals.as_slice() else {
+				return true;
+			};
+			match find(name).and_then(|flag| flag.find_invalid_element(arg)) {
+				Some((index, elem)) => {
+					errors.push((
+						MiddleArg::LongFlag(name.clone(), FlagValue::Invalid(arg.clone())),
+						ParseError::InvalidElement(index, elem),
+						ParseError::InvalidLong(name.clone()),
+					));
+					false
+				}
+				None => true,
+			}
+		};
+		if let Some(vals) = c.local_flags_values.inner_mut() {
+			vals.retain(&mut take_invalid);
+		}
+		if let Some(vals) = c.common_flags_values.inner_mut() {
+			vals.retain(&mut take_invalid);
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
+	/// Validates flag values stored in context (inputted or filled by environment variables or config) by range, length limit and custom validator of each flag.
+	/// If a value is invalid, `ParseError::ValidationFailed` with validator's message is pushed to context's `error_info_list`.
+	pub fn validate_flag_values<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		mut c: Context,
+	) -> Context {
+		let validate = |flag: Option<&Flag>, name: &String, val: &FlagValue| -> Option<ErrorInfo> {
+			if matches!(val, FlagValue::None | FlagValue::Invalid(_)) {
+				return None;
+			}
+			let message = flag?.validate(val).err()?;
+			Some((
+				MiddleArg::LongFlag(
+					name.clone(),
+					FlagValue::String(val.clone().get_string_value()),
+				),
+				ParseError::ValidationFailed(message),
+				ParseError::None,
+			))
+		};
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		for (name, val) in c.local_flags_values.inner().iter().flatten() {
+			errors.extend(validate(local_flags.find(name), name, val));
+		}
+		let common_flags = (current_common_flags, &c.common_flags);
+		for (name, val) in c.common_flags_values.inner().iter().flatten() {
+			errors.extend(validate(common_flags.find(name), name, val));
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
+	/// Validates args (and trailing args) left in context by declared positional args, and stores converted values to context's `positional_values`.
+	/// Variadic positional arg takes all rest args except args for required positional args declared after it.
+	pub fn parse_positionals(&self, positionals: &Vector<Positional>, mut c: Context) -> Context {
+		let positionals = match positionals {
+			Vector(Some(positionals)) => positionals,
+			Vector(None) => return c,
+		};
+		let mut values: VecDeque<String> = c.args.clone();
+		if let Vector(Some(trailing_args)) = &c.trailing_args {
+			values.extend(trailing_args.iter().cloned());
+		}
+		for (index, positional) in positionals.iter().enumerate() {
+			let inputted: Vec<String> = if positional.variadic {
+				let reserved = positionals[index + 1..]
+					.iter()
+					.filter(|p| p.required)
+					.count();
+				let len = values.len().saturating_sub(reserved);
+				values.drain(..len).collect()
+			} else {
+				values.pop_front().into_iter().collect()
+			};
+			if inputted.is_empty() && (positional.required || !positional.variadic) {
+				if positional.required {
+					c.error_info_list.push((
+						MiddleArg::Normal(positional.name.clone()),
+						ParseError::MissingPositional(positional.name.clone()),
+						ParseError::None,
+					));
+				}
+				continue;
+			}
+			let mut converted = Vec::with_capacity(inputted.len());
+			for val in inputted {
+				match positional.derive_value_from_string(val) {
+					FlagValue::Invalid(val) => c.error_info_list.push((
+						MiddleArg::Normal(val),
+						ParseError::InvalidPositional(positional.name.clone()),
+						ParseError::None,
+					)),
+					val => converted.push(val),
+				}
+			}
+			let value = if positional.variadic {
+				FlagValue::List(converted)
+			} else if let Some(val) = converted.pop() {
+				val
+			} else {
+				continue;
+			};
+			c.positional_values.push((positional.name.clone(), value));
+		}
+		for val in values {
+			c.error_info_list.push((
+				MiddleArg::Normal(val),
+				ParseError::UnexpectedPositional,
+				ParseError::None,
+			));
+		}
+		c
+	}
+
+	/// Checks values converted from non-UTF-8 args (inputted by `Command::run_from_os_args`).
+	/// Originals of string values and args are kept by their slot, path values are restored to original, and `ParseError::NonUtf8Value` is reported for values whose type requires UTF-8 (or which cannot be restored).
+	/// Invalid value errors caused by non-UTF-8 args are also replaced by `ParseError::NonUtf8Value`. After this, invalid sequences in context are shown as `U+FFFD`.
+	pub fn check_non_utf8_values(&self, mut c: Context) -> Context {
+		if c.non_utf8_args.is_empty() {
+			return c;
+		}
+		let mut non_utf8_args = std::mem::take(&mut c.non_utf8_args);
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		if let Vector(Some(error_info_list)) = &mut c.error_info_list {
+			for (arg, first, second) in error_info_list.iter_mut() {
+				let val = match arg {
+					MiddleArg::Normal(val) => Some(val.as_str()),
+					arg => arg.inner_if_string_val().map(|(_, val)| val),
+				};
+				if !val.is_some_and(|val| non_utf8_args.is_marked(val)) {
+					continue;
+				}
+				let invalid = match (&first, &second) {
+					(
+						ParseError::InvalidLong(_)
+						| ParseError::InvalidShort(..)
+						| ParseError::InvalidPositional(_),
+						_,
+					) => first.clone(),
+					(_, ParseError::InvalidLong(_) | ParseError::InvalidShort(..)) => second.clone(),
+					_ => continue,
+				};
+				*first = ParseError::NonUtf8Value;
+				*second = invalid;
+			}
+		}
+		for (values, slot, is_positional) in [
+			(
+				&mut c.local_flags_values,
+				Slot::LocalFlag as fn(usize, usize) -> Slot,
+				false,
+			),
+			(&mut c.common_flags_values, Slot::CommonFlag, false),
+			(&mut c.positional_values, Slot::Positional, true),
+		] {
+			let Vector(Some(values)) = values else {
+				continue;
+			};
+			for (i, (name, val)) in values.iter_mut().enumerate() {
+				let Some(mut lossy) = restore_non_utf8_value(val, &mut non_utf8_args, &|j| slot(i, j))
+				else {
+					continue;
+				};
+				non_utf8_args.unmark(&mut lossy);
+				errors.push(if is_positional {
+					(
+						MiddleArg::Normal(lossy),
+						ParseError::NonUtf8Value,
+						ParseError::InvalidPositional(name.clone()),
+					)
+				} else {
+					(
+						MiddleArg::LongFlag(name.clone(), FlagValue::String(lossy)),
+						ParseError::NonUtf8Value,
+						ParseError::InvalidLong(name.clone()),
+					)
+				});
+			}
+		}
+		for (args, slot) in [
+			(
+				c.raw_args.iter_mut().collect::<Vec<_>>(),
+				Slot::RawArg as fn(usize) -> Slot,
+			),
+			(c.args.iter_mut().collect(), Slot::Arg),
+			(
+				c.trailing_args.inner_mut().iter_mut().flatten().collect(),
+				Slot::TrailingArg,
+			),
+		] {
+			for (i, arg) in args.into_iter().enumerate() {
+				non_utf8_args.keep(slot(i), arg);
+				non_utf8_args.unmark(arg);
+			}
+		}
+		non_utf8_args.unmark(&mut c.exe_path);
+		for values in [
+			&mut c.local_flags_values,
+			&mut c.common_flags_values,
+			&mut c.positional_values,
+		] {
+			for (_, val) in values.inner_mut().iter_mut().flatten() {
+				unmark_value(val, &non_utf8_args);
+			}
+		}
+		for arg in c.parsing_args.iter_mut().flatten() {
+			unmark_middle_arg(arg, &non_utf8_args);
+		}
+		for (arg, first, second) in c.error_info_list.inner_mut().iter_mut().flatten() {
+			unmark_middle_arg(arg, &non_utf8_args);
+			unmark_error(first, &non_utf8_args);
+			unmark_error(second, &non_utf8_args);
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c.non_utf8_args = non_utf8_args;
+		c
+	}
+
+	/// Checks that all required local and common flags are inputted. For each missing flag, `ParseError::MissingRequiredFlag` is pushed to context's `error_info_list`.
+	pub fn check_required_flags<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		mut c: Context,
+	) -> Context {
+		let mut missing: Vec<String> = local_flags
+			.all_flags()
+			.into_iter()
+			.filter(|flag| flag.required && c.get_inputted_local_flag_value_of(&flag.name).is_none())
+			.map(Flag::get_name_clone)
+			.collect();
+		let mut checked: Vec<&String> = Vec::new();
+		let common_flags = (current_common_flags, &c.common_flags);
+		for flag in common_flags.all_flags() {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			if flag.required && c.get_inputted_common_flag_value_of(&flag.name).is_none() {
+				missing.push(flag.get_name_clone());
+			}
+		}
+		for name in missing {
+			c.error_info_list.push((
+				MiddleArg::LongFlag(name, FlagValue::None),
+				ParseError::MissingRequiredFlag,
+				ParseError::None,
+			));
+		}
+		c
+	}
+
+	/// Checks conflicts and requirements declared by inputted local and common flags, and constraints of `groups`.
+	/// Values filled from environment variables or config have lower precedence than args, so they do not conflict with flags inputted by args,
+	/// and they are not counted in a group if a flag of the group is inputted by args.
+	/// For each violation, error info is pushed to context's `error_info_list`.
+	pub fn check_flag_relations<T: FlagSearch, S: FlagSearch>(
+		&self,
+		local_flags: &T,
+		current_common_flags: &S,
+		groups: &Vector<FlagGroup>,
+		mut c: Context,
+	) -> Context {
+		let is_inputted = |name: &str| {
+			c.get_inputted_local_flag_value_of(name).is_some()
+				|| c.get_inputted_common_flag_value_of(name).is_some()
+		};
+		// 環境変数や設定ファイルの値は引数で指定された値より優先度が低いので、引数で指定されたフラグとは衝突させない
+		let is_filled = |name: &str| c.filled_flags.iter().any(|filled| filled == name);
+		let mut errors: Vec<ErrorInfo> = Vec::new();
+		let mut checked: Vec<&String> = Vec::new();
+		let mut conflicts: Vec<(&String, &String)> = Vec::new();
+		let common_flags = (current_common_flags, &c.common_flags);
+		let mut flags = local_flags.all_flags();
+		flags.append(&mut common_flags.all_flags());
+		for flag in flags {
+			if checked.contains(&&flag.name) {
+				continue;
+			}
+			checked.push(&flag.name);
+			if !is_inputted(&flag.name) {
+				continue;
+			}
+			if let Vector(Some(conflicts_with)) = &flag.conflicts_with {
+				for other in conflicts_with {
+					if is_inputted(other)
+						&& is_filled(other) == is_filled(&flag.name)
+						&& !conflicts.contains(&(other, &flag.name))
+					{
+						conflicts.push((&flag.name, other));
+						errors.push((
+							MiddleArg::LongFlag(flag.get_name_clone(), FlagValue::None),
+							ParseError::ConflictsWith(other.clone()),
+							ParseError::None,
+						));
+					}
+				}
+			}
+			if let Vector(Some(requires)) = &flag.requires {
+				for required in requires {
+					if !is_inputted(required) {
+						errors.push((
+							MiddleArg::LongFlag(flag.get_name_clone(), FlagValue::None),
+							ParseError::RequiresFlag(required.clone()),
+							ParseError::None,
+						));
+					}
+				}
+			}
+		}
+		if let Vector(Some(groups)) = groups {
+			for group in groups {
+				let mut inputted: Vec<&String> =
+					group.flags.iter().filter(|f| is_inputted(f)).collect();
+				if inputted.iter().any(|f| !is_filled(f)) {
+					inputted.retain(|f| !is_filled(f));
+				}
+				let violated = match group.kind {
+					GroupKind::AtLeastOne => inputted.is_empty().then(|| group.flags.first()),
+					GroupKind::ExactlyOne => match inputted.len() {
+						0 => Some(group.flags.first()),
+						1 => None,
+						_ => Some(inputted.get(1).copied()),
+					},
+					GroupKind::AtMostOne => (inputted.len() > 1).then(|| inputted.get(1).copied()),
+				};
+				if let Some(name) = violated {
+					errors.push((
+						MiddleArg::LongFlag(name.cloned().unwrap_or_default(), FlagValue::None),
+						ParseError::GroupViolation(group.kind, group.flags.clone()),
+						ParseError::None,
+					));
+				}
+			}
+		}
+		for error in errors {
+			c.error_info_list.push(error);
+		}
+		c
+	}
+
 	/// Parse args until args' end.
 	pub fn parse_args_until_end<T: FlagSearch, S: FlagSearch>(
 		self,
@@ -980,6 +2080,48 @@ impl Parser {
 		c
 	}
 
+	/// Parse args until first non-flag arg appears. The first non-flag arg and args after it are stored in `c.args` without parsing.
+	/// 最初の非フラグ引数が現れるまでパースし、それ以降の引数はパースせずに`c.args`に残す
+	pub fn parse_args_until_first_positional<T: FlagSearch, S: FlagSearch>(
+		self,
+		local_flags: &T,
+		current_common_flags: &S,
+		mut c: Context,
+	) -> Context {
+		let next = arg_match!(self, c.args.pop_front(),
+			long_flag=>long_flag{
+				let (next, _c) = self.parse_flags_start_with_long_flag(
+					long_flag,
+					local_flags,
+					current_common_flags,
+					c,
+				);
+				c = _c;
+				next
+			},
+			short_flag=>short_flag{
+				let (next, _c) = self.parse_flags_start_with_short_flag(
+					short_flag,
+					local_flags,
+					current_common_flags,
+					c,
+				);
+				c = _c;
+				next
+			},
+			normal_arg=>arg{
+				Some(arg)
+			},
+			none=>{
+				None
+			}
+		);
+		if let Some(arg) = next {
+			c.args.push_front(arg);
+		}
+		c
+	}
+
 	/// Parses flags start with long flag until non-flag arg appeared.
 	pub fn parse_flags_start_with_long_flag<T: FlagSearch, S: FlagSearch>(
 		&self,
@@ -988,12 +2130,39 @@ impl Parser {
 		current_common_flags: &S,
 		mut c: Context,
 	) -> (Option<String>, Context) {
+		if self.is_end_of_options(&long_flag) {
+			return (None, self.pass_through_rest_args(c));
+		}
 		long_flag = self.remove_long_flag_prefix(long_flag);
-		match long_flag.find(self.eq) {
+		let (name, after_name) = match self.find_eq(&long_flag) {
+			Some(index) => long_flag.split_at(index),
+			None => (long_flag.as_str(), ""),
+		};
+		match self.resolve_long_flag_abbreviation(name, local_flags, current_common_flags, &c) {
+			Ok(Some(full_name)) => long_flag = full_name + after_name,
+			Ok(None) => {}
+			Err(candidates) => {
+				let flag_arg = match after_name.chars().next() {
+					Some(eq) => MiddleArg::LongFlag(
+						name.to_owned(),
+						FlagValue::String(after_name[eq.len_utf8()..].to_owned()),
+					),
+					None => MiddleArg::LongFlag(name.to_owned(), FlagValue::None),
+				};
+				c.error_info_list.push((
+					flag_arg.clone(),
+					ParseError::AmbiguousLong(candidates.clone()),
+					ParseError::AmbiguousLong(candidates),
+				));
+				c.push_back_to_parsing_args(flag_arg);
+				return self.parse_next_if_flag(local_flags, current_common_flags, c);
+			}
+		}
+		match self.find_eq(&long_flag) {
 			Some(index) => {
 				let after_eq = long_flag.split_off(index + 1);
 				long_flag.pop();
-				match local_flags.find_long_flag(&long_flag) {
+				match self.search_long_flag(local_flags, &long_flag) {
 					LongFound::Name(l_flag) => match l_flag.derive_flag_value_from_string(after_eq) {
 						FlagValue::Invalid(after_eq) => {
 							let flag_arg =
@@ -1009,7 +2178,7 @@ impl Parser {
 							c.local_flags_values.push((long_flag, val));
 						}
 					},
-					LongFound::Long(l_flag) => match l_flag.flag_type.get_value_from_string(after_eq) {
+					LongFound::Long(l_flag) => match l_flag.derive_flag_value_from_string(after_eq) {
 						FlagValue::Invalid(after_eq) => {
 							let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::Invalid(after_eq));
 							let l_flag_name = l_flag.get_name_clone();
@@ -1025,9 +2194,10 @@ impl Parser {
 							c.local_flags_values.push((l_flag, val));
 						}
 					},
-					_ => match (current_common_flags, &c.common_flags).find_long_flag(&long_flag) {
-						LongFound::Name(c_flag) => match c_flag.flag_type.get_value_from_string(after_eq)
-						{
+					_ => match self
+						.search_long_flag(&(current_common_flags, &c.common_flags), &long_flag)
+					{
+						LongFound::Name(c_flag) => match c_flag.derive_flag_value_from_string(after_eq) {
 							FlagValue::Invalid(after_eq) => {
 								let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::String(after_eq));
 								c.error_info_list.push((
@@ -1039,8 +2209,7 @@ impl Parser {
 							}
 							val => c.common_flags_values.push((long_flag, val)),
 						},
-						LongFound::Long(c_flag) => match c_flag.flag_type.get_value_from_string(after_eq)
-						{
+						LongFound::Long(c_flag) => match c_flag.derive_flag_value_from_string(after_eq) {
 							FlagValue::Invalid(after_eq) => {
 								let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::String(after_eq));
 								c.error_info_list.push((
@@ -1054,10 +2223,12 @@ impl Parser {
 						},
 						_ => {
 							let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::String(after_eq));
-							c.error_info_list.push((
+							c.error_info_list.push(self.no_exist_error(
 								flag_arg.clone(),
 								ParseError::NoExistLong,
-								ParseError::NoExistLong,
+								local_flags,
+								current_common_flags,
+								&c,
 							));
 							c.push_back_to_parsing_args(flag_arg)
 						}
@@ -1065,7 +2236,7 @@ impl Parser {
 				}
 				self.parse_next_if_flag(local_flags, current_common_flags, c)
 			}
-			None => match local_flags.find_long_flag(&long_flag) {
+			None => match self.search_long_flag(local_flags, &long_flag) {
 				LongFound::Name(l_flag) => {
 					arg_match!(self, c.args.pop_front(),long_flag=>next_long_flag{
 						c.local_flags_values.push((long_flag, FlagValue::None));
@@ -1086,7 +2257,7 @@ impl Parser {
 						)
 					},
 					normal_arg=>next_arg{
-						match l_flag.derive_flag_value_from_string(next_arg) {
+						match l_flag.derive_flag_value_from_next_arg(next_arg) {
 							FlagValue::Invalid(next_arg) => {
 								c.local_flags_values.push((long_flag, FlagValue::None));
 								(Some(next_arg), c)
@@ -1125,14 +2296,14 @@ impl Parser {
 						)
 					}
 					normal_arg=>next_arg{
-						match l_flag.derive_flag_value_from_string(next_arg) {
+						match l_flag.derive_flag_value_from_next_arg(next_arg) {
 							FlagValue::Invalid(next_arg) => {
 								c.local_flags_values
 								.push((l_flag.get_name_clone(), FlagValue::None));
 								(Some(next_arg), c)
 							}
 							val => {
-								c.local_flags_values.push((long_flag, val));
+								c.local_flags_values.push((l_flag.get_name_clone(), val));
 								self.parse_next_if_flag(local_flags, current_common_flags, c)
 							}
 						}
@@ -1143,96 +2314,110 @@ impl Parser {
 						(None, c)
 					}
 				),
-				_ => match (current_common_flags, &c.common_flags).find_long_flag(&long_flag) {
-					LongFound::Name(c_flag) => {
-						arg_match!(self,c.args.pop_front(), long_flag=>next_long_flag{
-								c.common_flags_values.push((long_flag, FlagValue::None));
-								self.parse_flags_start_with_long_flag(
-									next_long_flag,
-									local_flags,
-									current_common_flags,
-									c,
-								)
-							},
-							short_flag=>next_short_flag{
-								c.common_flags_values.push((long_flag, FlagValue::None));
-								self.parse_flags_start_with_short_flag(
-									next_short_flag,
-									local_flags,
-									current_common_flags,
-									c,
-								)
-							}
-							normal_arg=>next_arg{
-								match c_flag.flag_type.get_value_from_string(next_arg) {
-								FlagValue::Invalid(next_arg) => {
+				_ => {
+					match self.search_long_flag(&(current_common_flags, &c.common_flags), &long_flag) {
+						LongFound::Name(c_flag) => {
+							arg_match!(self,c.args.pop_front(), long_flag=>next_long_flag{
 									c.common_flags_values.push((long_flag, FlagValue::None));
-									(Some(next_arg), c)
-								}
-								val => {
-									c.common_flags_values.push((long_flag, val));
-									self.parse_next_if_flag(local_flags, current_common_flags, c)
-								}
-							}
-							}
-							none=>next_none{
-								c.common_flags_values.push((long_flag, FlagValue::None));
-								(next_none, c)
-							}
-						)
-					}
-					LongFound::Long(c_flag) => {
-						arg_match!(self,c.args.pop_front(),
-							long_flag=>next_long_flag{
-								c.common_flags_values
-										.push((c_flag.get_name_clone(), FlagValue::None));
 									self.parse_flags_start_with_long_flag(
 										next_long_flag,
 										local_flags,
 										current_common_flags,
 										c,
 									)
-							},
-							short_flag=>next_short_flag{
-								c.common_flags_values
-										.push((c_flag.get_name_clone(), FlagValue::None));
+								},
+								short_flag=>next_short_flag{
+									c.common_flags_values.push((long_flag, FlagValue::None));
 									self.parse_flags_start_with_short_flag(
 										next_short_flag,
 										local_flags,
 										current_common_flags,
 										c,
 									)
-							},
-							normal_arg=>next_arg{
-								match c_flag.flag_type.get_value_from_string(next_arg) {
+								}
+								normal_arg=>next_arg{
+									match c_flag.derive_flag_value_from_next_arg(next_arg) {
 									FlagValue::Invalid(next_arg) => {
-										c.common_flags_values
-											.push((c_flag.get_name_clone(), FlagValue::None));
+										c.common_flags_values.push((long_flag, FlagValue::None));
 										(Some(next_arg), c)
 									}
 									val => {
-										c.common_flags_values.push((c_flag.get_name_clone(), val));
+										c.common_flags_values.push((long_flag, val));
 										self.parse_next_if_flag(local_flags, current_common_flags, c)
 									}
 								}
-							},
-						none=>next_none{
-							c.common_flags_values
-										.push((c_flag.get_name_clone(), FlagValue::None));
+								}
+								none=>next_none{
+									c.common_flags_values.push((long_flag, FlagValue::None));
 									(next_none, c)
-						})
-					}
-					_ => {
-						let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::None);
-						c.error_info_list.push((
-							flag_arg.clone(),
-							ParseError::NoExistLong,
-							ParseError::NoExistLong,
-						));
-						c.push_back_to_parsing_args(flag_arg);
-						self.parse_next_if_flag(local_flags, current_common_flags, c)
+								}
+							)
+						}
+						LongFound::Long(c_flag) => {
+							arg_match!(self,c.args.pop_front(),
+								long_flag=>next_long_flag{
+									c.common_flags_values
+											.push((c_flag.get_name_clone(), FlagValue::None));
+										self.parse_flags_start_with_long_flag(
+											next_long_flag,
+											local_flags,
+											current_common_flags,
+											c,
+										)
+								},
+								short_flag=>next_short_flag{
+									c.common_flags_values
+											.push((c_flag.get_name_clone(), FlagValue::None));
+										self.parse_flags_start_with_short_flag(
+											next_short_flag,
+											local_flags,
+											current_common_flags,
+											c,
+										)
+								},
+								normal_arg=>next_arg{
+									match c_flag.derive_flag_value_from_next_arg(next_arg) {
+										FlagValue::Invalid(next_arg) => {
+											c.common_flags_values
+												.push((c_flag.get_name_clone(), FlagValue::None));
+											(Some(next_arg), c)
+										}
+										val => {
+											c.common_flags_values.push((c_flag.get_name_clone(), val));
+											self.parse_next_if_flag(local_flags, current_common_flags, c)
+										}
+									}
+								},
+							none=>next_none{
+								c.common_flags_values
+											.push((c_flag.get_name_clone(), FlagValue::None));
+										(next_none, c)
+							})
+						}
+						_ => {
+							if let Some(l_flag) = self.search_negated_flag(local_flags, &long_flag) {
+								c.local_flags_values
+									.push((l_flag.get_name_clone(), FlagValue::Bool(false)));
+							} else if let Some(c_flag) = self
+								.search_negated_flag(&(current_common_flags, &c.common_flags), &long_flag)
+							{
+								c.common_flags_values
+									.push((c_flag.get_name_clone(), FlagValue::Bool(false)));
+							} else {
+								let flag_arg = MiddleArg::LongFlag(long_flag, FlagValue::None);
+								c.error_info_list.push(self.no_exist_error(
+									flag_arg.clone(),
+									ParseError::NoExistLong,
+									local_flags,
+									current_common_flags,
+									&c,
+								));
+								c.push_back_to_parsing_args(flag_arg);
+							}
+							self.parse_next_if_flag(local_flags, current_common_flags, c)
+						}
 					}
-				},
+				}
 			},
 		}
 	}
@@ -1245,7 +2430,24 @@ impl Parser {
 		current_common_flags: &S,
 		mut c: Context,
 	) -> (Option<String>, Context) {
-		match short_flag.find(self.eq) {
+		let short_name = self.get_short_flag_name(short_flag.clone());
+		if let Some((l_flag, val)) = self.search_numeric_shorthand(local_flags, &short_name) {
+			c.local_flags_values.push((l_flag.get_name_clone(), val));
+			return self.parse_next_if_flag(local_flags, current_common_flags, c);
+		}
+		if let Some((c_flag, val)) =
+			self.search_numeric_shorthand(&(current_common_flags, &c.common_flags), &short_name)
+		{
+			c.common_flags_values.push((c_flag.get_name_clone(), val));
+			return self.parse_next_if_flag(local_flags, current_common_flags, c);
+		}
+		if let Some((flags, value)) =
+			self.split_attached_short_value(&short_name, local_flags, current_common_flags, &c)
+		{
+			// 付随する値をeqで区切られた値として扱う
+			short_flag = format!("{}{}{}{}", self.flag_pattern, flags, self.eq, value);
+		}
+		match self.find_eq(&short_flag) {
 			Some(index) => {
 				let after_eq = short_flag.split_off(index + 1);
 				short_flag.pop();
@@ -1261,12 +2463,14 @@ impl Parser {
 					Some(before_eq) => {
 						let mut i = 0;
 						for s in short_flag.chars() {
-							match local_flags.find_short_flag(&s) {
+							match self.search_short_flag(local_flags, &s) {
 								Some(l_flag) => {
 									let record = (l_flag.get_name_clone(), FlagValue::None);
 									c.local_flags_values.push(record);
 								}
-								_ => match (current_common_flags, &c.common_flags).find_short_flag(&s) {
+								_ => match self
+									.search_short_flag(&(current_common_flags, &c.common_flags), &s)
+								{
 									Some(c_flag) => {
 										let record = (c_flag.get_name_clone(), FlagValue::None);
 										c.common_flags_values.push(record);
@@ -1279,10 +2483,12 @@ impl Parser {
 										let mut short_flag = short_flag.clone();
 										short_flag.push(before_eq);
 
-										c.error_info_list.push((
+										c.error_info_list.push(self.no_exist_error(
 											MiddleArg::ShortFlag(short_flag, FlagValue::None),
 											ParseError::NoExistShort(i),
-											ParseError::NoExistShort(i),
+											local_flags,
+											current_common_flags,
+											&c,
 										));
 									}
 								},
@@ -1290,7 +2496,7 @@ impl Parser {
 							i += 1;
 						}
 						//最後のフラグと値を処理
-						match local_flags.find_short_flag(&before_eq) {
+						match self.search_short_flag(local_flags, &before_eq) {
 							Some(l_flag) => {
 								match l_flag.derive_flag_value_from_string(after_eq) {
 									FlagValue::Invalid(after_eq) => {
@@ -1319,7 +2525,9 @@ impl Parser {
 							}
 							_ => {
 								//ローカルにヒットしなかった場合
-								match c.common_flags.find_short_flag(&before_eq) {
+								match self
+									.search_short_flag(&(current_common_flags, &c.common_flags), &before_eq)
+								{
 									Some(c_flag) => match c_flag.derive_flag_value_from_string(after_eq) {
 										FlagValue::Invalid(after_eq) => {
 											c.error_info_list.push((
@@ -1339,12 +2547,12 @@ impl Parser {
 											));
 										}
 										val => {
-											c.local_flags_values.push((c_flag.get_name_clone(), val));
+											c.common_flags_values.push((c_flag.get_name_clone(), val));
 										}
 									},
 									_ => {
 										let f_val = FlagValue::String(after_eq);
-										c.error_info_list.push((
+										c.error_info_list.push(self.no_exist_error(
 											MiddleArg::ShortFlag(
 												{
 													short_flag.push(before_eq);
@@ -1353,7 +2561,9 @@ impl Parser {
 												f_val.clone(),
 											),
 											ParseError::NoExistShort(i),
-											ParseError::NoExistShort(i),
+											local_flags,
+											current_common_flags,
+											&c,
 										));
 										c.push_back_to_parsing_args(MiddleArg::ShortFlag(
 											before_eq.to_string(),
@@ -1374,12 +2584,14 @@ impl Parser {
 				match last {
 					Some(last) => {
 						for s in short_flag.chars() {
-							match local_flags.find_short_flag(&s) {
+							match self.search_short_flag(local_flags, &s) {
 								Some(l_flag) => {
 									let record = (l_flag.get_name_clone(), FlagValue::None);
 									c.local_flags_values.push(record);
 								}
-								_ => match (current_common_flags, &c.common_flags).find_short_flag(&s) {
+								_ => match self
+									.search_short_flag(&(current_common_flags, &c.common_flags), &s)
+								{
 									Some(c_flag) => {
 										let record = (c_flag.get_name_clone(), FlagValue::None);
 										c.common_flags_values.push(record);
@@ -1389,10 +2601,12 @@ impl Parser {
 											s.to_string(),
 											FlagValue::None,
 										));
-										c.error_info_list.push((
+										c.error_info_list.push(self.no_exist_error(
 											MiddleArg::ShortFlag(short_flag.clone(), FlagValue::None),
 											ParseError::NoExistShort(i),
-											ParseError::NoExistShort(i),
+											local_flags,
+											current_common_flags,
+											&c,
 										));
 									}
 								},
@@ -1400,7 +2614,7 @@ impl Parser {
 							i += 1;
 						}
 						//最後の１フラグを処理
-						match local_flags.find_short_flag(&last) {
+						match self.search_short_flag(local_flags, &last) {
 							Some(l_flag) => {
 								arg_match!(self,c.args.pop_front(),
 										long_flag=>next_long_flag{
@@ -1424,7 +2638,7 @@ impl Parser {
 												)
 									},
 									normal_arg=>next_arg{
-										match l_flag.derive_flag_value_from_string(next_arg) {
+										match l_flag.derive_flag_value_from_next_arg(next_arg) {
 												FlagValue::Invalid(next_arg) => {
 													//
 													c.local_flags_values
@@ -1443,7 +2657,9 @@ impl Parser {
 												(next_none, c)
 								})
 							}
-							_ => match (current_common_flags, &c.common_flags).find_short_flag(&last) {
+							_ => match self
+								.search_short_flag(&(current_common_flags, &c.common_flags), &last)
+							{
 								Some(c_flag) => arg_match!(self, c.args.pop_front(),
 								long_flag=>next_long_flag {
 									c.common_flags_values
@@ -1466,7 +2682,7 @@ impl Parser {
 									)
 								},
 								normal_arg=>next_arg{
-									match c_flag.derive_flag_value_from_string(next_arg) {
+									match c_flag.derive_flag_value_from_next_arg(next_arg) {
 										FlagValue::Invalid(next_arg) => {
 											c.common_flags_values
 											.push((c_flag.get_name_clone(), FlagValue::None));
@@ -1493,10 +2709,12 @@ impl Parser {
 										FlagValue::None,
 									);
 									println!("{:?}", flag_arg);
-									c.error_info_list.push((
+									c.error_info_list.push(self.no_exist_error(
 										flag_arg.clone(),
 										ParseError::NoExistShort(i),
-										ParseError::NoExistShort(i),
+										local_flags,
+										current_common_flags,
+										&c,
 									));
 									c.push_back_to_parsing_args(flag_arg);
 									self.parse_next_if_flag(local_flags, current_common_flags, c)
@@ -1632,6 +2850,111 @@ impl MiddleArg {
 	}
 }
 
+/// Restores path value (or path values in list) taken from non-UTF-8 arg to original, and keeps original of string value (or string values in list) for its slot (`slot` takes index of element).
+/// If `val` requires UTF-8 (or cannot be restored) and includes part taken from non-UTF-8 arg, returns the part.
+fn restore_non_utf8_value(
+	val: &mut FlagValue,
+	non_utf8_args: &mut NonUtf8Args,
+	slot: &dyn Fn(usize) -> Slot,
+) -> Option<String> {
+	match val {
+		FlagValue::List(vals) => vals
+			.iter_mut()
+			.enumerate()
+			.find_map(|(j, val)| restore_non_utf8_value(val, non_utf8_args, &|_| slot(j))),
+		FlagValue::Path(path) => {
+			let lossy = path
+				.to_str()
+				.filter(|path| non_utf8_args.is_marked(path))?
+				.to_owned();
+			match non_utf8_args.restore(&lossy) {
+				Some(original) => {
+					*path = PathBuf::from(original);
+					None
+				}
+				None => Some(lossy),
+			}
+		}
+		FlagValue::String(val) => {
+			if non_utf8_args.is_marked(val) && !non_utf8_args.keep(slot(0), val) {
+				Some(val.clone())
+			} else {
+				None
+			}
+		}
+		FlagValue::Char(c) => Some(c.to_string()).filter(|c| non_utf8_args.is_marked(c)),
+		FlagValue::Map(entries) => entries
+			.iter()
+			.flat_map(|(key, value)| [key, value])
+			.find(|val| non_utf8_args.is_marked(val))
+			.cloned(),
+		_ => None,
+	}
+}
+
+/// Replaces markers of non-UTF-8 args in `val` by `U+FFFD`.
+fn unmark_value(val: &mut FlagValue, non_utf8_args: &NonUtf8Args) {
+	match val {
+		FlagValue::String(val) | FlagValue::Invalid(val) => non_utf8_args.unmark(val),
+		FlagValue::Path(path) => {
+			if let Some(mut lossy) = path.to_str().map(String::from) {
+				non_utf8_args.unmark(&mut lossy);
+				*path = PathBuf::from(lossy);
+			}
+		}
+		FlagValue::Char(c) => *c = non_utf8_args.unmark_char(*c),
+		FlagValue::List(vals) => {
+			for val in vals {
+				unmark_value(val, non_utf8_args);
+			}
+		}
+		FlagValue::Map(entries) => {
+			for (key, value) in entries {
+				non_utf8_args.unmark(key);
+				non_utf8_args.unmark(value);
+			}
+		}
+		_ => {}
+	}
+}
+
+/// Replaces markers of non-UTF-8 args in `arg` by `U+FFFD`.
+fn unmark_middle_arg(arg: &mut MiddleArg, non_utf8_args: &NonUtf8Args) {
+	match arg {
+		MiddleArg::Normal(val) => non_utf8_args.unmark(val),
+		MiddleArg::LongFlag(name, val) | MiddleArg::ShortFlag(name, val) => {
+			non_utf8_args.unmark(name);
+			unmark_value(val, non_utf8_args);
+		}
+	}
+}
+
+/// Replaces markers of non-UTF-8 args in `err` by `U+FFFD`.
+fn unmark_error(err: &mut ParseError, non_utf8_args: &NonUtf8Args) {
+	match err {
+		ParseError::InvalidShort(_, val)
+		| ParseError::InvalidElement(_, val)
+		| ParseError::ValidationFailed(val)
+		| ParseError::DuplicateKey(val) => non_utf8_args.unmark(val),
+		_ => {}
+	}
+}
+
+/// Returns Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut prev: Vec<usize> = (0..=b.len()).collect();
+	for (i, ac) in a.chars().enumerate() {
+		let mut current = vec![i + 1; b.len() + 1];
+		for (j, bc) in b.iter().enumerate() {
+			let substitution = prev[j] + usize::from(ac != *bc);
+			current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+		}
+		prev = current;
+	}
+	prev[b.len()]
+}
+
 /// Type of `ParseError`'s index
 type Index = usize;
 /// `ParseError` shows error in parsing
@@ -1647,6 +2970,40 @@ pub enum ParseError {
 	InvalidLong(String),
 	/// Shows not exist flag.
 	NotExist,
+	/// Shows that the flag does not exist but similar flags exist. Inner is the similar flags' names.
+	SimilarExist(Vec<String>),
+	/// Shows that abbreviated long flag matches several flags. Inner is the candidates' names.
+	AmbiguousLong(Vec<String>),
+	/// Shows that abbreviated sub command name matches several sub commands. Inner is the candidates' names.
+	AmbiguousSub(Vec<String>),
+	/// Shows that required positional arg is not inputted. Inner is the positional arg's name.
+	MissingPositional(String),
+	/// Shows that value of positional arg is invalid for its type. Inner is the positional arg's name.
+	InvalidPositional(String),
+	/// Shows that arg is not assigned to any declared positional arg.
+	UnexpectedPositional,
+	/// Shows that required flag is not inputted.
+	MissingRequiredFlag,
+	/// Shows that the flag is inputted with a flag which conflicts with it. Inner is the conflicting flag's name.
+	ConflictsWith(String),
+	/// Shows that the flag is inputted but a flag required by it is not inputted. Inner is the required flag's name.
+	RequiresFlag(String),
+	/// Shows that constraint of flag group is violated. Inner is the group's constraint and flags' names.
+	GroupViolation(GroupKind, Vec<String>),
+	/// Shows that value of environment variable is invalid for the flag. Inner is the environment variable's name.
+	InvalidEnv(String),
+	/// Shows that value in config is invalid for the flag. Inner is the config section's path (empty for top-level).
+	InvalidConfig(String),
+	/// Shows that config file could not be loaded. Inner is the reason.
+	ConfigLoadFailed(String),
+	/// Shows that the flag's value is rejected by its range, length limit or validator. Inner is the message.
+	ValidationFailed(String),
+	/// Shows that key of map flag is specified more than once. Inner is the key.
+	DuplicateKey(String),
+	/// Shows that an element of delimited list value is invalid. Inner is the element's index (0-origin) and the element. The flag is shown by the next `ParseError` (`InvalidLong` or `InvalidShort`).
+	InvalidElement(usize, String),
+	/// Shows that value converted from non-UTF-8 arg is given to flag or positional arg whose type requires UTF-8. The flag or positional arg is shown by the next `ParseError` (`InvalidLong`, `InvalidShort` or `InvalidPositional`).
+	NonUtf8Value,
 	/// Shows not parsed.
 	NotParsed,
 	/// Shows empty
@@ -1661,6 +3018,7 @@ pub type ErrorInfo = (MiddleArg, ParseError, ParseError);
 /// Presets for output Error info
 pub mod preset {
 	use super::{ErrorInfo, MiddleArg, ParseError};
+	use crate::{FlagGroup, flag::GroupKind};
 
 	/// Generates error description.
 	pub fn gen_error_description(err_info: &ErrorInfo) -> String {
@@ -1682,30 +3040,31 @@ pub mod preset {
 		description.push_str(".\n");
 		match err_info {
 			(flag_arg, ParseError::NoExistShort(i), ParseError::NoExistShort(_)) => {
-				let name = flag_arg.name();
 				description = format!(
 					"{}The short flag {} is an unknown short flag.",
 					description,
-					match name.len() {
-						1 => {
-							let mut s = String::from("-");
-							s.push(name.chars().nth(*i).unwrap());
-							s
-						}
-						_ => {
-							let mut s = String::from("\"");
-							s.push(name.chars().nth(*i).unwrap());
-							s.push('"');
-							s.push_str(" in ");
-							s.push_str(name);
-							s
-						}
-					},
+					unknown_short_flag_str(flag_arg.name(), *i),
+				);
+			}
+			(flag_arg, ParseError::NoExistShort(i), ParseError::SimilarExist(suggestions)) => {
+				description = format!(
+					"{}The short flag {} is an unknown short flag. {}",
+					description,
+					unknown_short_flag_str(flag_arg.name(), *i),
+					did_you_mean(suggestions)
 				);
 			}
 			(flag_arg, ParseError::NoExistLong, ParseError::NoExistLong) => {
 				description = format!("The flag --{} is an unknown flag.", flag_arg.name());
 			}
+			(flag_arg, ParseError::NoExistLong, ParseError::SimilarExist(suggestions)) => {
+				description = format!(
+					"{}The flag --{} is an unknown flag. {}",
+					description,
+					flag_arg.name(),
+					did_you_mean(suggestions)
+				);
+			}
 			(flag_arg, ParseError::NoExistShort(_), ParseError::InvalidShort(i, c_flag)) => {
 				let (name, val) = flag_arg.inner_if_string_val().unwrap();
 				description = format!(
@@ -1728,6 +3087,141 @@ pub mod preset {
 					flag_arg.get_flag_type_str()
 				);
 			}
+			(flag_arg, ParseError::AmbiguousLong(candidates), _) => {
+				description = format!(
+					"{}The flag --{} is ambiguous. It matches {}.",
+					description,
+					flag_arg.name(),
+					candidates
+						.iter()
+						.map(|c| format!("--{c}"))
+						.collect::<Vec<String>>()
+						.join(", ")
+				);
+			}
+			(arg, ParseError::AmbiguousSub(candidates), _) => {
+				description = format!(
+					"{}The sub command {} is ambiguous. It matches {}.",
+					description,
+					arg.name(),
+					candidates.join(", ")
+				);
+			}
+			(flag_arg, ParseError::MissingRequiredFlag, _) => {
+				description = format!(
+					"{}The required flag --{} is missing.",
+					description,
+					flag_arg.name()
+				);
+			}
+			(flag_arg, ParseError::ConflictsWith(other), _) => {
+				description = format!(
+					"{}The flag --{} cannot be used with --{}.",
+					description,
+					flag_arg.name(),
+					other
+				);
+			}
+			(flag_arg, ParseError::RequiresFlag(required), _) => {
+				description = format!(
+					"{}The flag --{} requires --{}.",
+					description,
+					flag_arg.name(),
+					required
+				);
+			}
+			(_, ParseError::GroupViolation(kind, flags), _) => {
+				let constraint = FlagGroup::new(*kind, flags.clone()).constraint_str();
+				description = format!(
+					"{}{}{} {} specified.",
+					description,
+					constraint[..1].to_uppercase(),
+					&constraint[1..],
+					if *kind == GroupKind::AtMostOne {
+						"can be"
+					} else {
+						"must be"
+					}
+				);
+			}
+			(flag_arg, ParseError::InvalidEnv(env), _) => {
+				let (name, val) = flag_arg.inner_if_string_val().unwrap();
+				description = format!(
+					"{description}The value {val} of environment variable {env} is invalid for the flag --{name}."
+				);
+			}
+			(flag_arg, ParseError::InvalidConfig(section), _) => {
+				let (name, val) = flag_arg.inner_if_string_val().unwrap();
+				let section = if section.is_empty() {
+					String::from("top-level")
+				} else {
+					format!("section [{section}]")
+				};
+				description = format!(
+					"{description}The value {val} in {section} of config is invalid for the flag --{name}."
+				);
+			}
+			(
+				flag_arg,
+				ParseError::InvalidElement(index, elem),
+				ParseError::InvalidLong(flag) | ParseError::InvalidShort(_, flag),
+			) => {
+				let (_, val) = flag_arg.inner_if_string_val().unwrap();
+				description = format!(
+					"{}The element {} at position {} of the value {} is invalid for the flag --{}.",
+					description,
+					elem,
+					index + 1,
+					val,
+					flag
+				);
+			}
+			(arg, ParseError::NonUtf8Value, ParseError::InvalidPositional(name)) => {
+				description = format!(
+					"{}The value {} of the arg <{}> is not valid UTF-8.",
+					description,
+					arg.name(),
+					name
+				);
+			}
+			(
+				flag_arg,
+				ParseError::NonUtf8Value,
+				ParseError::InvalidLong(flag) | ParseError::InvalidShort(_, flag),
+			) => {
+				let (_, val) = flag_arg.inner_if_string_val().unwrap();
+				description =
+					format!("{description}The value {val} of the flag --{flag} is not valid UTF-8.");
+			}
+			(flag_arg, ParseError::DuplicateKey(key), _) => {
+				let (name, val) = flag_arg.inner_if_string_val().unwrap();
+				description = format!(
+					"{description}The key {key} of the flag --{name} is specified more than once ({val})."
+				);
+			}
+			(flag_arg, ParseError::ValidationFailed(message), _) => {
+				let (name, val) = flag_arg.inner_if_string_val().unwrap();
+				description =
+					format!("{description}The value {val} of the flag --{name} is invalid: {message}.");
+			}
+			(flag_arg, ParseError::ConfigLoadFailed(reason), _) => {
+				let (_, path) = flag_arg.inner_if_string_val().unwrap();
+				description = format!("{description}Failed to load config file {path}: {reason}.");
+			}
+			(_, ParseError::MissingPositional(name), _) => {
+				description = format!("{description}The required arg <{name}> is missing.");
+			}
+			(arg, ParseError::InvalidPositional(name), _) => {
+				description = format!(
+					"{}The value {} is invalid for the arg <{}>.",
+					description,
+					arg.name(),
+					name
+				);
+			}
+			(arg, ParseError::UnexpectedPositional, _) => {
+				description = format!("{}The arg {} is unexpected.", description, arg.name());
+			}
 			(flag_arg, ParseError::InvalidShort(i, l_flag), _) => {
 				let (name, val) = flag_arg.inner_if_string_val().unwrap();
 				description = format!(
@@ -1744,6 +3238,35 @@ pub mod preset {
 		description
 	}
 
+	fn unknown_short_flag_str(name: &str, i: usize) -> String {
+		match name.len() {
+			1 => {
+				let mut s = String::from("-");
+				s.push(name.chars().nth(i).unwrap());
+				s
+			}
+			_ => {
+				let mut s = String::from("\"");
+				s.push(name.chars().nth(i).unwrap());
+				s.push('"');
+				s.push_str(" in ");
+				s.push_str(name);
+				s
+			}
+		}
+	}
+
+	fn did_you_mean(suggestions: &[String]) -> String {
+		format!(
+			"Did you mean {}?",
+			suggestions
+				.iter()
+				.map(|s| format!("--{s}"))
+				.collect::<Vec<String>>()
+				.join(" or ")
+		)
+	}
+
 	#[cfg(test)]
 	mod tests {
 		use crate::{Command, done, parser::preset::gen_error_description};
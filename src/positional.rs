@@ -0,0 +1,81 @@
+use crate::{FlagType, FlagValue};
+
+/// Struct for declared positional (non-flag) arg's information
+/// 位置引数（フラグでない引数）情報格納のための構造体です。
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Positional {
+	/// This positional arg's name
+	pub name: String,
+	/// This positional arg's description
+	pub description: String,
+	/// This positional arg's type. If this is variadic, this is type of each value.
+	pub flag_type: FlagType,
+	/// If this is true, parse error occurs when this positional arg is not inputted.
+	pub required: bool,
+	/// If this is true, this positional arg takes all rest args (except args for positional args declared after this) as `FlagValue::List`.
+	pub variadic: bool,
+}
+
+impl Positional {
+	/// Creates a new instance of required `Positional`.
+	pub fn new<T: Into<String>>(name: T, flag_type: FlagType) -> Self {
+		Positional {
+			name: name.into(),
+			description: String::default(),
+			flag_type,
+			required: true,
+			variadic: false,
+		}
+	}
+
+	/// Creates a new instance of required `Positional` with string type.
+	pub fn new_string<T: Into<String>>(name: T) -> Self {
+		Positional::new(name, FlagType::String)
+	}
+
+	/// Creates a new instance of required `Positional` with int type.
+	pub fn new_int<T: Into<String>>(name: T) -> Self {
+		Positional::new(name, FlagType::Int)
+	}
+
+	/// Creates a new instance of required `Positional` with float type.
+	pub fn new_float<T: Into<String>>(name: T) -> Self {
+		Positional::new(name, FlagType::Float)
+	}
+
+	/// Set description
+	pub fn description<T: Into<String>>(mut self, description: T) -> Self {
+		self.description = description.into();
+		self
+	}
+
+	/// Sets whether this positional arg is required
+	pub fn required(mut self, required: bool) -> Self {
+		self.required = required;
+		self
+	}
+
+	/// Sets whether this positional arg takes all rest args
+	pub fn variadic(mut self, variadic: bool) -> Self {
+		self.variadic = variadic;
+		self
+	}
+
+	/// Get value from string as this positional arg's type
+	pub fn derive_value_from_string(&self, val: String) -> FlagValue {
+		self.flag_type.get_value_from_string(val)
+	}
+
+	/// Returns usage expression of this positional arg, such as `<name>`, `[name]` or `<name>...`.
+	pub fn usage_str(&self) -> String {
+		let mut usage = if self.required {
+			format!("<{}>", self.name)
+		} else {
+			format!("[{}]", self.name)
+		};
+		if self.variadic {
+			usage.push_str("...");
+		}
+		usage
+	}
+}
@@ -3343,6 +3343,19 @@ mod tests {
 			long_alias: Vector(Some(vec!["long".to_owned(), "long2".to_owned()])),
 			default_value: FlagValue::Bool(false),
 			flag_type: FlagType::Bool,
+			negatable: false,
+			numeric_shorthand: false,
+			required: false,
+			conflicts_with: Vector::default(),
+			requires: Vector::default(),
+			env: None,
+			min: None,
+			max: None,
+			min_len: None,
+			max_len: None,
+			validator: None,
+			value_delimiter: None,
+			default_missing_value: None,
 		};
 		let _flag_name = String::from("test_flag");
 		let _flag_name2 = _flag_name.clone();
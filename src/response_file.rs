@@ -0,0 +1,293 @@
+use std::{
+	error::Error,
+	fmt,
+	path::{Path, PathBuf},
+};
+
+/// `ResponseFileError` shows error in expanding response files (`@path` args)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFileError {
+	/// Shows that response file cannot be read. Inner is the path and the reason.
+	Unreadable(PathBuf, String),
+	/// Shows that response file includes itself directly or indirectly. Inner is the path.
+	Cycle(PathBuf),
+	/// Shows that quote in response file is not closed. Inner is the path.
+	UnterminatedQuote(PathBuf),
+}
+
+impl fmt::Display for ResponseFileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ResponseFileError::Unreadable(path, reason) => {
+				write!(
+					f,
+					"cannot read response file {}: {}",
+					path.display(),
+					reason
+				)
+			}
+			ResponseFileError::Cycle(path) => {
+				write!(f, "response file {} includes itself", path.display())
+			}
+			ResponseFileError::UnterminatedQuote(path) => {
+				write!(f, "unterminated quote in response file {}", path.display())
+			}
+		}
+	}
+}
+
+impl Error for ResponseFileError {}
+
+/// Expands `@path` args in `raw_args` into args read from the files. The first arg (exe path) is not expanded, and args after the first `--` (end of options) are not expanded either.
+/// Relative paths in a response file are resolved from the directory of the file.
+/// Response files can include other response files, but a file including itself (directly or indirectly) is an error.
+/// `@path`形式の引数をファイルの内容で展開する。先頭の引数（実行パス）と最初の`--`以降の引数は展開しない。
+pub fn expand_args(raw_args: Vec<String>) -> Result<Vec<String>, ResponseFileError> {
+	let mut raw_args = raw_args.into_iter();
+	let mut expansion = Expansion {
+		expanded: raw_args.next().into_iter().collect(),
+		stack: Vec::new(),
+		end_of_options: false,
+	};
+	for arg in raw_args {
+		expansion.expand_arg(arg, None)?;
+	}
+	Ok(expansion.expanded)
+}
+
+/// State of expanding response files
+struct Expansion {
+	/// Expanded args
+	expanded: Vec<String>,
+	/// Canonical paths of response files being expanded
+	stack: Vec<PathBuf>,
+	/// True if `--` has appeared
+	end_of_options: bool,
+}
+
+impl Expansion {
+	/// Expands `arg` if it is `@path`, and pushes results to `expanded`. Relative path is resolved from `base` (current directory if `base` is None).
+	fn expand_arg(&mut self, arg: String, base: Option<&Path>) -> Result<(), ResponseFileError> {
+		let path = match arg.strip_prefix('@') {
+			Some(path) if !path.is_empty() && !self.end_of_options => match base {
+				Some(base) => base.join(path),
+				None => PathBuf::from(path),
+			},
+			_ => {
+				self.end_of_options |= arg == "--";
+				self.expanded.push(arg);
+				return Ok(());
+			}
+		};
+		let unreadable =
+			|err: std::io::Error| ResponseFileError::Unreadable(path.clone(), err.to_string());
+		let canonical = path.canonicalize().map_err(unreadable)?;
+		if self.stack.contains(&canonical) {
+			return Err(ResponseFileError::Cycle(path));
+		}
+		let text = std::fs::read_to_string(&canonical).map_err(unreadable)?;
+		let args = split(&text).ok_or_else(|| ResponseFileError::UnterminatedQuote(path.clone()))?;
+		let dir = canonical.parent().map(Path::to_path_buf);
+		self.stack.push(canonical);
+		for arg in args {
+			self.expand_arg(arg, dir.as_deref())?;
+		}
+		self.stack.pop();
+		Ok(())
+	}
+}
+
+/// Splits `text` into args like shell. Args are separated by whitespaces, and can be quoted by `'` or `"`.
+/// In double quotes and outside quotes, `\` escapes the next char. `#` at the beginning of arg starts comment until the end of line.
+/// If quote is not closed, returns None.
+pub fn split(text: &str) -> Option<Vec<String>> {
+	let mut args = Vec::new();
+	let mut chars = text.chars();
+	let mut current: Option<String> = None;
+	while let Some(c) = chars.next() {
+		match c {
+			c if c.is_whitespace() => {
+				args.extend(current.take());
+			}
+			'#' if current.is_none() => {
+				for c in chars.by_ref() {
+					if c == '\n' {
+						break;
+					}
+				}
+			}
+			'\'' => {
+				let arg = current.get_or_insert_with(String::new);
+				loop {
+					match chars.next()? {
+						'\'' => break,
+						c => arg.push(c),
+					}
+				}
+			}
+			'"' => {
+				let arg = current.get_or_insert_with(String::new);
+				loop {
+					match chars.next()? {
+						'"' => break,
+						'\\' => arg.push(chars.next()?),
+						c => arg.push(c),
+					}
+				}
+			}
+			'\\' => {
+				let arg = current.get_or_insert_with(String::new);
+				match chars.next() {
+					// line continuation
+					Some('\n') => {}
+					Some(c) => arg.push(c),
+					None => arg.push('\\'),
+				}
+			}
+			c => current.get_or_insert_with(String::new).push(c),
+		}
+	}
+	args.extend(current);
+	Some(args)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ResponseFileError, expand_args, split};
+	use std::path::PathBuf;
+
+	#[test]
+	fn split_test() {
+		let text = r#"
+# comment line
+--name "hello world" 'single \ quoted'
+  esc\ aped "quote \" in" # trailing comment
+empty "" a#b
+"#;
+		assert_eq!(
+			split(text),
+			Some(
+				vec![
+					"--name",
+					"hello world",
+					"single \\ quoted",
+					"esc aped",
+					"quote \" in",
+					"empty",
+					"",
+					"a#b"
+				]
+				.into_iter()
+				.map(String::from)
+				.collect()
+			)
+		);
+		assert_eq!(split("\"unterminated"), None);
+		assert_eq!(split("'unterminated"), None);
+	}
+
+	/// Temporary directory removed when dropped
+	struct TempDir(PathBuf);
+
+	impl TempDir {
+		fn new(name: &str) -> Self {
+			let dir = std::env::temp_dir().join(format!(
+				"combu_response_file_{}_{}",
+				name,
+				std::process::id()
+			));
+			std::fs::create_dir_all(&dir).unwrap();
+			TempDir(dir)
+		}
+
+		fn file(&self, name: &str, content: &str) -> PathBuf {
+			let path = self.0.join(name);
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(&path, content).unwrap();
+			path
+		}
+	}
+
+	impl Drop for TempDir {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.0);
+		}
+	}
+
+	#[test]
+	fn expand_args_test() {
+		let dir = TempDir::new("expand");
+		let inner = dir.file("inner.txt", "--inner 'nested value'\n");
+		let outer = dir.file(
+			"outer.txt",
+			&format!("--outer @\"{}\" @\n", inner.display()),
+		);
+		let args = vec![
+			"@exe".to_owned(),
+			"first".to_owned(),
+			format!("@{}", outer.display()),
+			"last".to_owned(),
+		];
+		assert_eq!(
+			expand_args(args),
+			Ok(vec![
+				"@exe",
+				"first",
+				"--outer",
+				"--inner",
+				"nested value",
+				"@",
+				"last"
+			]
+			.into_iter()
+			.map(String::from)
+			.collect())
+		);
+
+		let cycle_a = dir.file("cycle_a.txt", "");
+		let cycle_b = dir.file("cycle_b.txt", &format!("@{}", cycle_a.display()));
+		std::fs::write(&cycle_a, format!("a @{}", cycle_b.display())).unwrap();
+		assert_eq!(
+			expand_args(vec!["exe".to_owned(), format!("@{}", cycle_a.display())]),
+			Err(ResponseFileError::Cycle(cycle_a.clone()))
+		);
+
+		let unterminated = dir.file("unterminated.txt", "'open");
+		assert_eq!(
+			expand_args(vec![
+				"exe".to_owned(),
+				format!("@{}", unterminated.display())
+			]),
+			Err(ResponseFileError::UnterminatedQuote(unterminated))
+		);
+
+		let missing = inner.with_file_name("missing.txt");
+		assert!(matches!(
+			expand_args(vec!["exe".to_owned(), format!("@{}", missing.display())]),
+			Err(ResponseFileError::Unreadable(path, _)) if path == missing
+		));
+	}
+
+	#[test]
+	fn expand_args_relative_and_end_of_options_test() {
+		let dir = TempDir::new("relative");
+		dir.file("sub/nested.txt", "--nested");
+		// 入れ子の相対パスは含んでいるファイルのディレクトリから解決する
+		let outer = dir.file("sub/outer.txt", "@nested.txt -- @nested.txt");
+		let cmd = |args: &[&str]| {
+			std::iter::once("exe".to_owned())
+				.chain(args.iter().map(|arg| (*arg).to_owned()))
+				.collect::<Vec<String>>()
+		};
+		let at_outer = format!("@{}", outer.display());
+		assert_eq!(
+			expand_args(cmd(&[&at_outer, &at_outer])),
+			Ok(cmd(&["--nested", "--", "@nested.txt", &at_outer]))
+		);
+		// `--`以降は展開しない
+		assert_eq!(
+			expand_args(cmd(&["--", &at_outer])),
+			Ok(cmd(&["--", &at_outer]))
+		);
+	}
+}
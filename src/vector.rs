@@ -304,7 +304,7 @@ pub mod flag {
 	pub enum LongFound<T> {
 		/// Shows hit inner long flag's name.
 		Name(T),
-		/// Shows hit inner long flag's long alias.
+		/// Shows hit inner long flag's long alias (or hit not by exact name, such as ignoring case).
 		Long(T),
 		/// Shows hit no long flag.
 		None,
@@ -318,6 +318,47 @@ pub mod flag {
 		fn find_short_flag(&self, short_alias: &char) -> Option<&Flag>;
 		/// Finds flag that has specidied name.
 		fn find(&self, name: &str) -> Option<&Flag>;
+		/// Finds long flag ignoring case. Exact match of name is prior, and other hits are returned as `LongFound::Long`.
+		/// Default implementation is same as `find_long_flag`.
+		fn find_long_flag_ignore_case(&self, name_or_alias: &str) -> LongFound<&Flag> {
+			self.find_long_flag(name_or_alias)
+		}
+		/// Finds short flag ignoring case. Exact match is prior.
+		/// Default implementation is same as `find_short_flag`.
+		fn find_short_flag_ignore_case(&self, short_alias: &char) -> Option<&Flag> {
+			self.find_short_flag(short_alias)
+		}
+		/// Finds flag that accepts numeric shorthand such as `-5`.
+		/// Default implementation searches `all_flags`.
+		fn find_numeric_shorthand(&self) -> Option<&Flag> {
+			self
+				.all_flags()
+				.into_iter()
+				.find(|flag| flag.numeric_shorthand)
+		}
+		/// Finds all flags whose name or long alias starts with `prefix`.
+		/// Default implementation searches `all_flags`.
+		fn find_long_flags_by_prefix(&self, prefix: &str) -> Vec<&Flag> {
+			self
+				.all_flags()
+				.into_iter()
+				.filter(|flag| flag.has_long_prefix(prefix))
+				.collect()
+		}
+		/// Finds all flags whose name or long alias starts with `prefix` ignoring case.
+		/// Default implementation searches `all_flags`.
+		fn find_long_flags_by_prefix_ignore_case(&self, prefix: &str) -> Vec<&Flag> {
+			self
+				.all_flags()
+				.into_iter()
+				.filter(|flag| flag.has_long_prefix_ignore_case(prefix))
+				.collect()
+		}
+		/// Returns all flags searched by this (used for suggestion, abbreviation and numeric shorthand).
+		/// Default implementation returns no flag.
+		fn all_flags(&self) -> Vec<&Flag> {
+			Vec::new()
+		}
 	}
 
 	impl FlagSearch for Vector<Flag> {
@@ -350,6 +391,40 @@ pub mod flag {
 				Vector(None) => None,
 			}
 		}
+
+		fn find_long_flag_ignore_case(&self, name_or_alias: &str) -> LongFound<&Flag> {
+			match self.find_long_flag(name_or_alias) {
+				LongFound::None => match &self {
+					Vector(None) => LongFound::None,
+					Vector(Some(flags)) => match flags.iter().rfind(|flag| {
+						flag.is_ignore_case(name_or_alias) || flag.is_long_ignore_case(name_or_alias)
+					}) {
+						None => LongFound::None,
+						Some(f) => LongFound::Long(f),
+					},
+				},
+				found => found,
+			}
+		}
+
+		fn find_short_flag_ignore_case(&self, short_alias: &char) -> Option<&Flag> {
+			match self.find_short_flag(short_alias) {
+				None => match &self {
+					Vector(Some(flags)) => flags
+						.iter()
+						.find(|flag| flag.is_short_ignore_case(short_alias)),
+					Vector(None) => None,
+				},
+				found => found,
+			}
+		}
+
+		fn all_flags(&self) -> Vec<&Flag> {
+			match &self {
+				Vector(Some(flags)) => flags.iter().collect(),
+				Vector(None) => Vec::new(),
+			}
+		}
 	}
 
 	impl<T: FlagSearch> FlagSearch for Vector<T> {
@@ -418,6 +493,49 @@ pub mod flag {
 				}
 			}
 		}
+
+		fn find_long_flag_ignore_case(&self, name_or_alias: &str) -> LongFound<&Flag> {
+			match &self {
+				Vector(None) => LongFound::None,
+				Vector(Some(flags_list)) => {
+					let mut iter = flags_list.iter();
+					loop {
+						let flags = iter.next_back();
+						if let Some(flags) = flags {
+							match flags.find_long_flag_ignore_case(name_or_alias) {
+								LongFound::None => {}
+								val => {
+									break val;
+								}
+							}
+						} else {
+							break LongFound::None;
+						}
+					}
+				}
+			}
+		}
+
+		fn find_short_flag_ignore_case(&self, short_alias: &char) -> Option<&Flag> {
+			match &self {
+				Vector(None) => None,
+				Vector(Some(flags_list)) => flags_list
+					.iter()
+					.rev()
+					.find_map(|flags| flags.find_short_flag_ignore_case(short_alias)),
+			}
+		}
+
+		fn all_flags(&self) -> Vec<&Flag> {
+			match &self {
+				Vector(None) => Vec::new(),
+				Vector(Some(flags_list)) => flags_list
+					.iter()
+					.rev()
+					.flat_map(FlagSearch::all_flags)
+					.collect(),
+			}
+		}
 	}
 
 	impl<T: FlagSearch, S: FlagSearch> FlagSearch for (&T, &S) {
@@ -441,6 +559,26 @@ pub mod flag {
 				val => val,
 			}
 		}
+
+		fn find_long_flag_ignore_case(&self, name_or_alias: &str) -> LongFound<&Flag> {
+			match self.0.find_long_flag_ignore_case(name_or_alias) {
+				LongFound::None => self.1.find_long_flag_ignore_case(name_or_alias),
+				val => val,
+			}
+		}
+
+		fn find_short_flag_ignore_case(&self, short_alias: &char) -> Option<&Flag> {
+			match self.0.find_short_flag_ignore_case(short_alias) {
+				None => self.1.find_short_flag_ignore_case(short_alias),
+				val => val,
+			}
+		}
+
+		fn all_flags(&self) -> Vec<&Flag> {
+			let mut flags = self.0.all_flags();
+			flags.append(&mut self.1.all_flags());
+			flags
+		}
 	}
 }
 
@@ -547,4 +685,45 @@ mod tests {
 		assert_eq!(main.len(), 3);
 		assert_eq!(main, Vector(option_vec));
 	}
+
+	#[test]
+	fn flag_search_default_test() {
+		use super::flag::{FlagSearch, LongFound};
+		use crate::Flag;
+
+		/// Flags implementing only required methods of `FlagSearch`
+		struct Flags(Vec<Flag>);
+
+		impl FlagSearch for Flags {
+			fn find_long_flag(&self, name_or_alias: &str) -> LongFound<&Flag> {
+				match self.find(name_or_alias) {
+					Some(flag) => LongFound::Name(flag),
+					None => LongFound::None,
+				}
+			}
+			fn find_short_flag(&self, short_alias: &char) -> Option<&Flag> {
+				self.0.iter().find(|flag| flag.is_short(short_alias))
+			}
+			fn find(&self, name: &str) -> Option<&Flag> {
+				self.0.iter().find(|flag| flag.is(name))
+			}
+			fn all_flags(&self) -> Vec<&Flag> {
+				self.0.iter().collect()
+			}
+		}
+
+		let flags = Flags(vec![
+			Flag::new_bool("verbose"),
+			Flag::new_bool("version"),
+			Flag::new_int("lines").numeric_shorthand(true),
+		]);
+		assert_eq!(
+			flags
+				.find_numeric_shorthand()
+				.map(|flag| flag.name.as_str()),
+			Some("lines")
+		);
+		assert_eq!(flags.find_long_flags_by_prefix("ver").len(), 2);
+		assert_eq!(flags.find_long_flags_by_prefix_ignore_case("VERB").len(), 1);
+	}
 }